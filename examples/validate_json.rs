@@ -0,0 +1,32 @@
+//! Validates a JSON document using only the core type, range and control
+//! validation, i.e. without the optional `tdate` and `uri` features:
+//!
+//! ```sh
+//! cargo run --example validate_json --no-default-features --features json
+//! ```
+
+use cddl::validate_json_from_str;
+
+fn main() {
+  let cddl = r#"
+    person = {
+      name: tstr .size (1..64),
+      age: 0..120,
+      ? tags: [* tstr],
+    }
+  "#;
+  let json = r#"{ "name": "Alice", "age": 42, "tags": ["admin"] }"#;
+
+  #[cfg(feature = "additional-controls")]
+  let result = validate_json_from_str(cddl, json, None);
+  #[cfg(not(feature = "additional-controls"))]
+  let result = validate_json_from_str(cddl, json);
+
+  match result {
+    Ok(()) => println!("valid"),
+    Err(e) => {
+      eprintln!("{}", e);
+      std::process::exit(1);
+    }
+  }
+}
@@ -144,7 +144,7 @@ impl<'a> fmt::Display for Comments<'a> {
 /// cddl = S 1*(rule S)
 /// ```
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Default, Debug, PartialEq, Clone)]
+#[derive(Default, Debug, Clone)]
 pub struct CDDL<'a> {
   /// Zero or more production rules
   #[cfg_attr(target_arch = "wasm32", serde(borrow))]
@@ -156,6 +156,50 @@ pub struct CDDL<'a> {
   pub comments: Option<Comments<'a>>,
 }
 
+impl<'a> PartialEq for CDDL<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    self.rules == other.rules
+  }
+}
+
+impl<'a> CDDL<'a> {
+  /// Returns an iterator over the type rules, including type choice
+  /// alternates
+  pub fn type_rules(&self) -> impl Iterator<Item = &TypeRule<'a>> {
+    self.rules.iter().filter_map(|r| match r {
+      Rule::Type { rule, .. } => Some(rule),
+      Rule::Group { .. } => None,
+    })
+  }
+
+  /// Returns an iterator over the group rules, including group choice
+  /// alternates
+  pub fn group_rules(&self) -> impl Iterator<Item = &GroupRule<'a>> {
+    self.rules.iter().filter_map(|r| match r {
+      Rule::Group { rule, .. } => Some(&**rule),
+      Rule::Type { .. } => None,
+    })
+  }
+
+  /// Returns the names of the rules in order of their first definition. Names
+  /// of rules extended by choice alternates are only returned once
+  pub fn rule_names(&self) -> Vec<&'a str> {
+    let mut names = Vec::new();
+    for r in self.rules.iter() {
+      let name = match r {
+        Rule::Type { rule, .. } => rule.name.ident,
+        Rule::Group { rule, .. } => rule.name.ident,
+      };
+
+      if !names.contains(&name) {
+        names.push(name);
+      }
+    }
+
+    names
+  }
+}
+
 impl<'a> fmt::Display for CDDL<'a> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     #[cfg(target_arch = "wasm32")]
@@ -208,6 +252,23 @@ impl<'a> fmt::Display for CDDL<'a> {
   }
 }
 
+/// Format a CDDL document in canonical form, with each rule on its own line and
+/// normalized spacing between tokens. Comments are retained
+pub fn to_canonical_string(cddl: &CDDL) -> String {
+  let mut output = String::new();
+
+  #[cfg(feature = "ast-comments")]
+  if let Some(comments) = &cddl.comments {
+    output.push_str(&comments.to_string());
+  }
+
+  for rule in cddl.rules.iter() {
+    let _ = writeln!(output, "{}", rule.to_string().trim_end());
+  }
+
+  output
+}
+
 /// Identifier for a type name, group name or bareword, with an optional socket
 ///
 /// ```abnf
@@ -230,7 +291,7 @@ pub struct Identifier<'a> {
 
 impl<'a> PartialEq for Identifier<'a> {
   fn eq(&self, other: &Self) -> bool {
-    self.to_string() == other.to_string()
+    self.ident == other.ident && self.socket == other.socket
   }
 }
 
@@ -300,7 +361,7 @@ impl<'a> From<Token<'a>> for Identifier<'a> {
 ///     / groupname [genericparm] S assigng S grpent
 /// ```
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Clone)]
 pub enum Rule<'a> {
   /// Type expression
   Type {
@@ -331,6 +392,16 @@ pub enum Rule<'a> {
   },
 }
 
+impl<'a> PartialEq for Rule<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Rule::Type { rule: l, .. }, Rule::Type { rule: r, .. }) => l == r,
+      (Rule::Group { rule: l, .. }, Rule::Group { rule: r, .. }) => l == r,
+      _ => false,
+    }
+  }
+}
+
 impl<'a> Rule<'a> {
   /// Return `Span` for `Rule`
   #[cfg(feature = "ast-span")]
@@ -462,7 +533,7 @@ impl<'a> Rule<'a> {
 /// typename [genericparm] S assignt S type
 /// ```
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Clone)]
 pub struct TypeRule<'a> {
   /// Type name identifier
   #[cfg_attr(target_arch = "wasm32", serde(borrow))]
@@ -484,6 +555,15 @@ pub struct TypeRule<'a> {
   pub comments_after_assignt: Option<Comments<'a>>,
 }
 
+impl<'a> PartialEq for TypeRule<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    self.name == other.name
+      && self.generic_params == other.generic_params
+      && self.is_type_choice_alternate == other.is_type_choice_alternate
+      && self.value == other.value
+  }
+}
+
 impl<'a> fmt::Display for TypeRule<'a> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     let mut tr_output = self.name.to_string();
@@ -520,7 +600,7 @@ impl<'a> fmt::Display for TypeRule<'a> {
 /// groupname [genericparm] S assigng S grpent
 /// ```
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Clone)]
 pub struct GroupRule<'a> {
   /// Group name identifier
   #[cfg_attr(target_arch = "wasm32", serde(borrow))]
@@ -542,6 +622,15 @@ pub struct GroupRule<'a> {
   pub comments_after_assigng: Option<Comments<'a>>,
 }
 
+impl<'a> PartialEq for GroupRule<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    self.name == other.name
+      && self.generic_params == other.generic_params
+      && self.is_group_choice_alternate == other.is_group_choice_alternate
+      && self.entry == other.entry
+  }
+}
+
 impl<'a> fmt::Display for GroupRule<'a> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     let mut gr_output = self.name.to_string();
@@ -578,7 +667,7 @@ impl<'a> fmt::Display for GroupRule<'a> {
 /// genericparm =  "<" S id S *("," S id S ) ">"
 /// ```
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct GenericParams<'a> {
   /// List of generic parameters
   pub params: Vec<GenericParam<'a>>,
@@ -589,7 +678,7 @@ pub struct GenericParams<'a> {
 
 /// Generic parameter
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, Clone)]
 pub struct GenericParam<'a> {
   /// Generic parameter
   pub param: Identifier<'a>,
@@ -604,6 +693,22 @@ pub struct GenericParam<'a> {
   pub comments_after_ident: Option<Comments<'a>>,
 }
 
+impl<'a> PartialEq for GenericParams<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    self.params == other.params
+  }
+}
+
+impl<'a> Eq for GenericParams<'a> {}
+
+impl<'a> PartialEq for GenericParam<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    self.param == other.param
+  }
+}
+
+impl<'a> Eq for GenericParam<'a> {}
+
 impl<'a> fmt::Display for GenericParams<'a> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     let mut gp = String::from("<");
@@ -637,7 +742,7 @@ impl<'a> fmt::Display for GenericParams<'a> {
 /// genericarg = "<" S type1 S *("," S type1 S )  ">"
 /// ```
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct GenericArgs<'a> {
   /// Generic arguments
   pub args: Vec<GenericArg<'a>>,
@@ -648,7 +753,7 @@ pub struct GenericArgs<'a> {
 
 /// Generic argument
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct GenericArg<'a> {
   /// Generic argument
   pub arg: Box<Type1<'a>>,
@@ -663,6 +768,18 @@ pub struct GenericArg<'a> {
   pub comments_after_type: Option<Comments<'a>>,
 }
 
+impl<'a> PartialEq for GenericArgs<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    self.args == other.args
+  }
+}
+
+impl<'a> PartialEq for GenericArg<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    self.arg == other.arg
+  }
+}
+
 impl<'a> fmt::Display for GenericArgs<'a> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     let mut ga = String::from("<");
@@ -696,7 +813,7 @@ impl<'a> fmt::Display for GenericArgs<'a> {
 /// type = type1 *(S "/" S  type1)
 /// ```
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct Type<'a> {
   /// Type choices
   pub type_choices: Vec<TypeChoice<'a>>,
@@ -705,6 +822,14 @@ pub struct Type<'a> {
   pub span: Span,
 }
 
+// Types are compared structurally. Spans and comments are ignored so that
+// types which only differ in their formatting are equal
+impl<'a> PartialEq for Type<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    self.type_choices == other.type_choices
+  }
+}
+
 impl<'a> Type<'a> {
   /// take all the comments after a type
   /// this is useful if the type is consumed to build another type object
@@ -758,7 +883,7 @@ impl<'a> Type<'a> {
 
 /// Type choice
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct TypeChoice<'a> {
   /// Type choice
   pub type1: Type1<'a>,
@@ -772,6 +897,12 @@ pub struct TypeChoice<'a> {
   pub comments_after_type: Option<Comments<'a>>,
 }
 
+impl<'a> PartialEq for TypeChoice<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    self.type1 == other.type1
+  }
+}
+
 impl<'a> fmt::Display for Type<'a> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     let mut type_str = String::new();
@@ -862,7 +993,7 @@ impl<'a> Type<'a> {
 /// type1 = type2 [S (rangeop / ctlop) S type2]
 /// ```
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct Type1<'a> {
   /// Type
   pub type2: Type2<'a>,
@@ -878,6 +1009,12 @@ pub struct Type1<'a> {
   pub comments_after_type: Option<Comments<'a>>,
 }
 
+impl<'a> PartialEq for Type1<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    self.type2 == other.type2 && self.operator == other.operator
+  }
+}
+
 impl<'a> From<Value<'a>> for Type1<'a> {
   fn from(value: Value<'a>) -> Self {
     #[cfg(feature = "ast-span")]
@@ -933,7 +1070,7 @@ impl<'a> From<Value<'a>> for Type1<'a> {
 
 /// Range or control operator
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct Operator<'a> {
   /// Operator
   pub operator: RangeCtlOp,
@@ -950,6 +1087,12 @@ pub struct Operator<'a> {
   pub comments_after_operator: Option<Comments<'a>>,
 }
 
+impl<'a> PartialEq for Operator<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    self.operator == other.operator && self.type2 == other.type2
+  }
+}
+
 impl<'a> fmt::Display for Type1<'a> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     let mut t1_str = String::new();
@@ -1007,7 +1150,7 @@ impl<'a> fmt::Display for Type1<'a> {
 /// ctlop = "." id
 /// ```
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, Clone)]
 pub enum RangeCtlOp {
   /// Range operator
   RangeOp {
@@ -1027,6 +1170,25 @@ pub enum RangeCtlOp {
   },
 }
 
+impl PartialEq for RangeCtlOp {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (
+        RangeCtlOp::RangeOp {
+          is_inclusive: l, ..
+        },
+        RangeCtlOp::RangeOp {
+          is_inclusive: r, ..
+        },
+      ) => l == r,
+      (RangeCtlOp::CtlOp { ctrl: l, .. }, RangeCtlOp::CtlOp { ctrl: r, .. }) => l == r,
+      _ => false,
+    }
+  }
+}
+
+impl Eq for RangeCtlOp {}
+
 impl fmt::Display for RangeCtlOp {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
@@ -1058,7 +1220,7 @@ impl fmt::Display for RangeCtlOp {
 ///     / "#"                                 ; any
 /// ```
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum Type2<'a> {
   /// Integer value
   IntValue {
@@ -1282,13 +1444,119 @@ pub enum Type2<'a> {
   },
 }
 
+impl<'a> PartialEq for Type2<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Type2::IntValue { value: l, .. }, Type2::IntValue { value: r, .. }) => l == r,
+      (Type2::UintValue { value: l, .. }, Type2::UintValue { value: r, .. }) => l == r,
+      (Type2::FloatValue { value: l, .. }, Type2::FloatValue { value: r, .. }) => l == r,
+      (Type2::TextValue { value: l, .. }, Type2::TextValue { value: r, .. }) => l == r,
+      (Type2::UTF8ByteString { value: l, .. }, Type2::UTF8ByteString { value: r, .. })
+      | (Type2::B16ByteString { value: l, .. }, Type2::B16ByteString { value: r, .. })
+      | (Type2::B64ByteString { value: l, .. }, Type2::B64ByteString { value: r, .. }) => l == r,
+      (
+        Type2::Typename {
+          ident: l,
+          generic_args: lga,
+          ..
+        },
+        Type2::Typename {
+          ident: r,
+          generic_args: rga,
+          ..
+        },
+      )
+      | (
+        Type2::Unwrap {
+          ident: l,
+          generic_args: lga,
+          ..
+        },
+        Type2::Unwrap {
+          ident: r,
+          generic_args: rga,
+          ..
+        },
+      )
+      | (
+        Type2::ChoiceFromGroup {
+          ident: l,
+          generic_args: lga,
+          ..
+        },
+        Type2::ChoiceFromGroup {
+          ident: r,
+          generic_args: rga,
+          ..
+        },
+      ) => l == r && lga == rga,
+      (Type2::ParenthesizedType { pt: l, .. }, Type2::ParenthesizedType { pt: r, .. }) => l == r,
+      (Type2::Map { group: l, .. }, Type2::Map { group: r, .. })
+      | (Type2::Array { group: l, .. }, Type2::Array { group: r, .. })
+      | (
+        Type2::ChoiceFromInlineGroup { group: l, .. },
+        Type2::ChoiceFromInlineGroup { group: r, .. },
+      ) => l == r,
+      (
+        Type2::TaggedData {
+          tag: ltag, t: l, ..
+        },
+        Type2::TaggedData {
+          tag: rtag, t: r, ..
+        },
+      ) => ltag == rtag && l == r,
+      (
+        Type2::DataMajorType {
+          mt: lmt,
+          constraint: l,
+          ..
+        },
+        Type2::DataMajorType {
+          mt: rmt,
+          constraint: r,
+          ..
+        },
+      ) => lmt == rmt && l == r,
+      (Type2::Any { .. }, Type2::Any { .. }) => true,
+      _ => false,
+    }
+  }
+}
+
+impl<'a> Type2<'a> {
+  /// Return `Span` for `Type2`
+  #[cfg(feature = "ast-span")]
+  pub fn span(&self) -> Span {
+    match self {
+      Type2::IntValue { span, .. }
+      | Type2::UintValue { span, .. }
+      | Type2::FloatValue { span, .. }
+      | Type2::TextValue { span, .. }
+      | Type2::UTF8ByteString { span, .. }
+      | Type2::B16ByteString { span, .. }
+      | Type2::B64ByteString { span, .. }
+      | Type2::Typename { span, .. }
+      | Type2::ParenthesizedType { span, .. }
+      | Type2::Map { span, .. }
+      | Type2::Array { span, .. }
+      | Type2::Unwrap { span, .. }
+      | Type2::ChoiceFromInlineGroup { span, .. }
+      | Type2::ChoiceFromGroup { span, .. }
+      | Type2::TaggedData { span, .. }
+      | Type2::DataMajorType { span, .. }
+      | Type2::Any { span } => *span,
+    }
+  }
+}
+
 #[allow(clippy::cognitive_complexity)]
 impl<'a> fmt::Display for Type2<'a> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
       Type2::IntValue { value, .. } => write!(f, "{}", value),
       Type2::UintValue { value, .. } => write!(f, "{}", value),
-      Type2::FloatValue { value, .. } => write!(f, "{}", value),
+      // Debug formatting retains the fractional part of integral floats
+      Type2::FloatValue { value, .. } => write!(f, "{:?}", value),
       Type2::TextValue { value, .. } => write!(f, "\"{}\"", value),
       Type2::UTF8ByteString { value, .. } => write!(
         f,
@@ -1296,10 +1564,18 @@ impl<'a> fmt::Display for Type2<'a> {
         std::str::from_utf8(value).map_err(|_| fmt::Error)?
       ),
       Type2::B16ByteString { value, .. } => {
-        write!(f, "{}", std::str::from_utf8(value).map_err(|_| fmt::Error)?)
+        write!(
+          f,
+          "h'{}'",
+          std::str::from_utf8(value).map_err(|_| fmt::Error)?
+        )
       }
       Type2::B64ByteString { value, .. } => {
-        write!(f, "{}", std::str::from_utf8(value).map_err(|_| fmt::Error)?)
+        write!(
+          f,
+          "b64'{}'",
+          std::str::from_utf8(value).map_err(|_| fmt::Error)?
+        )
       }
       Type2::Typename {
         ident,
@@ -1914,7 +2190,7 @@ pub fn type_from_token(token: Token) -> Type {
 /// group = grpchoice * (S "//" S grpchoice)
 /// ```
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct Group<'a> {
   /// Group choices
   #[cfg_attr(target_arch = "wasm32", serde(borrow))]
@@ -1924,6 +2200,12 @@ pub struct Group<'a> {
   pub span: Span,
 }
 
+impl<'a> PartialEq for Group<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    self.group_choices == other.group_choices
+  }
+}
+
 impl<'a> From<GroupEntry<'a>> for Group<'a> {
   fn from(ge: GroupEntry<'a>) -> Self {
     Group {
@@ -2018,7 +2300,7 @@ impl<'a> fmt::Display for Group<'a> {
 ///
 /// If tuple is true, then entry is marked by a trailing comma
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct GroupChoice<'a> {
   /// Group entries where the second item in the tuple indicates where or not a
   /// trailing comma is present
@@ -2036,6 +2318,12 @@ pub struct GroupChoice<'a> {
   pub comments_before_grpchoice: Option<Comments<'a>>,
 }
 
+impl<'a> PartialEq for GroupChoice<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    self.group_entries == other.group_entries
+  }
+}
+
 impl<'a> GroupChoice<'a> {
   /// Create new group choice from group entries
   pub fn new(group_entries: Vec<GroupEntry<'a>>) -> Self {
@@ -2261,7 +2549,7 @@ impl<'a> fmt::Display for GroupChoice<'a> {
 ///       / [occur S] "(" S group S ")"
 /// ```
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum GroupEntry<'a> {
   /// Value group entry type
   ValueMemberKey {
@@ -2322,6 +2610,30 @@ pub enum GroupEntry<'a> {
   },
 }
 
+impl<'a> PartialEq for GroupEntry<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (GroupEntry::ValueMemberKey { ge: l, .. }, GroupEntry::ValueMemberKey { ge: r, .. }) => {
+        l == r
+      }
+      (GroupEntry::TypeGroupname { ge: l, .. }, GroupEntry::TypeGroupname { ge: r, .. }) => l == r,
+      (
+        GroupEntry::InlineGroup {
+          occur: lo,
+          group: l,
+          ..
+        },
+        GroupEntry::InlineGroup {
+          occur: ro,
+          group: r,
+          ..
+        },
+      ) => lo == ro && l == r,
+      _ => false,
+    }
+  }
+}
+
 impl<'a> GroupEntry<'a> {
   #[cfg(feature = "ast-comments")]
   fn has_trailing_comments(&self) -> bool {
@@ -2340,7 +2652,7 @@ impl<'a> GroupEntry<'a> {
 
 /// Optional comma
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct OptionalComma<'a> {
   /// Optional comma
   pub optional_comma: bool,
@@ -2354,6 +2666,14 @@ pub struct OptionalComma<'a> {
   pub _a: PhantomData<&'a ()>,
 }
 
+impl<'a> PartialEq for OptionalComma<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    self.optional_comma == other.optional_comma
+  }
+}
+
+impl<'a> Eq for OptionalComma<'a> {}
+
 impl<'a> fmt::Display for OptionalComma<'a> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     let mut optcomma_str = String::new();
@@ -2534,7 +2854,7 @@ impl<'a> fmt::Display for GroupEntry<'a> {
 
 /// Occurrence indicator
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct Occurrence<'a> {
   /// Occurrence indicator
   pub occur: Occur,
@@ -2548,6 +2868,31 @@ pub struct Occurrence<'a> {
   pub _a: PhantomData<&'a ()>,
 }
 
+impl<'a> PartialEq for Occurrence<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    match (self.occur, other.occur) {
+      (
+        Occur::Exact {
+          lower: ll,
+          upper: lu,
+          ..
+        },
+        Occur::Exact {
+          lower: rl,
+          upper: ru,
+          ..
+        },
+      ) => ll == rl && lu == ru,
+      (Occur::ZeroOrMore { .. }, Occur::ZeroOrMore { .. })
+      | (Occur::OneOrMore { .. }, Occur::OneOrMore { .. })
+      | (Occur::Optional { .. }, Occur::Optional { .. }) => true,
+      _ => false,
+    }
+  }
+}
+
+impl<'a> Eq for Occurrence<'a> {}
+
 impl<'a> fmt::Display for Occurrence<'a> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     #[cfg(feature = "ast-comments")]
@@ -2638,7 +2983,7 @@ impl<'a> fmt::Display for TypeGroupnameEntry<'a> {
 ///           / value S ":"
 /// ```
 #[cfg_attr(target_arch = "wasm32", derive(Serialize))]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum MemberKey<'a> {
   /// Type expression
   Type1 {
@@ -2721,6 +3066,32 @@ pub enum NonMemberKey<'a> {
   Type(Type<'a>),
 }
 
+impl<'a> PartialEq for MemberKey<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (
+        MemberKey::Type1 {
+          t1: l, is_cut: lc, ..
+        },
+        MemberKey::Type1 {
+          t1: r, is_cut: rc, ..
+        },
+      ) => l == r && lc == rc,
+      (MemberKey::Bareword { ident: l, .. }, MemberKey::Bareword { ident: r, .. }) => l == r,
+      (MemberKey::Value { value: l, .. }, MemberKey::Value { value: r, .. }) => l == r,
+      (
+        MemberKey::NonMemberKey {
+          non_member_key: l, ..
+        },
+        MemberKey::NonMemberKey {
+          non_member_key: r, ..
+        },
+      ) => l == r,
+      _ => false,
+    }
+  }
+}
+
 impl<'a> fmt::Display for MemberKey<'a> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
@@ -2915,6 +3286,55 @@ pub enum Occur {
   },
 }
 
+impl Occur {
+  /// Lower and upper bounds of the occurrence indicator, where an upper bound
+  /// of `None` is unbounded
+  pub fn bounds(&self) -> (usize, Option<usize>) {
+    match self {
+      Occur::Exact { lower, upper, .. } => (lower.unwrap_or(0), *upper),
+      Occur::ZeroOrMore { .. } => (0, None),
+      Occur::OneOrMore { .. } => (1, None),
+      Occur::Optional { .. } => (0, Some(1)),
+    }
+  }
+
+  /// Whether both occurrence indicators allow the same number of occurrences,
+  /// regardless of their form or span, e.g. `?` and `0*1`
+  pub fn is_equivalent(&self, other: &Occur) -> bool {
+    self.bounds() == other.bounds()
+  }
+
+  /// Collapse equivalent occurrence indicators into their shorthand form, i.e.
+  /// `0*1` into `?`, `0*` into `*` and `1*` into `+`. Returns `None` for `1*1`
+  /// since it is equivalent to the absence of an occurrence indicator
+  pub fn normalize(self) -> Option<Occur> {
+    #[cfg(feature = "ast-span")]
+    let span = match self {
+      Occur::Exact { span, .. }
+      | Occur::ZeroOrMore { span }
+      | Occur::OneOrMore { span }
+      | Occur::Optional { span } => span,
+    };
+
+    match self.bounds() {
+      (1, Some(1)) => None,
+      (0, Some(1)) => Some(Occur::Optional {
+        #[cfg(feature = "ast-span")]
+        span,
+      }),
+      (0, None) => Some(Occur::ZeroOrMore {
+        #[cfg(feature = "ast-span")]
+        span,
+      }),
+      (1, None) => Some(Occur::OneOrMore {
+        #[cfg(feature = "ast-span")]
+        span,
+      }),
+      _ => Some(self),
+    }
+  }
+}
+
 impl fmt::Display for Occur {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
@@ -3068,4 +3488,145 @@ mod tests {
       " key1: \"value1\", key2: \"value2\", ".to_string()
     )
   }
+
+  #[test]
+  fn verify_type_equality_ignores_formatting() -> std::result::Result<(), String> {
+    fn type_value(cddl: &str) -> std::result::Result<Type<'_>, String> {
+      match crate::cddl_from_str(cddl, true)?.rules.remove(0) {
+        Rule::Type { rule, .. } => Ok(rule.value),
+        _ => Err("expected type rule".to_string()),
+      }
+    }
+
+    assert_eq!(
+      type_value("a = { ? b: int, c: [* tstr] }")?,
+      type_value("a = {\n  ?b:int,\n  c : [ * tstr ]\n}")?
+    );
+    assert_eq!(
+      type_value("a = uint .size 8 / bar<int>\nbar<t> = [t]")?,
+      type_value("a = uint .size 8 ; eight bytes\n  / bar< int >\nbar<t> = [t]")?
+    );
+    assert_ne!(
+      type_value("a = { b: int }")?,
+      type_value("a = { b: uint }")?
+    );
+    assert_ne!(type_value("a = 0..10")?, type_value("a = 0...10")?);
+
+    Ok(())
+  }
+
+  #[test]
+  fn verify_canonical_string_round_trip() -> std::result::Result<(), String> {
+    let input = r#"
+      person = {
+        name:tstr,  ? age : uint .le 150,
+        * tstr => any
+      }
+      ratio=0.0..1.0
+      digest =h'0102' / b64'AQI='
+      pair<T>=[T,T]
+      points=[* pair<float>]
+      colors=&( red:1, green:2 )
+      base = ( id: int )
+    "#;
+
+    let cddl = crate::cddl_from_str(input, true)?;
+    let canonical = to_canonical_string(&cddl);
+    let reparsed = crate::cddl_from_str(&canonical, true)?;
+
+    assert_eq!(canonical, to_canonical_string(&reparsed));
+    assert_eq!(cddl, reparsed);
+
+    Ok(())
+  }
+
+  #[test]
+  fn verify_occur_normalization() {
+    let exact = |lower, upper| Occur::Exact {
+      lower,
+      upper,
+      #[cfg(feature = "ast-span")]
+      span: (0, 0, 0),
+    };
+
+    assert_eq!(exact(Some(1), Some(1)).normalize(), None);
+    assert_eq!(
+      exact(Some(0), Some(1)).normalize().map(|o| o.to_string()),
+      Some("?".to_string())
+    );
+    assert_eq!(
+      exact(None, None).normalize().map(|o| o.to_string()),
+      Some("*".to_string())
+    );
+    assert_eq!(
+      exact(Some(1), None).normalize().map(|o| o.to_string()),
+      Some("+".to_string())
+    );
+    assert_eq!(
+      exact(Some(2), Some(3)).normalize().map(|o| o.to_string()),
+      Some("2*3".to_string())
+    );
+
+    let optional = Occur::Optional {
+      #[cfg(feature = "ast-span")]
+      span: (1, 2, 1),
+    };
+    assert!(exact(None, Some(1)).is_equivalent(&optional));
+    assert_ne!(exact(None, Some(1)), optional);
+    assert!(!exact(Some(1), None).is_equivalent(&exact(Some(2), None)));
+  }
+
+  #[test]
+  fn verify_rule_accessors() -> std::result::Result<(), String> {
+    let cddl = crate::cddl_from_str(
+      indoc::indoc!(
+        r#"
+          a = { b, c: d }
+          b = ( e: int )
+          d = tstr / int
+          d /= bool
+          b //= ( f: uint )
+        "#
+      ),
+      true,
+    )?;
+
+    assert_eq!(
+      cddl.type_rules().map(|r| r.name.ident).collect::<Vec<_>>(),
+      ["a", "d", "d"]
+    );
+    assert_eq!(
+      cddl.group_rules().map(|r| r.name.ident).collect::<Vec<_>>(),
+      ["b", "b"]
+    );
+    assert_eq!(cddl.rule_names(), ["a", "b", "d"]);
+
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "ast-span")]
+  fn verify_identifier_and_type2_spans() -> std::result::Result<(), String> {
+    let input = "root = { name: tstr }\nlink = [* tstr] / #6.32(tstr)\n";
+    let cddl = crate::cddl_from_str(input, true)?;
+
+    let source = |span: Span| &input[span.0..span.1];
+
+    let rule = match &cddl.rules[1] {
+      Rule::Type { rule, .. } => rule,
+      _ => return Err("expected a type rule".into()),
+    };
+    assert_eq!(rule.name.span, (22, 26, 2));
+    assert_eq!(source(rule.name.span), "link");
+
+    let type2_sources = rule
+      .value
+      .type_choices
+      .iter()
+      .map(|tc| source(tc.type1.type2.span()))
+      .collect::<Vec<_>>();
+    assert_eq!(type2_sources, ["[* tstr]", "#6.32(tstr)"]);
+
+    Ok(())
+  }
 }
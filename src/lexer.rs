@@ -90,6 +90,13 @@ pub enum LexerErrorType {
 #[cfg(feature = "std")]
 impl std::error::Error for Error {}
 
+impl Error {
+  /// Position in the input at which the error occurred
+  pub fn position(&self) -> Position {
+    self.position
+  }
+}
+
 impl fmt::Display for Error {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     let mut files = SimpleFiles::new();
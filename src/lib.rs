@@ -220,6 +220,33 @@
 //! [RFC 9165](https://datatracker.ietf.org/doc/html/rfc9165). Enabled by
 //! default.
 //!
+//! **`--feature tdate`**
+//!
+//! Validate the format of `tdate` (RFC 3339) strings and the range of `time`
+//! values. Without it, any text string is accepted as a `tdate` and any number
+//! as a `time`, and the `chrono` dependency is dropped. Enabled by default.
+//!
+//! **`--feature uri`**
+//!
+//! Validate the format of `uri` strings. Without it, any text string is
+//! accepted as a `uri`, and the `uriparse` dependency is dropped. Enabled by
+//! default.
+//!
+//! **`--feature arbitrary-precision`**
+//!
+//! Enable the `arbitrary_precision` feature of `serde_json` so that JSON
+//! integers beyond the 64-bit range are validated against `uint`, `nint`,
+//! `int` and integer ranges rather than being parsed as floats. Disabled by
+//! default.
+//!
+//! Disabling `tdate` and `uri` keeps the dependency footprint small, e.g. for
+//! `wasm32-unknown-unknown` targets. The `validate_json` example builds with
+//! the minimal feature set:
+//!
+//! ```sh
+//! cargo run --example validate_json --no-default-features --features json
+//! ```
+//!
 //! ### Parsing CDDL
 //!
 //! ```rust
@@ -535,7 +562,7 @@ extern crate core as std;
 #[cfg(feature = "std")]
 extern crate serde_json;
 
-#[cfg(feature = "std")]
+#[cfg(feature = "uri")]
 extern crate uriparse;
 
 #[cfg(feature = "std")]
@@ -573,11 +600,21 @@ pub use self::{
 #[cfg(feature = "cbor")]
 #[cfg(not(feature = "lsp"))]
 #[cfg(not(target_arch = "wasm32"))]
-pub use self::validator::validate_cbor_from_slice;
+pub use self::validator::{is_valid_cbor, validate_cbor_from_slice, validate_cbor_value};
+
+#[doc(inline)]
+#[cfg(feature = "std")]
+#[cfg(not(feature = "lsp"))]
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::validator::SchemaValidator;
 
 #[doc(inline)]
 #[cfg(feature = "std")]
 #[cfg(feature = "json")]
 #[cfg(not(feature = "lsp"))]
 #[cfg(not(target_arch = "wasm32"))]
-pub use self::validator::validate_json_from_str;
+pub use self::validator::{
+  is_valid_json, validate_json_from_reader, validate_json_from_str,
+  validate_json_from_str_with_options, validate_json_reporting_extras, validate_json_value,
+  validate_jsonl,
+};
@@ -66,9 +66,21 @@ pub struct Parser<'a> {
 /// Parsing error types
 #[derive(Debug, Display)]
 pub enum Error {
-  /// Parsing errors
-  #[displaydoc("{0}")]
-  CDDL(String),
+  /// Parsing errors, located at the first of them
+  #[displaydoc("{msg}")]
+  CDDL {
+    /// Report of all parsing errors
+    msg: String,
+    /// Line of the first error. Only tracked with the `ast-span` feature,
+    /// otherwise 0
+    line: usize,
+    /// Column of the first error. Only tracked with the `ast-span` feature,
+    /// otherwise 0
+    column: usize,
+    /// Byte offset of the first error into the input. Only tracked with the
+    /// `ast-span` feature, otherwise 0
+    byte_offset: usize,
+  },
   #[cfg_attr(
     feature = "ast-span",
     displaydoc("parsing error: position {position:?}, msg: {msg}")
@@ -127,48 +139,7 @@ impl<'a> Parser<'a> {
       #[cfg(feature = "ast-span")]
       parser_position: Position::default(),
       current_rule_generic_param_idents: None,
-      typenames: Rc::new(BTreeSet::from([
-        "any",
-        "uint",
-        "nint",
-        "int",
-        "bstr",
-        "bytes",
-        "tstr",
-        "text",
-        "tdate",
-        "time",
-        "number",
-        "biguint",
-        "bignint",
-        "bigint",
-        "integer",
-        "unsigned",
-        "decfrac",
-        "bigfloat",
-        "eb64url",
-        "eb64legacy",
-        "eb16",
-        "encoded-cbor",
-        "uri",
-        "b64url",
-        "b64legacy",
-        "regexp",
-        "mime-message",
-        "cbor-any",
-        "float16",
-        "float32",
-        "float64",
-        "float16-32",
-        "float32-64",
-        "float",
-        "false",
-        "true",
-        "bool",
-        "nil",
-        "null",
-        "undefined",
-      ])),
+      typenames: Rc::new(token::prelude_types().iter().copied().collect()),
       groupnames: Rc::new(BTreeSet::default()),
       unknown_rule_idents: Vec::default(),
       is_guaranteed: false,
@@ -1848,19 +1819,10 @@ impl<'a> Parser<'a> {
         let begin_type2_line = self.lexer_position.line;
 
         match (*mt, *constraint) {
-          // Tagged data item containing the given type as the tagged value
-          (Some(6), tag) => {
+          // Tagged data item containing the given type as the tagged value.
+          // Without a parenthesized type, #6 is a plain major type
+          (Some(6), tag) if self.peek_token_is(&Token::LPAREN) => {
             self.next_token()?;
-            if !self.cur_token_is(Token::LPAREN) {
-              self.errors.push(Error::PARSER {
-                #[cfg(feature = "ast-span")]
-                position: self.parser_position,
-                msg: InvalidTagSyntax.into(),
-              });
-
-              return Err(Error::INCREMENTAL);
-            }
-
             self.next_token()?;
 
             #[cfg(feature = "ast-comments")]
@@ -1895,7 +1857,7 @@ impl<'a> Parser<'a> {
               #[cfg(feature = "ast-span")]
               span: (
                 begin_type2_range,
-                self.parser_position.range.1,
+                self.lexer_position.range.1,
                 begin_type2_line,
               ),
             })
@@ -3470,6 +3432,81 @@ pub fn cddl_from_str(input: &str, print_stderr: bool) -> std::result::Result<CDD
   }
 }
 
+/// Returns a `ast::CDDL` from a `&str` like [`cddl_from_str`], except that
+/// errors are returned as an [`Error::CDDL`] located at the first syntax error,
+/// e.g. for highlighting it in an editor
+///
+/// # Arguments
+///
+/// * `input` - A string slice with the CDDL text input
+///
+/// # Example
+///
+/// ```
+/// use cddl::parser::{cddl_from_str_with_position, Error};
+///
+/// match cddl_from_str_with_position("a = int\nb = {") {
+///   Err(Error::CDDL { line, .. }) => assert_eq!(line, 2),
+///   _ => unreachable!(),
+/// }
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "std")]
+pub fn cddl_from_str_with_position(input: &str) -> Result<CDDL<'_>> {
+  // Line and column are derived from the byte offset, which is what the
+  // error report itself highlights
+  let located = |msg: String, position: Option<Position>| {
+    let (line, column, byte_offset) = position
+      .and_then(|p| {
+        let preceding = input.get(..p.range.0)?;
+        let line_start = preceding.rfind('\n').map_or(0, |i| i + 1);
+
+        Some((
+          preceding.matches('\n').count() + 1,
+          preceding[line_start..].chars().count() + 1,
+          p.range.0,
+        ))
+      })
+      .unwrap_or_default();
+
+    Error::CDDL {
+      msg,
+      line,
+      column,
+      byte_offset,
+    }
+  };
+
+  let mut p =
+    Parser::new(input, Box::new(lexer::lexer_from_str(input).iter())).map_err(|e| match e {
+      Error::LEXER(e) => located(e.to_string(), Some(e.position())),
+      e => e,
+    })?;
+
+  match p.parse_cddl() {
+    Ok(c) => Ok(c),
+    Err(Error::INCREMENTAL) if !p.errors.is_empty() => {
+      let msg = p
+        .report_errors(false)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| Error::INCREMENTAL.to_string());
+
+      #[cfg(feature = "ast-span")]
+      let position = p.errors.iter().find_map(|e| match e {
+        Error::PARSER { position, .. } => Some(*position),
+        _ => None,
+      });
+      #[cfg(not(feature = "ast-span"))]
+      let position = None;
+
+      Err(located(msg, position))
+    }
+    Err(Error::LEXER(e)) => Err(located(e.to_string(), Some(e.position()))),
+    Err(e) => Err(e),
+  }
+}
+
 /// Identify root type name from CDDL input string
 #[cfg(feature = "std")]
 #[cfg(not(target_arch = "wasm32"))]
@@ -3598,6 +3635,7 @@ pub fn cddl_from_str(input: &str) -> std::result::Result<CDDL, String> {
 pub fn cddl_from_str(input: &str) -> result::Result<JsValue, JsValue> {
   #[derive(Serialize)]
   struct ParserError {
+    #[cfg(feature = "ast-span")]
     position: Position,
     msg: ErrorMsg,
   }
@@ -3612,8 +3650,14 @@ pub fn cddl_from_str(input: &str) -> result::Result<JsValue, JsValue> {
               &p.errors
                 .iter()
                 .filter_map(|e| {
-                  if let Error::PARSER { position, msg } = e {
+                  if let Error::PARSER {
+                    #[cfg(feature = "ast-span")]
+                    position,
+                    msg,
+                  } = e
+                  {
                     Some(ParserError {
+                      #[cfg(feature = "ast-span")]
                       position: *position,
                       msg: msg.clone(),
                     })
@@ -3642,6 +3686,7 @@ pub fn cddl_from_str(input: &str) -> result::Result<JsValue, JsValue> {
 pub fn format_cddl_from_str(input: &str) -> result::Result<String, JsValue> {
   #[derive(Serialize)]
   struct ParserError {
+    #[cfg(feature = "ast-span")]
     position: Position,
     msg: ErrorMsg,
   }
@@ -3656,8 +3701,14 @@ pub fn format_cddl_from_str(input: &str) -> result::Result<String, JsValue> {
               &p.errors
                 .iter()
                 .filter_map(|e| {
-                  if let Error::PARSER { position, msg } = e {
+                  if let Error::PARSER {
+                    #[cfg(feature = "ast-span")]
+                    position,
+                    msg,
+                  } = e
+                  {
                     Some(ParserError {
+                      #[cfg(feature = "ast-span")]
                       position: *position,
                       msg: msg.clone(),
                     })
@@ -16,6 +16,12 @@ mod tests {
   use indoc::indoc;
   use pretty_assertions::assert_eq;
 
+  /// Assert that two AST nodes are equal including their spans and comments,
+  /// which are ignored by their PartialEq implementations
+  fn assert_eq_with_spans<T: std::fmt::Debug>(left: &T, right: &T) {
+    assert_eq!(format!("{:#?}", left), format!("{:#?}", right));
+  }
+
   #[test]
   fn verify_rule_diagnostic() -> Result<()> {
     let input = indoc!(
@@ -275,7 +281,7 @@ mod tests {
       span: (0, 17, 1),
     };
 
-    assert_eq!(generic_args, expected_output);
+    assert_eq_with_spans(&generic_args, &expected_output);
     assert_eq!(generic_args.to_string(), expected_output.to_string());
 
     Ok(())
@@ -346,7 +352,7 @@ mod tests {
       span: (0, 23, 1),
     };
 
-    assert_eq!(t, expected_output);
+    assert_eq_with_spans(&t, &expected_output);
     assert_eq!(t.to_string(), expected_output.to_string());
 
     Ok(())
@@ -553,7 +559,7 @@ mod tests {
       let l = Lexer::new(inputs[idx]);
       let t1 = Parser::new(inputs[idx], Box::new(l.iter()))?.parse_type1(None)?;
 
-      assert_eq!(&t1, expected_output);
+      assert_eq_with_spans(&t1, expected_output);
       assert_eq!(t1.to_string(), expected_output.to_string());
     }
 
@@ -995,7 +1001,7 @@ mod tests {
       let l = Lexer::new(inputs[idx]);
       let t2 = Parser::new(inputs[idx], Box::new(l.iter()))?.parse_type2()?;
 
-      assert_eq!(&t2, expected_output);
+      assert_eq_with_spans(&t2, expected_output);
       assert_eq!(t2.to_string(), expected_output.to_string());
     }
 
@@ -1365,7 +1371,7 @@ mod tests {
       let l = Lexer::new(inputs[idx]);
       let t2 = Parser::new(inputs[idx], Box::new(l.iter()))?.parse_type2()?;
 
-      assert_eq!(&t2, expected_output);
+      assert_eq_with_spans(&t2, expected_output);
       assert_eq!(t2.to_string(), expected_output.to_string());
     }
 
@@ -1665,7 +1671,7 @@ mod tests {
       let l = Lexer::new(inputs[idx]);
       let grpent = Parser::new(inputs[idx], Box::new(l.iter()))?.parse_grpent(false)?;
 
-      assert_eq!(&grpent, expected_output);
+      assert_eq_with_spans(&grpent, expected_output);
       assert_eq!(grpent.to_string(), expected_output.to_string());
     }
 
@@ -1797,7 +1803,7 @@ mod tests {
       let mk = Parser::new(inputs[idx], Box::new(l.iter()))?.parse_memberkey(false)?;
 
       if let Some(mk) = mk {
-        assert_eq!(&mk, expected_output);
+        assert_eq_with_spans(&mk, expected_output);
         assert_eq!(mk.to_string(), expected_output.to_string());
       }
     }
@@ -1859,7 +1865,7 @@ mod tests {
       let o = Parser::new(inputs[idx], Box::new(l.iter()))?.parse_occur(false)?;
 
       if let Some(o) = o {
-        assert_eq!(&o, expected_output);
+        assert_eq_with_spans(&o, expected_output);
         assert_eq!(o.to_string(), expected_output.to_string());
       }
     }
@@ -1936,7 +1942,7 @@ mod tests {
     };
 
     let parser = Parser::new(input, Box::new(Lexer::new(input).iter()))?.parse_cddl()?;
-    assert_eq!(parser, expected_output);
+    assert_eq_with_spans(&parser, &expected_output);
     assert_eq!(parser.to_string(), expected_output.to_string());
 
     Ok(())
@@ -2041,7 +2047,7 @@ mod tests {
     };
 
     let parser = Parser::new(input, Box::new(Lexer::new(input).iter()))?.parse_cddl()?;
-    assert_eq!(parser, expected_output);
+    assert_eq_with_spans(&parser, &expected_output);
     assert_eq!(parser.to_string(), expected_output.to_string());
 
     Ok(())
@@ -2226,7 +2232,7 @@ mod tests {
     };
 
     let parser = Parser::new(input, Box::new(Lexer::new(input).iter()))?.parse_cddl()?;
-    assert_eq!(parser, expected_output);
+    assert_eq_with_spans(&parser, &expected_output);
     assert_eq!(parser.to_string(), expected_output.to_string());
 
     Ok(())
@@ -2421,7 +2427,7 @@ mod tests {
     };
 
     let parser = Parser::new(input, Box::new(Lexer::new(input).iter()))?.parse_cddl()?;
-    assert_eq!(parser, expected_output);
+    assert_eq_with_spans(&parser, &expected_output);
     assert_eq!(parser.to_string(), expected_output.to_string());
 
     Ok(())
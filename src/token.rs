@@ -245,6 +245,10 @@ pub enum ControlOperator {
   #[cfg(feature = "additional-controls")]
   /// .feature control operator (rfc 9165)
   FEATURE,
+  #[cfg(feature = "extensions")]
+  /// .join control operator. Non-standard extension that joins the string
+  /// members of an array with the controller as the separator
+  JOIN,
 }
 
 impl<'a> Token<'a> {
@@ -505,6 +509,8 @@ impl fmt::Display for ControlOperator {
       ControlOperator::ABNFB => write!(f, ".abnfb"),
       #[cfg(feature = "additional-controls")]
       ControlOperator::FEATURE => write!(f, ".feature"),
+      #[cfg(feature = "extensions")]
+      ControlOperator::JOIN => write!(f, ".join"),
       ControlOperator::AND => write!(f, ".and"),
       ControlOperator::LT => write!(f, ".lt"),
       ControlOperator::LE => write!(f, ".le"),
@@ -636,10 +642,67 @@ pub fn lookup_control_from_str(ident: &str) -> Option<ControlOperator> {
     ".abnfb" => Some(ControlOperator::ABNFB),
     #[cfg(feature = "additional-controls")]
     ".feature" => Some(ControlOperator::FEATURE),
+    #[cfg(feature = "extensions")]
+    ".join" => Some(ControlOperator::JOIN),
     _ => None,
   }
 }
 
+/// Names of the types defined in the standard prelude (RFC 8610 Appendix D)
+///
+/// # Example
+///
+/// ```
+/// use cddl::token::prelude_types;
+///
+/// assert!(prelude_types().contains(&"uint"));
+/// assert!(!prelude_types().contains(&"myrule"));
+/// ```
+pub fn prelude_types() -> &'static [&'static str] {
+  &[
+    "any",
+    "uint",
+    "nint",
+    "int",
+    "bstr",
+    "bytes",
+    "tstr",
+    "text",
+    "tdate",
+    "time",
+    "number",
+    "biguint",
+    "bignint",
+    "bigint",
+    "integer",
+    "unsigned",
+    "decfrac",
+    "bigfloat",
+    "eb64url",
+    "eb64legacy",
+    "eb16",
+    "encoded-cbor",
+    "uri",
+    "b64url",
+    "b64legacy",
+    "regexp",
+    "mime-message",
+    "cbor-any",
+    "float16",
+    "float32",
+    "float64",
+    "float16-32",
+    "float32-64",
+    "float",
+    "false",
+    "true",
+    "bool",
+    "nil",
+    "null",
+    "undefined",
+  ]
+}
+
 /// Returns token in standard prelude from given string
 ///
 /// # Arguments
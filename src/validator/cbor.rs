@@ -2,25 +2,31 @@
 #![cfg(feature = "cbor")]
 #![cfg(not(feature = "lsp"))]
 
-use super::*;
+use super::{
+  value::{compare_numeric, range_contains},
+  *,
+};
 use crate::{
   ast::*,
   token,
   visitor::{self, *},
 };
 
-use core::convert::TryInto;
 use std::{
   borrow::Cow,
   collections::HashMap,
-  convert::TryFrom,
   fmt::{self, Write},
 };
 
-use chrono::{TimeZone, Utc};
+#[cfg(feature = "uri")]
+use std::convert::TryFrom;
+
 use ciborium::value::Value;
 use serde_json;
 
+use crate::validator::control::range_bound_values;
+#[cfg(feature = "extensions")]
+use crate::validator::control::validate_join_control;
 #[cfg(feature = "additional-controls")]
 use crate::validator::control::{
   abnf_from_complex_controller, cat_operation, plus_operation, validate_abnf,
@@ -31,6 +37,7 @@ pub type Result<T> = std::result::Result<(), Error<T>>;
 
 /// cbor validation error
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error<T: std::fmt::Debug> {
   /// Zero or more validation errors
   Validation(Vec<ValidationError>),
@@ -46,6 +53,11 @@ pub enum Error<T: std::fmt::Debug> {
   Base16Decoding(base16::DecodeError),
   /// Base64 decoding error
   Base64Decoding(data_encoding::DecodeError),
+  /// The CDDL document has no type rule to validate against
+  NoRootRule,
+  /// The CDDL document uses syntax which is invalid or isn't supported during
+  /// validation
+  Syntax(SyntaxError),
 }
 
 impl<T: std::fmt::Debug> fmt::Display for Error<T> {
@@ -64,6 +76,8 @@ impl<T: std::fmt::Debug> fmt::Display for Error<T> {
       Error::UTF8Parsing(error) => write!(f, "error parsing utf8: {}", error),
       Error::Base16Decoding(error) => write!(f, "error decoding base16: {}", error),
       Error::Base64Decoding(error) => write!(f, "error decoding base64: {}", error),
+      Error::NoRootRule => write!(f, "CDDL document contains no root type rule"),
+      Error::Syntax(error) => write!(f, "invalid CDDL syntax: {}", error),
     }
   }
 }
@@ -77,6 +91,52 @@ impl<T: std::fmt::Debug + 'static> std::error::Error for Error<T> {
   }
 }
 
+impl<T: std::fmt::Debug> IntoIterator for Error<T> {
+  type Item = ValidationError;
+  type IntoIter = std::vec::IntoIter<ValidationError>;
+
+  /// Iterate over the owned validation errors. Other errors yield nothing
+  fn into_iter(self) -> Self::IntoIter {
+    match self {
+      Error::Validation(errors) => errors.into_iter(),
+      _ => Vec::new().into_iter(),
+    }
+  }
+}
+
+impl<T: std::fmt::Debug> Error<T> {
+  /// Individual validation errors, or none if the error did not occur during
+  /// validation
+  pub fn leaf_errors(&self) -> Vec<&ValidationError> {
+    match self {
+      Error::Validation(errors) => errors.iter().collect(),
+      _ => Vec::new(),
+    }
+  }
+
+  /// Whether the CDDL document uses syntax which is invalid or isn't supported
+  /// during validation
+  pub fn is_syntax(&self) -> bool {
+    matches!(self, Error::Syntax(_))
+  }
+
+  /// Whether any of the validation errors reports an array item or map entry
+  /// occurring fewer or more times than allowed, including a missing required
+  /// entry
+  pub fn is_occurrence(&self) -> bool {
+    self.leaf_errors().iter().any(|e| e.is_occurrence)
+  }
+
+  /// The error raised while decoding the CBOR document being validated, if
+  /// that is what failed
+  pub fn as_target(&self) -> Option<&ciborium::de::Error<T>> {
+    match self {
+      Error::CBORParsing(error) => Some(error),
+      _ => None,
+    }
+  }
+}
+
 /// cbor validation error
 #[derive(Clone, Debug)]
 pub struct ValidationError {
@@ -94,6 +154,9 @@ pub struct ValidationError {
   pub is_group_to_choice_enum: bool,
   /// Error is associated with a type/group name group entry
   pub type_group_name_entry: Option<String>,
+  /// Whether or not the error reports an array item or map entry occurring fewer
+  /// or more times than allowed, including a missing required entry
+  pub is_occurrence: bool,
 }
 
 impl fmt::Display for ValidationError {
@@ -135,6 +198,7 @@ impl<T: std::fmt::Debug> Error<T> {
       is_multi_type_choice: cv.is_multi_type_choice,
       is_group_to_choice_enum: cv.is_group_to_choice_enum,
       type_group_name_entry: cv.type_group_name_entry.map(|e| e.to_string()),
+      is_occurrence: false,
       is_multi_group_choice: cv.is_multi_group_choice,
     }])
   }
@@ -185,6 +249,9 @@ pub struct CBORValidator<'a> {
   entry_counts: Option<Vec<EntryCount>>,
   // Collect map entry keys that have already been validated
   validated_keys: Option<Vec<Value>>,
+  // Rules being evaluated against the current CBOR value, used to detect
+  // cyclic rule references
+  visited_rules: Vec<&'a str>,
   // Collect map entry values that have yet to be validated
   values_to_validate: Option<Vec<Value>>,
   // Whether or not the validator is validating a map entry value
@@ -205,8 +272,6 @@ pub struct CBORValidator<'a> {
   enabled_features: Option<Box<[JsValue]>>,
   #[cfg(feature = "additional-controls")]
   has_feature_errors: bool,
-  #[cfg(feature = "additional-controls")]
-  disabled_features: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug)]
@@ -244,6 +309,7 @@ impl<'a> CBORValidator<'a> {
       is_ctrl_map_equality: false,
       entry_counts: None,
       validated_keys: None,
+      visited_rules: Vec::new(),
       values_to_validate: None,
       validating_value: false,
       valid_array_items: None,
@@ -253,7 +319,6 @@ impl<'a> CBORValidator<'a> {
       is_multi_type_choice_type_rule_validating_array: false,
       enabled_features,
       has_feature_errors: false,
-      disabled_features: None,
     }
   }
 
@@ -284,6 +349,7 @@ impl<'a> CBORValidator<'a> {
       is_ctrl_map_equality: false,
       entry_counts: None,
       validated_keys: None,
+      visited_rules: Vec::new(),
       values_to_validate: None,
       validating_value: false,
       valid_array_items: None,
@@ -321,6 +387,7 @@ impl<'a> CBORValidator<'a> {
       is_ctrl_map_equality: false,
       entry_counts: None,
       validated_keys: None,
+      visited_rules: Vec::new(),
       values_to_validate: None,
       validating_value: false,
       valid_array_items: None,
@@ -330,7 +397,6 @@ impl<'a> CBORValidator<'a> {
       is_multi_type_choice_type_rule_validating_array: false,
       enabled_features,
       has_feature_errors: false,
-      disabled_features: None,
     }
   }
 
@@ -361,6 +427,7 @@ impl<'a> CBORValidator<'a> {
       is_ctrl_map_equality: false,
       entry_counts: None,
       validated_keys: None,
+      visited_rules: Vec::new(),
       values_to_validate: None,
       validating_value: false,
       valid_array_items: None,
@@ -416,7 +483,10 @@ impl<'a> CBORValidator<'a> {
                 ArrayItemToken::Range(lower, upper, is_inclusive) => {
                   cv.visit_range(lower, upper, *is_inclusive)?
                 }
-                ArrayItemToken::Group(group) => cv.visit_group(group)?,
+                ArrayItemToken::Map(map) => match v {
+                  Value::Map(_) => cv.visit_type2(map)?,
+                  _ => cv.add_error(format!("expected map object {}, got {:?}", map, v)),
+                },
                 ArrayItemToken::Identifier(ident) => cv.visit_identifier(ident)?,
                 ArrayItemToken::TaggedData(tagged_data) => cv.visit_type2(tagged_data)?,
               }
@@ -470,7 +540,10 @@ impl<'a> CBORValidator<'a> {
                   ArrayItemToken::Range(lower, upper, is_inclusive) => {
                     cv.visit_range(lower, upper, *is_inclusive)?
                   }
-                  ArrayItemToken::Group(group) => cv.visit_group(group)?,
+                  ArrayItemToken::Map(map) => match v {
+                    Value::Map(_) => cv.visit_type2(map)?,
+                    _ => cv.add_error(format!("expected map object {}, got {:?}", map, v)),
+                  },
                   ArrayItemToken::Identifier(ident) => cv.visit_identifier(ident)?,
                   ArrayItemToken::TaggedData(tagged_data) => cv.visit_type2(tagged_data)?,
                 }
@@ -486,7 +559,7 @@ impl<'a> CBORValidator<'a> {
         }
         Err(errors) => {
           for e in errors.into_iter() {
-            self.add_error(e);
+            self.add_occurrence_error(e);
           }
         }
       }
@@ -494,6 +567,100 @@ impl<'a> CBORValidator<'a> {
 
     Ok(())
   }
+
+  /// Validate the items of a CBOR array against the entries of an array group
+  /// choice in order, where each entry consumes as many consecutive items as
+  /// its occurrence indicator allows
+  fn validate_array_items_positionally<T: std::fmt::Debug + 'static>(
+    &mut self,
+    gc: &GroupChoice<'a>,
+  ) -> visitor::Result<Error<T>>
+  where
+    cbor::Error<T>: From<cbor::Error<std::io::Error>>,
+  {
+    let items = match &self.cbor {
+      Value::Array(a) => a.clone(),
+      _ => return Ok(()),
+    };
+
+    match match_array_items_positionally(gc, items.len(), |ge, idx| {
+      self.validate_array_item::<T>(ge, idx, &items[idx])
+    })? {
+      Some(PositionalMismatch::Item(mut item_errors)) => self.errors.append(&mut item_errors),
+      Some(PositionalMismatch::MissingItem(entry, idx)) => self.add_error(format!(
+        "expected array item {} at index {}, got end of array",
+        gc.group_entries[entry].0, idx
+      )),
+      Some(PositionalMismatch::UnexpectedItem(idx)) => self.add_error(format!(
+        "unexpected array item at index {}, got {:?}",
+        idx, items[idx]
+      )),
+      None => (),
+    }
+
+    Ok(())
+  }
+
+  /// Validate a single CBOR array item against a group entry, returning the
+  /// resulting errors
+  fn validate_array_item<T: std::fmt::Debug + 'static>(
+    &self,
+    ge: &GroupEntry<'a>,
+    idx: usize,
+    item: &Value,
+  ) -> std::result::Result<Vec<ValidationError>, Error<T>>
+  where
+    cbor::Error<T>: From<cbor::Error<std::io::Error>>,
+  {
+    #[cfg(all(feature = "additional-controls", target_arch = "wasm32"))]
+    let mut cv = CBORValidator::new(self.cddl, item.clone(), self.enabled_features.clone());
+    #[cfg(all(feature = "additional-controls", not(target_arch = "wasm32")))]
+    let mut cv = CBORValidator::new(self.cddl, item.clone(), self.enabled_features);
+    #[cfg(not(feature = "additional-controls"))]
+    let mut cv = CBORValidator::new(self.cddl, item.clone());
+
+    cv.generic_rules = self.generic_rules.clone();
+    cv.eval_generic_rule = self.eval_generic_rule;
+    cv.is_multi_type_choice = self.is_multi_type_choice;
+    let _ = write!(cv.cbor_location, "{}/{}", self.cbor_location, idx);
+
+    match ge {
+      GroupEntry::ValueMemberKey { ge, .. } => {
+        Visitor::<Error<T>>::visit_type(&mut cv, &ge.entry_type)?
+      }
+      GroupEntry::TypeGroupname { ge, .. } if ge.generic_args.is_some() => {
+        Visitor::<Error<T>>::visit_type_groupname_entry(
+          &mut cv,
+          &TypeGroupnameEntry {
+            occur: None,
+            name: ge.name.clone(),
+            generic_args: ge.generic_args.clone(),
+          },
+        )?
+      }
+      GroupEntry::TypeGroupname { ge, .. } => {
+        Visitor::<Error<T>>::visit_identifier(&mut cv, &ge.name)?
+      }
+      _ => (),
+    }
+
+    Ok(cv.errors)
+  }
+
+  /// Collect a validation error reporting an array item or map entry
+  /// occurring fewer or more times than allowed
+  fn add_occurrence_error(&mut self, reason: String) {
+    self.errors.push(ValidationError {
+      reason,
+      cddl_location: self.cddl_location.clone(),
+      cbor_location: self.cbor_location.clone(),
+      is_multi_type_choice: self.is_multi_type_choice,
+      is_multi_group_choice: self.is_multi_group_choice,
+      is_group_to_choice_enum: self.is_group_to_choice_enum,
+      type_group_name_entry: self.type_group_name_entry.map(|e| e.to_string()),
+      is_occurrence: true,
+    });
+  }
 }
 
 impl<'a, 'b, T: std::fmt::Debug + 'static> Validator<'a, 'b, cbor::Error<T>> for CBORValidator<'a>
@@ -501,18 +668,26 @@ where
   cbor::Error<T>: From<cbor::Error<std::io::Error>>,
 {
   fn validate(&mut self) -> std::result::Result<(), cbor::Error<T>> {
+    let mut has_root = false;
     for r in self.cddl.rules.iter() {
       // First type rule is root
       if let Rule::Type { rule, .. } = r {
         if rule.generic_params.is_none() {
+          has_root = true;
           self.is_root = true;
+          self.visited_rules.push(rule.name.ident);
           self.visit_type_rule(rule)?;
+          self.visited_rules.pop();
           self.is_root = false;
           break;
         }
       }
     }
 
+    if !has_root {
+      return Err(Error::NoRootRule);
+    }
+
     if !self.errors.is_empty() {
       return Err(Error::Validation(self.errors.clone()));
     }
@@ -529,6 +704,7 @@ where
       is_multi_group_choice: self.is_multi_group_choice,
       is_group_to_choice_enum: self.is_group_to_choice_enum,
       type_group_name_entry: self.type_group_name_entry.map(|e| e.to_string()),
+      is_occurrence: false,
     });
   }
 }
@@ -639,10 +815,7 @@ where
         self.visit_type_choice(type_choice)?;
 
         #[cfg(feature = "additional-controls")]
-        if self.errors.len() == error_count
-          && !self.has_feature_errors
-          && self.disabled_features.is_none()
-        {
+        if self.errors.len() == error_count && !self.has_feature_errors {
           // Disregard invalid type choice validation errors if one of the
           // choices validates successfully
           let type_choice_error_count = self.errors.len() - initial_error_count;
@@ -672,10 +845,7 @@ where
       self.visit_type_choice(type_choice)?;
 
       #[cfg(feature = "additional-controls")]
-      if self.errors.len() == error_count
-        && !self.has_feature_errors
-        && self.disabled_features.is_none()
-      {
+      if self.errors.len() == error_count && !self.has_feature_errors {
         // Disregard invalid type choice validation errors if one of the
         // choices validates successfully
         let type_choice_error_count = self.errors.len() - initial_error_count;
@@ -721,12 +891,12 @@ where
             if !validate_entry_count(&entry_counts, len) {
               for ec in entry_counts.iter() {
                 if let Some(occur) = &ec.entry_occurrence {
-                  self.add_error(format!(
+                  self.add_occurrence_error(format!(
                     "expected array with length per occurrence {}",
                     occur,
                   ));
                 } else {
-                  self.add_error(format!(
+                  self.add_occurrence_error(format!(
                     "expected array with length {}, got {}",
                     ec.count, len
                   ));
@@ -793,14 +963,64 @@ where
 
   fn visit_range(
     &mut self,
-    lower: &Type2,
-    upper: &Type2,
+    lower: &'b Type2<'a>,
+    upper: &'b Type2<'a>,
     is_inclusive: bool,
   ) -> visitor::Result<Error<T>> {
     if let Value::Array(_) = &self.cbor {
       return self.validate_array_items(&ArrayItemToken::Range(lower, upper, is_inclusive));
     }
 
+    // Range bounds referencing named rules are resolved to their numeric
+    // values. The value is in range if any pair of resolved bounds admits it
+    if matches!(lower, Type2::Typename { .. }) || matches!(upper, Type2::Typename { .. }) {
+      let lowers = range_bound_values(self.cddl, lower);
+      let uppers = range_bound_values(self.cddl, upper);
+
+      if lowers.is_empty() || uppers.is_empty() {
+        return Err(Error::Syntax(SyntaxError::new(
+          SyntaxErrorKind::InvalidRangeBound,
+          &format!(
+            "invalid cddl range. bounds must resolve to numeric values, got {} and {}",
+            lower, upper
+          ),
+        )));
+      }
+
+      let error_count = self.errors.len();
+      for l in lowers.iter() {
+        for u in uppers.iter() {
+          let cur_errors = self.errors.len();
+          self.visit_range(l, u, is_inclusive)?;
+          if self.errors.len() == cur_errors {
+            self.errors.truncate(error_count);
+            return Ok(());
+          }
+        }
+      }
+
+      return Ok(());
+    }
+
+    // Ranges mixing integer and float bounds are compared as floats
+    if mixed_numeric_range_bounds(lower, upper).is_some() {
+      if range_contains(&self.cbor, lower, upper, is_inclusive) != Some(true) {
+        if is_inclusive {
+          self.add_error(format!(
+            "expected number to be in range {} <= value <= {}, got {:?}",
+            lower, upper, self.cbor
+          ));
+        } else {
+          self.add_error(format!(
+            "expected number to be in range {} <= value < {}, got {:?}",
+            lower, upper, self.cbor
+          ));
+        }
+      }
+
+      return Ok(());
+    }
+
     match lower {
       Type2::IntValue { value: l, .. } => match upper {
         Type2::IntValue { value: u, .. } => {
@@ -811,31 +1031,16 @@ where
             )
           } else {
             format!(
-              "expected integer to be in range {} < value < {}, got {:?}",
+              "expected integer to be in range {} <= value < {}, got {:?}",
               l, u, self.cbor
             )
           };
 
-          match &self.cbor {
-            Value::Integer(i) => {
-              if is_inclusive {
-                if i128::from(*i) < *l as i128 || i128::from(*i) > *u as i128 {
-                  self.add_error(error_str);
-                } else {
-                  return Ok(());
-                }
-              } else if i128::from(*i) <= *l as i128 || i128::from(*i) >= *u as i128 {
-                self.add_error(error_str);
-                return Ok(());
-              } else {
-                return Ok(());
-              }
-            }
-            _ => {
-              self.add_error(error_str);
-              return Ok(());
-            }
+          if range_contains(&self.cbor, lower, upper, is_inclusive) != Some(true) {
+            self.add_error(error_str);
           }
+
+          return Ok(());
         }
         Type2::UintValue { value: u, .. } => {
           let error_str = if is_inclusive {
@@ -845,38 +1050,25 @@ where
             )
           } else {
             format!(
-              "expected integer to be in range {} < value < {}, got {:?}",
+              "expected integer to be in range {} <= value < {}, got {:?}",
               l, u, self.cbor
             )
           };
 
-          match &self.cbor {
-            Value::Integer(i) => {
-              if is_inclusive {
-                if i128::from(*i) < *l as i128 || i128::from(*i) > *u as i128 {
-                  self.add_error(error_str);
-                } else {
-                  return Ok(());
-                }
-              } else if i128::from(*i) <= *l as i128 || i128::from(*i) >= *u as i128 {
-                self.add_error(error_str);
-                return Ok(());
-              } else {
-                return Ok(());
-              }
-            }
-            _ => {
-              self.add_error(error_str);
-              return Ok(());
-            }
+          if range_contains(&self.cbor, lower, upper, is_inclusive) != Some(true) {
+            self.add_error(error_str);
           }
+
+          return Ok(());
         }
         _ => {
-          self.add_error(format!(
-            "invalid cddl range. upper value must be an integer type. got {}",
-            upper
-          ));
-          return Ok(());
+          return Err(Error::Syntax(SyntaxError::new(
+            SyntaxErrorKind::InvalidRangeBound,
+            &format!(
+              "invalid cddl range. upper value must be an integer type. got {}",
+              upper
+            ),
+          )));
         }
       },
       Type2::UintValue { value: l, .. } => match upper {
@@ -888,25 +1080,18 @@ where
             )
           } else {
             format!(
-              "expected uint to be in range {} < value < {}, got {:?}",
+              "expected uint to be in range {} <= value < {}, got {:?}",
               l, u, self.cbor
             )
           };
 
           match &self.cbor {
-            Value::Integer(i) => {
-              if is_inclusive {
-                if i128::from(*i) < *l as i128 || i128::from(*i) > *u as i128 {
-                  self.add_error(error_str);
-                } else {
-                  return Ok(());
-                }
-              } else if i128::from(*i) <= *l as i128 || i128::from(*i) >= *u as i128 {
+            Value::Integer(_) => {
+              if range_contains(&self.cbor, lower, upper, is_inclusive) != Some(true) {
                 self.add_error(error_str);
-                return Ok(());
-              } else {
-                return Ok(());
               }
+
+              return Ok(());
             }
             Value::Text(s) => match self.ctrl {
               Some(ControlOperator::SIZE) => {
@@ -915,15 +1100,15 @@ where
                 if is_inclusive {
                   if s.len() < *l || s.len() > *u {
                     self.add_error(format!(
-                      "expected \"{}\" string length to be in the range {} <= value <= {}, got {}",
+                      "expected \"{}\" string length in UTF-8 bytes to be in the range {} <= value <= {}, got {}",
                       s, l, u, len
                     ));
                   }
 
                   return Ok(());
-                } else if s.len() <= *l || s.len() >= *u {
+                } else if s.len() < *l || s.len() >= *u {
                   self.add_error(format!(
-                    "expected \"{}\" string length to be in the range {} < value < {}, got {}",
+                    "expected \"{}\" string length in UTF-8 bytes to be in the range {} <= value < {}, got {}",
                     s, l, u, len
                   ));
                   return Ok(());
@@ -934,6 +1119,31 @@ where
                 return Ok(());
               }
             },
+            Value::Bytes(b) => match self.ctrl {
+              Some(ControlOperator::SIZE) => {
+                let len = b.len();
+                if is_inclusive {
+                  if len < *l || len > *u {
+                    self.add_error(format!(
+                      "expected \"{:?}\" byte string length to be in the range {} <= value <= {}, got {}",
+                      b, l, u, len
+                    ));
+                  }
+
+                  return Ok(());
+                } else if len < *l || len >= *u {
+                  self.add_error(format!(
+                    "expected \"{:?}\" byte string length to be in the range {} <= value < {}, got {}",
+                    b, l, u, len
+                  ));
+                  return Ok(());
+                }
+              }
+              _ => {
+                self.add_error("byte string value cannot be validated against a range without the .size control operator".to_string());
+                return Ok(());
+              }
+            },
             _ => {
               self.add_error(error_str);
               return Ok(());
@@ -941,11 +1151,13 @@ where
           }
         }
         _ => {
-          self.add_error(format!(
-            "invalid cddl range. upper value must be a uint type. got {}",
-            upper
-          ));
-          return Ok(());
+          return Err(Error::Syntax(SyntaxError::new(
+            SyntaxErrorKind::InvalidRangeBound,
+            &format!(
+              "invalid cddl range. upper value must be a uint type. got {}",
+              upper
+            ),
+          )));
         }
       },
       Type2::FloatValue { value: l, .. } => match upper {
@@ -957,47 +1169,32 @@ where
             )
           } else {
             format!(
-              "expected float to be in range {} < value < {}, got {:?}",
+              "expected float to be in range {} <= value < {}, got {:?}",
               l, u, self.cbor
             )
           };
 
-          match &self.cbor {
-            Value::Float(f) => {
-              if is_inclusive {
-                if *f < *l || *f > *u {
-                  self.add_error(error_str);
-                } else {
-                  return Ok(());
-                }
-              } else if *f <= *l || *f >= *u {
-                self.add_error(error_str);
-                return Ok(());
-              } else {
-                return Ok(());
-              }
-            }
-            _ => {
-              self.add_error(error_str);
-              return Ok(());
-            }
+          if range_contains(&self.cbor, lower, upper, is_inclusive) != Some(true) {
+            self.add_error(error_str);
           }
+
+          return Ok(());
         }
         _ => {
-          self.add_error(format!(
-            "invalid cddl range. upper value must be a float type. got {}",
-            upper
-          ));
-          return Ok(());
+          return Err(Error::Syntax(SyntaxError::new(
+            SyntaxErrorKind::InvalidRangeBound,
+            &format!(
+              "invalid cddl range. upper value must be a float type. got {}",
+              upper
+            ),
+          )));
         }
       },
       _ => {
-        self.add_error(
-          "invalid cddl range. upper and lower values must be either integers or floats"
-            .to_string(),
-        );
-
-        return Ok(());
+        return Err(Error::Syntax(SyntaxError::new(
+          SyntaxErrorKind::InvalidRangeBound,
+          "invalid cddl range. upper and lower values must be either integers or floats",
+        )));
       }
     }
 
@@ -1138,18 +1335,32 @@ where
       ControlOperator::LT | ControlOperator::GT | ControlOperator::GE | ControlOperator::LE => {
         match target {
           Type2::Typename { ident, .. } if is_ident_numeric_data_type(self.cddl, ident) => {
+            if let Type2::TextValue { .. }
+            | Type2::UTF8ByteString { .. }
+            | Type2::B16ByteString { .. }
+            | Type2::B64ByteString { .. } = controller
+            {
+              return Err(Error::Syntax(SyntaxError::new(
+                SyntaxErrorKind::ControlOperatorTypeMismatch,
+                &format!(
+                  "controller for {} operator must be a numerical value, got {}",
+                  ctrl, controller
+                ),
+              )));
+            }
+
             self.ctrl = Some(ctrl);
             self.visit_type2(controller)?;
             self.ctrl = None;
             Ok(())
           }
-          _ => {
-            self.add_error(format!(
+          _ => Err(Error::Syntax(SyntaxError::new(
+            SyntaxErrorKind::ControlOperatorTypeMismatch,
+            &format!(
               "target for .lt, .gt, .ge or .le operator must be a numerical data type, got {}",
               target
-            ));
-            Ok(())
-          }
+            ),
+          ))),
         }
       }
       ControlOperator::SIZE => match target {
@@ -1200,7 +1411,7 @@ where
         Ok(())
       }
       ControlOperator::DEFAULT => {
-        self.ctrl = Some(ctrl);
+        // Any present value is validated against the target as is
         let error_count = self.errors.len();
         self.visit_type2(target)?;
         if self.errors.len() != error_count {
@@ -1487,10 +1698,7 @@ where
               }
               self.ctrl = None;
             } else {
-              self
-                .disabled_features
-                .get_or_insert(vec![value.to_string()])
-                .push(value.to_string());
+              self.add_error(format!("feature \"{}\" is not enabled", value));
             }
           } else if let Some(Type2::UTF8ByteString { value, .. }) = tv {
             let value = std::str::from_utf8(value).map_err(Error::UTF8Parsing)?;
@@ -1502,12 +1710,13 @@ where
               }
               self.ctrl = None;
             } else {
-              self
-                .disabled_features
-                .get_or_insert(vec![value.to_string()])
-                .push(value.to_string());
+              self.add_error(format!("feature \"{}\" is not enabled", value));
             }
           }
+        } else {
+          // Without a configured feature set, the target is validated as if
+          // the feature were enabled
+          self.visit_type2(target)?;
         }
 
         self.ctrl = None;
@@ -1530,10 +1739,7 @@ where
               }
               self.ctrl = None;
             } else {
-              self
-                .disabled_features
-                .get_or_insert(vec![value.to_string()])
-                .push(value.to_string());
+              self.add_error(format!("feature \"{}\" is not enabled", value));
             }
           } else if let Some(Type2::UTF8ByteString { value, .. }) = tv {
             let value = std::str::from_utf8(value).map_err(Error::UTF8Parsing)?;
@@ -1545,12 +1751,32 @@ where
               }
               self.ctrl = None;
             } else {
-              self
-                .disabled_features
-                .get_or_insert(vec![value.to_string()])
-                .push(value.to_string());
+              self.add_error(format!("feature \"{}\" is not enabled", value));
             }
           }
+        } else {
+          // Without a configured feature set, the target is validated as if
+          // the feature were enabled
+          self.visit_type2(target)?;
+        }
+
+        self.ctrl = None;
+
+        Ok(())
+      }
+      #[cfg(feature = "extensions")]
+      ControlOperator::JOIN => {
+        self.ctrl = Some(ctrl);
+
+        let error = match &self.cbor {
+          Value::Text(s) => validate_join_control(self.cddl, target, controller, s).err(),
+          _ => Some(format!(
+            ".join control can only be matched against a CBOR text string, got {:?}",
+            self.cbor
+          )),
+        };
+        if let Some(e) = error {
+          self.add_error(e);
         }
 
         self.ctrl = None;
@@ -1563,8 +1789,16 @@ where
   fn visit_type2(&mut self, t2: &Type2<'a>) -> visitor::Result<Error<T>> {
     if matches!(self.ctrl, Some(ControlOperator::CBOR)) {
       if let Value::Bytes(b) = &self.cbor {
-        let value = ciborium::de::from_reader(&b[..]);
+        let mut bytes = &b[..];
+        let value = ciborium::de::from_reader::<Value, _>(&mut bytes);
         match value {
+          Ok(_) if !bytes.is_empty() => {
+            let trailing = bytes.len();
+            self.add_error(format!(
+              "embedded CBOR must be a single data item, got {} trailing bytes",
+              trailing
+            ));
+          }
           Ok(value) => {
             let current_location = self.cbor_location.clone();
 
@@ -1595,31 +1829,30 @@ where
             self.add_error(format!("error decoding embedded CBOR, {}", e));
           }
         }
+      } else {
+        self.add_error(format!(
+          ".cbor control can only be matched against a CBOR byte string, got {:?}",
+          self.cbor
+        ));
       }
 
       return Ok(());
     } else if matches!(self.ctrl, Some(ControlOperator::CBORSEQ)) {
       if let Value::Bytes(b) = &self.cbor {
-        let value = ciborium::de::from_reader(&b[..]);
-        match value {
-          Ok(Value::Array(_)) => {
+        // The data items of the sequence are validated as the items of an
+        // array
+        match cbor_sequence_from_slice(b) {
+          Ok(items) => {
             let current_location = self.cbor_location.clone();
+            let value = Value::Array(items);
 
             #[cfg(all(feature = "additional-controls", target_arch = "wasm32"))]
-            let mut cv = CBORValidator::new(
-              self.cddl,
-              value.unwrap_or(Value::Null),
-              self.enabled_features.clone(),
-            );
+            let mut cv = CBORValidator::new(self.cddl, value, self.enabled_features.clone());
             #[cfg(all(feature = "additional-controls", not(target_arch = "wasm32")))]
-            let mut cv = CBORValidator::new(
-              self.cddl,
-              value.unwrap_or(Value::Null),
-              self.enabled_features,
-            );
+            let mut cv = CBORValidator::new(self.cddl, value, self.enabled_features);
 
             #[cfg(not(feature = "additional-controls"))]
-            let mut cv = CBORValidator::new(self.cddl, value.unwrap_or(Value::Null));
+            let mut cv = CBORValidator::new(self.cddl, value);
 
             cv.generic_rules = self.generic_rules.clone();
             cv.eval_generic_rule = self.eval_generic_rule;
@@ -1637,13 +1870,14 @@ where
             self.errors.append(&mut cv.errors);
           }
           Err(e) => {
-            self.add_error(format!("error decoding embedded CBOR, {}", e));
+            self.add_error(format!("error decoding embedded CBOR sequence, {}", e));
           }
-          Ok(v) => self.add_error(format!(
-            "embedded CBOR must be a CBOR sequence, got {:?}",
-            v
-          )),
         }
+      } else {
+        self.add_error(format!(
+          ".cborseq control can only be matched against a CBOR byte string, got {:?}",
+          self.cbor
+        ));
       }
 
       return Ok(());
@@ -1709,7 +1943,7 @@ where
           self.cut_value = None;
           Ok(())
         }
-        Value::Array(_) => self.validate_array_items(&ArrayItemToken::Group(group)),
+        Value::Array(_) => self.validate_array_items(&ArrayItemToken::Map(t2)),
         _ => {
           self.add_error(format!("expected map object {}, got {:?}", t2, self.cbor));
           Ok(())
@@ -1720,15 +1954,30 @@ where
           if group.group_choices.len() == 1
             && group.group_choices[0].group_entries.is_empty()
             && !a.is_empty()
-            && !matches!(
-              self.ctrl,
-              Some(ControlOperator::NE) | Some(ControlOperator::DEFAULT)
-            )
+            && !matches!(self.ctrl, Some(ControlOperator::NE))
           {
             self.add_error(format!("expected empty array, got {:?}", self.cbor));
             return Ok(());
           }
 
+          if group
+            .group_choices
+            .iter()
+            .all(|gc| is_positional_array_group_choice(self.cddl, gc))
+          {
+            let initial_error_count = self.errors.len();
+            for gc in group.group_choices.iter() {
+              let error_count = self.errors.len();
+              self.validate_array_items_positionally::<T>(gc)?;
+              if self.errors.len() == error_count {
+                self.errors.truncate(initial_error_count);
+                break;
+              }
+            }
+
+            return Ok(());
+          }
+
           self.entry_counts = Some(entry_counts_from_group(self.cddl, group));
           self.visit_group(group)?;
           self.entry_counts = None;
@@ -1790,7 +2039,7 @@ where
           Ok(())
         }
         _ => {
-          self.add_error(format!("expected array type, got {:?}", self.cbor));
+          self.add_error(format!("expected array type {}, got {:?}", t2, self.cbor));
           Ok(())
         }
       },
@@ -1801,6 +2050,11 @@ where
       } => {
         if let Some(ga) = generic_args {
           if let Some(rule) = rule_from_ident(self.cddl, ident) {
+            if let Some(reason) = generic_arity_mismatch(rule, ga) {
+              self.add_error(reason);
+              return Ok(());
+            }
+
             if let Some(gr) = self
               .generic_rules
               .iter_mut()
@@ -1845,16 +2099,18 @@ where
           return Ok(());
         }
 
+        let is_group_to_choice_enum = self.is_group_to_choice_enum;
         self.is_group_to_choice_enum = true;
         self.visit_identifier(ident)?;
-        self.is_group_to_choice_enum = false;
+        self.is_group_to_choice_enum = is_group_to_choice_enum;
 
         Ok(())
       }
       Type2::ChoiceFromInlineGroup { group, .. } => {
+        let is_group_to_choice_enum = self.is_group_to_choice_enum;
         self.is_group_to_choice_enum = true;
         self.visit_group(group)?;
-        self.is_group_to_choice_enum = false;
+        self.is_group_to_choice_enum = is_group_to_choice_enum;
         Ok(())
       }
       Type2::Typename {
@@ -1864,6 +2120,11 @@ where
       } => {
         if let Some(ga) = generic_args {
           if let Some(rule) = rule_from_ident(self.cddl, ident) {
+            if let Some(reason) = generic_arity_mismatch(rule, ga) {
+              self.add_error(reason);
+              return Ok(());
+            }
+
             if let Some(gr) = self
               .generic_rules
               .iter_mut()
@@ -1904,6 +2165,27 @@ where
           self.is_multi_type_choice = true;
         }
 
+        // A socket or type extended with `/=` is the choice of its base
+        // definition, if any, and all of its alternates
+        if let Some(choices) = extended_type_choices(self.cddl, ident, &type_choice_alternates) {
+          if self.visited_rules.contains(&ident.ident) {
+            self.add_error(format!("cyclic rule reference: {}", ident));
+            return Ok(());
+          }
+
+          let t = Type {
+            type_choices: choices,
+            #[cfg(feature = "ast-span")]
+            span: ident.span,
+          };
+
+          self.visited_rules.push(ident.ident);
+          let result = self.visit_type(&t);
+          self.visited_rules.pop();
+
+          return result;
+        }
+
         let error_count = self.errors.len();
         for t in type_choice_alternates {
           let cur_errors = self.errors.len();
@@ -1928,6 +2210,9 @@ where
       Type2::B16ByteString { value, .. } => {
         self.visit_value(&token::Value::BYTE(ByteValue::B16(value.clone())))
       }
+      Type2::B64ByteString { value, .. } => {
+        self.visit_value(&token::Value::BYTE(ByteValue::B64(value.clone())))
+      }
       Type2::ParenthesizedType { pt, .. } => self.visit_type(pt),
       Type2::Unwrap {
         ident,
@@ -1943,6 +2228,11 @@ where
 
         if let Some(ga) = generic_args {
           if let Some(rule) = unwrap_rule_from_ident(self.cddl, ident) {
+            if let Some(reason) = generic_arity_mismatch(rule, ga) {
+              self.add_error(reason);
+              return Ok(());
+            }
+
             if let Some(gr) = self
               .generic_rules
               .iter_mut()
@@ -1982,15 +2272,14 @@ where
           return self.visit_rule(rule);
         }
 
-        self.add_error(format!(
-          "cannot unwrap identifier {}, rule not found",
-          ident
-        ));
-
-        Ok(())
+        Err(Error::Syntax(SyntaxError::new(
+          SyntaxErrorKind::UndefinedRule,
+          &format!("cannot unwrap identifier {}, rule not found", ident),
+        )))
       }
       Type2::TaggedData { tag, t, .. } => match &self.cbor {
         Value::Tag(actual_tag, value) => {
+          // Without a tag number, #6(type) matches data with any tag
           if let Some(tag) = tag {
             if *tag as u64 != *actual_tag {
               self.add_error(format!(
@@ -1999,12 +2288,6 @@ where
               ));
               return Ok(());
             }
-          } else if *actual_tag > 0 {
-            self.add_error(format!(
-              "expected tagged data #6({}), got {:?}",
-              t, self.cbor
-            ));
-            return Ok(());
           }
 
           #[cfg(all(feature = "additional-controls", target_arch = "wasm32"))]
@@ -2088,8 +2371,9 @@ where
               }
             },
             _ => self.add_error(format!(
-              "expected major type {} with constraint {:?}, got {:?}",
-              mt, constraint, self.cbor
+              "expected major type {}, got {:?}",
+              major_type_notation(*mt, *constraint),
+              self.cbor
             )),
           }
 
@@ -2106,8 +2390,9 @@ where
               _ => return Ok(()),
             },
             _ => self.add_error(format!(
-              "expected major type {} with constraint {:?}, got {:?}",
-              mt, constraint, self.cbor
+              "expected major type {}, got {:?}",
+              major_type_notation(*mt, *constraint),
+              self.cbor
             )),
           }
 
@@ -2124,8 +2409,9 @@ where
               _ => return Ok(()),
             },
             _ => self.add_error(format!(
-              "expected major type {} with constraint {:?}, got {:?}",
-              mt, constraint, self.cbor
+              "expected major type {}, got {:?}",
+              major_type_notation(*mt, *constraint),
+              self.cbor
             )),
           }
 
@@ -2142,8 +2428,9 @@ where
               _ => return Ok(()),
             },
             _ => self.add_error(format!(
-              "expected major type {} with constraint {:?}, got {:?}",
-              mt, constraint, self.cbor
+              "expected major type {}, got {:?}",
+              major_type_notation(*mt, *constraint),
+              self.cbor
             )),
           }
 
@@ -2160,37 +2447,81 @@ where
               _ => return Ok(()),
             },
             _ => self.add_error(format!(
-              "expected major type {} with constraint {:?}, got {:?}",
-              mt, constraint, self.cbor
+              "expected major type {}, got {:?}",
+              major_type_notation(*mt, *constraint),
+              self.cbor
             )),
           }
 
           Ok(())
         }
-        Value::Float(_f) => {
+        Value::Tag(tag, _) => {
           match mt {
-            7u8 => match constraint {
-              Some(_c) => unimplemented!(),
+            6u8 => match constraint {
+              Some(c) if *c as u64 == *tag => return Ok(()),
+              Some(c) => self.add_error(format!(
+                "expected tagged data type with tag {} (#{}.{}), got {:?}",
+                c, mt, c, self.cbor
+              )),
               _ => return Ok(()),
             },
             _ => self.add_error(format!(
-              "expected major type {} with constraint {:?}, got {:?}",
-              mt, constraint, self.cbor
+              "expected major type {}, got {:?}",
+              major_type_notation(*mt, *constraint),
+              self.cbor
             )),
           }
 
           Ok(())
         }
-        _ => {
-          if let Some(constraint) = constraint {
-            self.add_error(format!(
-              "expected major type #{}.{}, got {:?}",
-              mt, constraint, self.cbor
-            ));
-          } else {
-            self.add_error(format!("expected major type #{}, got {:?}", mt, self.cbor));
+        Value::Float(_f) => {
+          match mt {
+            7u8 => match constraint {
+              // Decoded floats no longer carry their encoded width
+              Some(25..=27) | None => return Ok(()),
+              Some(_) => self.add_error(format!(
+                "expected major type {}, got {:?}",
+                major_type_notation(*mt, *constraint),
+                self.cbor
+              )),
+            },
+            _ => self.add_error(format!(
+              "expected major type {}, got {:?}",
+              major_type_notation(*mt, *constraint),
+              self.cbor
+            )),
           }
 
+          Ok(())
+        }
+        // Simple values: false (#7.20), true (#7.21), null (#7.22) and
+        // undefined (#7.23), the latter two both decoding to null
+        Value::Bool(_) | Value::Null => {
+          let simple_value = match &self.cbor {
+            Value::Bool(false) => 20,
+            Value::Bool(true) => 21,
+            _ => 22,
+          };
+
+          match (mt, constraint) {
+            (7u8, None) => (),
+            (7u8, Some(c)) if *c == simple_value || (simple_value == 22 && *c == 23) => (),
+            _ => self.add_error(format!(
+              "expected major type {}, got {:?}",
+              major_type_notation(*mt, *constraint),
+              self.cbor
+            )),
+          }
+
+          Ok(())
+        }
+        _ => {
+          self.add_error(format!(
+            "expected major type {}, got {:?}",
+            major_type_notation(*mt, *constraint),
+            self.cbor
+          ));
+
           Ok(())
         }
       },
@@ -2198,13 +2529,6 @@ where
       Type2::Any { .. } => Ok(()),
       #[cfg(not(feature = "ast-span"))]
       Type2::Any {} => Ok(()),
-      _ => {
-        self.add_error(format!(
-          "unsupported data type for validating cbor, got {}",
-          t2
-        ));
-        Ok(())
-      }
     }
   }
 
@@ -2230,7 +2554,16 @@ where
     // member key
     if !self.is_colon_shortcut_present {
       if let Some(r) = rule_from_ident(self.cddl, ident) {
-        return self.visit_rule(r);
+        if self.visited_rules.contains(&ident.ident) {
+          self.add_error(format!("cyclic rule reference: {}", ident));
+          return Ok(());
+        }
+
+        self.visited_rules.push(ident.ident);
+        let result = self.visit_rule(r);
+        self.visited_rules.pop();
+
+        return result;
       }
     }
 
@@ -2240,6 +2573,22 @@ where
 
     match &self.cbor {
       Value::Null if is_ident_null_data_type(self.cddl, ident) => Ok(()),
+      // undefined (#7.23) decodes to null, so the two can't be told apart
+      Value::Null if is_ident_undefined_data_type(self.cddl, ident) => Ok(()),
+      Value::Integer(_)
+      | Value::Float(_)
+      | Value::Bool(_)
+      | Value::Text(_)
+      | Value::Bytes(_)
+      | Value::Tag(..)
+        if is_ident_undefined_data_type(self.cddl, ident) =>
+      {
+        self.add_error(format!(
+          "expected type {} (simple value 23, which decodes the same as null), got {:?}",
+          ident, self.cbor
+        ));
+        Ok(())
+      }
       Value::Bytes(_) if is_ident_byte_string_data_type(self.cddl, ident) => Ok(()),
       Value::Bool(b) => {
         if is_ident_bool_data_type(self.cddl, ident) {
@@ -2259,13 +2608,17 @@ where
             self.add_error(format!("expected type {}, got {:?}", ident, self.cbor));
           }
 
+          Ok(())
+        } else if is_ident_nint_data_type(self.cddl, ident) {
+          if !i128::from(*i).is_negative() {
+            self.add_error(format!("expected type {}, got {:?}", ident, self.cbor));
+          }
+
           Ok(())
         } else if is_ident_integer_data_type(self.cddl, ident) {
           Ok(())
         } else if is_ident_time_data_type(self.cddl, ident) {
-          if let chrono::LocalResult::None =
-            Utc.timestamp_millis_opt((i128::from(*i) * 1000) as i64)
-          {
+          if !is_valid_unix_timestamp_millis((i128::from(*i) * 1000) as i64) {
             let i = *i;
             self.add_error(format!(
               "expected time data type, invalid UNIX timestamp {:?}",
@@ -2281,9 +2634,18 @@ where
       }
       Value::Float(f) => {
         if is_ident_float_data_type(self.cddl, ident) {
+          if !is_float_representable(ident, *f) {
+            self.add_error(format!(
+              "expected type {}, got {:?} which is not representable as {}",
+              ident, self.cbor, ident
+            ));
+          }
+
+          Ok(())
+        } else if let Token::NUMBER = lookup_ident(ident.ident) {
           Ok(())
         } else if is_ident_time_data_type(self.cddl, ident) {
-          if let chrono::LocalResult::None = Utc.timestamp_millis_opt((*f * 1000f64) as i64) {
+          if !is_valid_unix_timestamp_millis((*f * 1000f64) as i64) {
             let f = *f;
             self.add_error(format!(
               "expected time data type, invalid UNIX timestamp {:?}",
@@ -2299,6 +2661,7 @@ where
       }
       Value::Text(s) => {
         if is_ident_uri_data_type(self.cddl, ident) {
+          #[cfg(feature = "uri")]
           if let Err(e) = uriparse::URI::try_from(&**s) {
             self.add_error(format!("expected URI data type, decoding error: {}", e));
           }
@@ -2310,6 +2673,7 @@ where
             ));
           }
         } else if is_ident_tdate_data_type(self.cddl, ident) {
+          #[cfg(feature = "tdate")]
           if let Err(e) = chrono::DateTime::parse_from_rfc3339(s) {
             self.add_error(format!("expected tdate data type, decoding error: {}", e));
           }
@@ -2325,7 +2689,9 @@ where
         match *tag {
           0 => {
             if is_ident_tdate_data_type(self.cddl, ident) {
+              #[cfg_attr(not(feature = "tdate"), allow(unused_variables))]
               if let Value::Text(value) = value.as_ref() {
+                #[cfg(feature = "tdate")]
                 if let Err(e) = chrono::DateTime::parse_from_rfc3339(value) {
                   self.add_error(format!("expected tdate data type, decoding error: {}", e));
                 }
@@ -2339,18 +2705,14 @@ where
           1 => {
             if is_ident_time_data_type(self.cddl, ident) {
               if let Value::Integer(value) = *value.as_ref() {
-                let dt = Utc.timestamp_opt(value.try_into().unwrap(), 0);
-                if let chrono::LocalResult::None = dt {
+                if !is_valid_unix_timestamp_millis((i128::from(value) * 1000) as i64) {
                   self.add_error(format!(
                     "expected time data type, invalid UNIX timestamp {:?}",
                     self.cbor
                   ));
                 }
               } else if let Value::Float(value) = value.as_ref() {
-                let seconds = value.trunc() as i64;
-                let nanoseconds = (value.fract() * 1e9) as u32;
-                let dt = Utc.timestamp_opt(seconds, nanoseconds);
-                if let chrono::LocalResult::None = dt {
+                if !is_valid_unix_timestamp_millis((value * 1000f64) as i64) {
                   self.add_error(format!(
                     "expected time data type, invalid UNIX timestamp {:?}",
                     self.cbor
@@ -2363,6 +2725,21 @@ where
               self.add_error(format!("expected type {}, got {:?}", ident, self.cbor));
             }
           }
+          32 => {
+            if is_ident_uri_data_type(self.cddl, ident) {
+              #[cfg_attr(not(feature = "uri"), allow(unused_variables))]
+              if let Value::Text(value) = value.as_ref() {
+                #[cfg(feature = "uri")]
+                if let Err(e) = uriparse::URI::try_from(&**value) {
+                  self.add_error(format!("expected URI data type, decoding error: {}", e));
+                }
+              } else {
+                self.add_error(format!("expected type {}, got {:?}", ident, self.cbor));
+              }
+            } else {
+              self.add_error(format!("expected type {}, got {:?}", ident, self.cbor));
+            }
+          }
           _ => (),
         }
 
@@ -2753,7 +3130,7 @@ where
             if let Occur::ZeroOrMore { .. } | Occur::OneOrMore { .. } = occur {
               if let Occur::OneOrMore { .. } = occur {
                 if m.is_empty() {
-                  self.add_error(format!(
+                  self.add_occurrence_error(format!(
                     "map cannot be empty, one or more entries with key type {} required",
                     ident
                   ));
@@ -2766,12 +3143,12 @@ where
                   if let Some(upper) = upper {
                     if values_to_validate.len() < *lower || values_to_validate.len() > *upper {
                       if lower == upper {
-                        self.add_error(format!(
+                        self.add_occurrence_error(format!(
                           "object must contain exactly {} entries of key of type {}",
                           lower, ident,
                         ));
                       } else {
-                        self.add_error(format!(
+                        self.add_occurrence_error(format!(
                           "object must contain between {} and {} entries of key of type {}",
                           lower, upper, ident,
                         ));
@@ -2782,7 +3159,7 @@ where
                   }
 
                   if values_to_validate.len() < *lower {
-                    self.add_error(format!(
+                    self.add_occurrence_error(format!(
                       "object must contain at least {} entries of key of type {}",
                       lower, ident,
                     ));
@@ -2793,7 +3170,7 @@ where
 
                 if let Some(upper) = upper {
                   if values_to_validate.len() > *upper {
-                    self.add_error(format!(
+                    self.add_occurrence_error(format!(
                       "object must contain no more than {} entries of key of type {}",
                       upper, ident,
                     ));
@@ -2810,7 +3187,7 @@ where
             if let Occur::ZeroOrMore {} | Occur::OneOrMore {} = occur {
               if let Occur::OneOrMore {} = occur {
                 if m.is_empty() {
-                  self.add_error(format!(
+                  self.add_occurrence_error(format!(
                     "object cannot be empty, one or more entries with key type {} required",
                     ident
                   ));
@@ -2823,12 +3200,12 @@ where
                   if let Some(upper) = upper {
                     if values_to_validate.len() < *lower || values_to_validate.len() > *upper {
                       if lower == upper {
-                        self.add_error(format!(
+                        self.add_occurrence_error(format!(
                           "object must contain exactly {} entries of key of type {}",
                           lower, ident,
                         ));
                       } else {
-                        self.add_error(format!(
+                        self.add_occurrence_error(format!(
                           "object must contain between {} and {} entries of key of type {}",
                           lower, upper, ident,
                         ));
@@ -2839,7 +3216,7 @@ where
                   }
 
                   if values_to_validate.len() < *lower {
-                    self.add_error(format!(
+                    self.add_occurrence_error(format!(
                       "object must contain at least {} entries of key of type {}",
                       lower, ident,
                     ));
@@ -2850,7 +3227,7 @@ where
 
                 if let Some(upper) = upper {
                   if values_to_validate.len() > *upper {
-                    self.add_error(format!(
+                    self.add_occurrence_error(format!(
                       "object must contain no more than {} entries of key of type {}",
                       upper, ident,
                     ));
@@ -3080,6 +3457,11 @@ where
 
     if let Some(ga) = &entry.generic_args {
       if let Some(rule) = rule_from_ident(self.cddl, &entry.name) {
+        if let Some(reason) = generic_arity_mismatch(rule, ga) {
+          self.add_error(reason);
+          return Ok(());
+        }
+
         if let Some(gr) = self
           .generic_rules
           .iter_mut()
@@ -3179,18 +3561,16 @@ where
     let error: Option<String> = match &self.cbor {
       Value::Integer(i) => match value {
         token::Value::INT(v) => match &self.ctrl {
-          Some(ControlOperator::NE) | Some(ControlOperator::DEFAULT)
-            if i128::from(*i) != *v as i128 =>
-          {
-            None
-          }
-          Some(ControlOperator::LT) if i128::from(*i) < *v as i128 => None,
-          Some(ControlOperator::LE) if i128::from(*i) <= *v as i128 => None,
-          Some(ControlOperator::GT) if i128::from(*i) > *v as i128 => None,
-          Some(ControlOperator::GE) if i128::from(*i) >= *v as i128 => None,
+          Some(
+            ctrl @ (ControlOperator::NE
+            | ControlOperator::LT
+            | ControlOperator::LE
+            | ControlOperator::GT
+            | ControlOperator::GE),
+          ) if compare_numeric(&self.cbor, *ctrl, value) == Some(true) => None,
           #[cfg(feature = "additional-controls")]
           Some(ControlOperator::PLUS) => {
-            if i128::from(*i) == *v as i128 {
+            if compare_numeric(&self.cbor, ControlOperator::EQ, value) == Some(true) {
               None
             } else {
               Some(format!("expected computed .plus value {}, got {:?}", v, i))
@@ -3198,7 +3578,7 @@ where
           }
           #[cfg(feature = "additional-controls")]
           None | Some(ControlOperator::FEATURE) => {
-            if i128::from(*i) == *v as i128 {
+            if compare_numeric(&self.cbor, ControlOperator::EQ, value) == Some(true) {
               None
             } else {
               Some(format!("expected value {}, got {:?}", v, i))
@@ -3206,7 +3586,7 @@ where
           }
           #[cfg(not(feature = "additional-controls"))]
           None => {
-            if i128::from(*i) == *v as i128 {
+            if compare_numeric(&self.cbor, ControlOperator::EQ, value) == Some(true) {
               None
             } else {
               Some(format!("expected value {}, got {:?}", v, i))
@@ -3220,15 +3600,13 @@ where
           )),
         },
         token::Value::UINT(v) => match &self.ctrl {
-          Some(ControlOperator::NE) | Some(ControlOperator::DEFAULT)
-            if i128::from(*i) != *v as i128 =>
-          {
-            None
-          }
-          Some(ControlOperator::LT) if i128::from(*i) < *v as i128 => None,
-          Some(ControlOperator::LE) if i128::from(*i) <= *v as i128 => None,
-          Some(ControlOperator::GT) if i128::from(*i) > *v as i128 => None,
-          Some(ControlOperator::GE) if i128::from(*i) >= *v as i128 => None,
+          Some(
+            ctrl @ (ControlOperator::NE
+            | ControlOperator::LT
+            | ControlOperator::LE
+            | ControlOperator::GT
+            | ControlOperator::GE),
+          ) if compare_numeric(&self.cbor, *ctrl, value) == Some(true) => None,
           Some(ControlOperator::SIZE) => match 256i128.checked_pow(*v as u32) {
             Some(n) if i128::from(*i) < n => None,
             _ => Some(format!("expected value .size {}, got {:?}", v, i)),
@@ -3246,7 +3624,7 @@ where
           }
           #[cfg(feature = "additional-controls")]
           Some(ControlOperator::PLUS) => {
-            if i128::from(*i) == *v as i128 {
+            if compare_numeric(&self.cbor, ControlOperator::EQ, value) == Some(true) {
               None
             } else {
               Some(format!("expected computed .plus value {}, got {:?}", v, i))
@@ -3254,7 +3632,7 @@ where
           }
           #[cfg(feature = "additional-controls")]
           None | Some(ControlOperator::FEATURE) => {
-            if i128::from(*i) == *v as i128 {
+            if compare_numeric(&self.cbor, ControlOperator::EQ, value) == Some(true) {
               None
             } else {
               Some(format!("expected value {}, got {:?}", v, i))
@@ -3262,7 +3640,7 @@ where
           }
           #[cfg(not(feature = "additional-controls"))]
           None => {
-            if i128::from(*i) == *v as i128 {
+            if compare_numeric(&self.cbor, ControlOperator::EQ, value) == Some(true) {
               None
             } else {
               Some(format!("expected value {}, got {:?}", v, i))
@@ -3280,18 +3658,16 @@ where
       },
       Value::Float(f) => match value {
         token::Value::FLOAT(v) => match &self.ctrl {
-          Some(ControlOperator::NE) | Some(ControlOperator::DEFAULT)
-            if (*f - *v).abs() > std::f64::EPSILON =>
-          {
-            None
-          }
-          Some(ControlOperator::LT) if *f < *v => None,
-          Some(ControlOperator::LE) if *f <= *v => None,
-          Some(ControlOperator::GT) if *f > *v => None,
-          Some(ControlOperator::GE) if *f >= *v => None,
+          Some(
+            ctrl @ (ControlOperator::NE
+            | ControlOperator::LT
+            | ControlOperator::LE
+            | ControlOperator::GT
+            | ControlOperator::GE),
+          ) if compare_numeric(&self.cbor, *ctrl, value) == Some(true) => None,
           #[cfg(feature = "additional-controls")]
           Some(ControlOperator::PLUS) => {
-            if (*f - *v).abs() < std::f64::EPSILON {
+            if compare_numeric(&self.cbor, ControlOperator::EQ, value) == Some(true) {
               None
             } else {
               Some(format!("expected computed .plus value {}, got {:?}", v, f))
@@ -3299,7 +3675,7 @@ where
           }
           #[cfg(feature = "additional-controls")]
           None | Some(ControlOperator::FEATURE) => {
-            if (*f - *v).abs() < std::f64::EPSILON {
+            if compare_numeric(&self.cbor, ControlOperator::EQ, value) == Some(true) {
               None
             } else {
               Some(format!("expected value {}, got {:?}", v, f))
@@ -3307,7 +3683,7 @@ where
           }
           #[cfg(not(feature = "additional-controls"))]
           None => {
-            if (*f - *v).abs() < std::f64::EPSILON {
+            if compare_numeric(&self.cbor, ControlOperator::EQ, value) == Some(true) {
               None
             } else {
               Some(format!("expected value {}, got {:?}", v, f))
@@ -3324,7 +3700,7 @@ where
       },
       Value::Text(s) => match value {
         token::Value::TEXT(t) => match &self.ctrl {
-          Some(ControlOperator::NE) | Some(ControlOperator::DEFAULT) => {
+          Some(ControlOperator::NE) => {
             if s != t {
               None
             } else {
@@ -3341,7 +3717,13 @@ where
                   .as_str()
                   .ok_or_else(|| Error::from_validator(self, "malformed regex".to_string()))?,
               )
-              .ok_or_else(|| Error::from_validator(self, "malformed regex".to_string()))?,
+              .map(|re| anchor_regex(&re))
+              .ok_or_else(|| {
+                Error::Syntax(SyntaxError::new(
+                  SyntaxErrorKind::RegexLookaround,
+                  "regex uses unsupported lookaround",
+                ))
+              })?,
             )
             .map_err(|e| Error::from_validator(self, e.to_string()))?;
 
@@ -3385,7 +3767,12 @@ where
             if s.len() == *u {
               None
             } else {
-              Some(format!("expected \"{}\" .size {}, got {}", s, u, s.len()))
+              Some(format!(
+                "expected \"{}\" .size {} (UTF-8 bytes), got {} bytes",
+                s,
+                u,
+                s.len()
+              ))
             }
           }
           _ => Some(format!("expected {}, got {}", u, s)),
@@ -3396,6 +3783,21 @@ where
         _ => Some(format!("expected {}, got \"{}\"", value, s)),
       },
       Value::Bytes(b) => match value {
+        token::Value::BYTE(bv) if self.ctrl.is_none() => {
+          let expected = match bv {
+            ByteValue::UTF8(utf8bv) => utf8bv.to_vec(),
+            ByteValue::B16(b16bv) => base16::decode(b16bv).map_err(Error::Base16Decoding)?,
+            ByteValue::B64(b64bv) => data_encoding::BASE64URL
+              .decode(b64bv)
+              .map_err(Error::Base64Decoding)?,
+          };
+
+          if &expected == b {
+            None
+          } else {
+            Some(format!("expected value {}, got {:?}", bv, b))
+          }
+        }
         token::Value::UINT(v) => match &self.ctrl {
           Some(ControlOperator::SIZE) => {
             if b.len() == *v {
@@ -3555,10 +3957,11 @@ where
         {
           self.advance_to_next_entry = true;
           None
-        } else if let Some(ControlOperator::NE) | Some(ControlOperator::DEFAULT) = &self.ctrl {
+        } else if let Some(ControlOperator::NE) = &self.ctrl {
           None
         } else {
-          Some(format!("object missing key: \"{}\"", value))
+          self.add_occurrence_error(format!("object missing key: {}", value));
+          None
         }
 
         #[cfg(not(feature = "ast-span"))]
@@ -3576,10 +3979,11 @@ where
         {
           self.advance_to_next_entry = true;
           None
-        } else if let Some(Token::NE) | Some(Token::DEFAULT) = &self.ctrl {
+        } else if let Some(ControlOperator::NE) = &self.ctrl {
           None
         } else {
-          Some(format!("object missing key: \"{}\"", value))
+          self.add_occurrence_error(format!("object missing key: {}", value));
+          None
         }
       }
       _ => Some(format!("expected {}, got {:?}", value, self.cbor)),
@@ -3614,6 +4018,27 @@ pub fn token_value_into_cbor_value(value: token::Value) -> ciborium::value::Valu
   }
 }
 
+/// Formats a major type and its optional constraint as written in CDDL, e.g.
+/// `#6.32`
+fn major_type_notation(mt: u8, constraint: Option<usize>) -> String {
+  match constraint {
+    Some(c) => format!("#{}.{}", mt, c),
+    None => format!("#{}", mt),
+  }
+}
+
+/// Decodes a CBOR sequence (RFC 8742) into its individual data items
+fn cbor_sequence_from_slice(
+  mut bytes: &[u8],
+) -> std::result::Result<Vec<Value>, ciborium::de::Error<std::io::Error>> {
+  let mut items = Vec::new();
+  while !bytes.is_empty() {
+    items.push(ciborium::de::from_reader(&mut bytes)?);
+  }
+
+  Ok(items)
+}
+
 #[cfg(test)]
 #[cfg(not(target_arch = "wasm32"))]
 mod tests {
@@ -3621,6 +4046,27 @@ mod tests {
   use ciborium::cbor;
   use indoc::indoc;
 
+  /// Assert that validation failed with errors reported at the given CBOR
+  /// locations with reasons starting with the given text, leaving out the
+  /// debug representation of the offending value
+  fn assert_validation_errors(result: Result<std::io::Error>, expected: &[(&str, &str)]) {
+    match result {
+      Err(Error::Validation(errors)) => {
+        assert_eq!(errors.len(), expected.len(), "{:?}", errors);
+        for (error, (location, reason)) in errors.iter().zip(expected) {
+          assert_eq!(error.cbor_location, *location);
+          assert!(
+            error.reason.starts_with(reason),
+            "{:?} doesn't start with {:?}",
+            error.reason,
+            reason
+          );
+        }
+      }
+      r => panic!("expected validation errors, got {:?}", r),
+    }
+  }
+
   #[cfg(not(feature = "additional-controls"))]
   #[test]
   fn validate() -> std::result::Result<(), Box<dyn std::error::Error>> {
@@ -3903,4 +4349,757 @@ mod tests {
 
     Ok(())
   }
+
+  #[cfg(feature = "additional-controls")]
+  #[test]
+  fn validate_no_root_rule() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("entries = (a: int)", true)?;
+    let cbor = ciborium::value::Value::Map(vec![(
+      ciborium::value::Value::Text("a".to_string()),
+      ciborium::value::Value::Integer(1.into()),
+    )]);
+
+    let mut cv = CBORValidator::new(&cddl, cbor, None);
+    assert!(matches!(cv.validate(), Err(Error::NoRootRule)));
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_default_control() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        config = {
+          ? timeout: uint .default 30,
+          ? mode: "fast" .default "fast",
+          ? retries: 3 .default 3,
+        }
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    let entry = |key: &str, value: Value| (Value::Text(key.to_string()), value);
+
+    for cbor in [
+      Value::Map(vec![]),
+      Value::Map(vec![entry("timeout", Value::Integer(5.into()))]),
+      Value::Map(vec![entry("mode", Value::Text("fast".to_string()))]),
+      Value::Map(vec![entry("retries", Value::Integer(3.into()))]),
+    ] {
+      let mut cv = CBORValidator::new(&cddl, cbor, None);
+      cv.validate()?;
+    }
+
+    for cbor in [
+      Value::Map(vec![entry("timeout", Value::Text("x".to_string()))]),
+      Value::Map(vec![entry("mode", Value::Text("slow".to_string()))]),
+      Value::Map(vec![entry("retries", Value::Integer(4.into()))]),
+    ] {
+      let mut cv = CBORValidator::new(&cddl, cbor, None);
+      assert!(cv.validate().is_err());
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_float_major_type() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        root = #7.27
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    let mut cv = CBORValidator::new(&cddl, ciborium::value::Value::Float(1.5), None);
+    cv.validate()?;
+
+    let cddl = cddl_from_str("root = #7.20", true).map_err(json::Error::CDDLParsing)?;
+
+    let mut cv = CBORValidator::new(&cddl, ciborium::value::Value::Float(1.5), None);
+    assert!(cv.validate().is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_tagged_data_tag_number() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let tagged = |tag: u64, value: Value| Value::Tag(tag, Box::from(value));
+
+    let cddl = cddl_from_str("link = #6.32(tstr)", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(
+      &cddl,
+      tagged(32, Value::Text("https://example.com".into())),
+      None,
+    )
+    .validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(
+        &cddl,
+        tagged(33, Value::Text("https://example.com".into())),
+        None,
+      )
+      .validate(),
+      &[("", "expected tagged data #6.32(tstr)")],
+    );
+    assert_validation_errors(
+      CBORValidator::new(&cddl, tagged(32, Value::Integer(1.into())), None).validate(),
+      &[("", "expected type tstr")],
+    );
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Text("https://example.com".into()), None).validate(),
+      &[("", "expected tagged data #6.32(tstr)")],
+    );
+
+    let cddl = cddl_from_str("any-tag = #6(tstr)", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, tagged(100, Value::Text("a".into())), None).validate()?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_data_major_type() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let tagged = |tag: u64, value: Value| Value::Tag(tag, Box::from(value));
+
+    let cddl = cddl_from_str("a = #0", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Integer(1.into()), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Integer((-1).into()), None).validate(),
+      &[("", "expected uint data type (#0)")],
+    );
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Text("a".into()), None).validate(),
+      &[("", "expected major type #0")],
+    );
+
+    let cddl = cddl_from_str("a = #1", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Integer((-1).into()), None).validate()?;
+
+    let cddl = cddl_from_str("a = #2", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Bytes(vec![0x01]), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Text("a".into()), None).validate(),
+      &[("", "expected major type #2")],
+    );
+
+    let cddl = cddl_from_str("a = #3", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Text("a".into()), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Null, None).validate(),
+      &[("", "expected major type #3")],
+    );
+
+    let cddl = cddl_from_str("a = #4", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Array(vec![]), None).validate()?;
+
+    let cddl = cddl_from_str("a = #5", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Map(vec![]), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Array(vec![]), None).validate(),
+      &[("", "expected major type #5")],
+    );
+
+    let cddl = cddl_from_str("a = #6", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, tagged(32, Value::Text("a".into())), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Text("a".into()), None).validate(),
+      &[("", "expected major type #6")],
+    );
+
+    let cddl = cddl_from_str("a = #6.32", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, tagged(32, Value::Text("a".into())), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, tagged(33, Value::Text("a".into())), None).validate(),
+      &[("", "expected tagged data type with tag 32 (#6.32)")],
+    );
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Text("a".into()), None).validate(),
+      &[("", "expected major type #6.32")],
+    );
+
+    let cddl = cddl_from_str("a = #7", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Float(1.5), None).validate()?;
+    CBORValidator::new(&cddl, Value::Bool(true), None).validate()?;
+    CBORValidator::new(&cddl, Value::Null, None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Integer(1.into()), None).validate(),
+      &[("", "expected major type #7")],
+    );
+
+    let cddl = cddl_from_str("a = #7.20", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Bool(false), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Bool(true), None).validate(),
+      &[("", "expected major type #7.20")],
+    );
+
+    let cddl = cddl_from_str("a = #7.21", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Bool(true), None).validate()?;
+
+    let cddl = cddl_from_str("a = #7.22", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Null, None).validate()?;
+
+    let cddl = cddl_from_str("a = #7.24", true).map_err(json::Error::CDDLParsing)?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Float(1.5), None).validate(),
+      &[("", "expected major type #7.24")],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_array_upper_bound_occurrence() -> std::result::Result<(), Box<dyn std::error::Error>>
+  {
+    let cddl = cddl_from_str("a = [ *3 int ]", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Array(vec![]), None).validate()?;
+    CBORValidator::new(
+      &cddl,
+      Value::Array(vec![
+        Value::Integer(0.into()),
+        Value::Integer(1.into()),
+        Value::Integer(2.into()),
+      ]),
+      None,
+    )
+    .validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(
+        &cddl,
+        Value::Array(vec![
+          Value::Integer(0.into()),
+          Value::Integer(1.into()),
+          Value::Integer(2.into()),
+          Value::Integer(3.into()),
+        ]),
+        None,
+      )
+      .validate(),
+      &[("", "array must have not more than 3 items")],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_byte_string_values() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("a = h'01020304'", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Bytes(vec![1, 2, 3, 4]), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Bytes(vec![1, 2, 3]), None).validate(),
+      &[("", "expected value h'01020304'")],
+    );
+
+    let cddl = cddl_from_str("a = b64'AQIDBA=='", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Bytes(vec![1, 2, 3, 4]), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Bytes(vec![1, 2, 3, 5]), None).validate(),
+      &[("", "expected value b64'AQIDBA=='")],
+    );
+
+    let cddl = cddl_from_str("a = 'abc'", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Bytes(b"abc".to_vec()), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Bytes(b"abd".to_vec()), None).validate(),
+      &[("", "expected value 'abc'")],
+    );
+
+    let cddl = cddl_from_str("a = bstr .size 4", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Bytes(vec![1, 2, 3, 4]), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Bytes(vec![1, 2, 3]), None).validate(),
+      &[("", r#"expected "[1, 2, 3]" .size 4"#)],
+    );
+
+    let cddl = cddl_from_str("a = bstr .size (2..4)", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Bytes(vec![1, 2, 3]), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Bytes(vec![1]), None).validate(),
+      &[(
+        "",
+        r#"expected "[1]" byte string length to be in the range 2 <= value <= 4"#,
+      )],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_numeric_data_types() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("a = uint", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Integer(5.into()), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Integer((-5).into()), None).validate(),
+      &[("", "expected type uint")],
+    );
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Float(5.0), None).validate(),
+      &[("", "expected type uint")],
+    );
+
+    let cddl = cddl_from_str("a = nint", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Integer((-5).into()), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Integer(5.into()), None).validate(),
+      &[("", "expected type nint")],
+    );
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Float(-5.0), None).validate(),
+      &[("", "expected type nint")],
+    );
+
+    let cddl = cddl_from_str("a = int", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Integer((-5).into()), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Float(1.5), None).validate(),
+      &[("", "expected type int")],
+    );
+
+    let cddl = cddl_from_str("a = float16", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Float(1.5), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Float(1e300), None).validate(),
+      &[("", "expected type float16")],
+    );
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Float(0.1), None).validate(),
+      &[("", "expected type float16")],
+    );
+
+    let cddl = cddl_from_str("a = float32", true).map_err(json::Error::CDDLParsing)?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Float(1e300), None).validate(),
+      &[("", "expected type float32")],
+    );
+
+    let cddl = cddl_from_str("a = float64", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Float(1e300), None).validate()?;
+
+    let cddl = cddl_from_str("a = float", true).map_err(json::Error::CDDLParsing)?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Integer(5.into()), None).validate(),
+      &[("", "expected type float")],
+    );
+
+    let cddl = cddl_from_str("a = number", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Float(1.5), None).validate()?;
+    CBORValidator::new(&cddl, Value::Integer(5.into()), None).validate()?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_exclusive_range_bounds() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("a = 3 ... 5", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Integer(3.into()), None).validate()?;
+    CBORValidator::new(&cddl, Value::Integer(4.into()), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Integer(5.into()), None).validate(),
+      &[("", "expected uint to be in range 3 <= value < 5")],
+    );
+
+    let cddl = cddl_from_str("a = -5 ... -3", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Integer((-5).into()), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Integer((-3).into()), None).validate(),
+      &[("", "expected integer to be in range -5 <= value < -3")],
+    );
+
+    let cddl = cddl_from_str("a = 3.0 ... 5.0", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Float(3.0), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Float(5.0), None).validate(),
+      &[("", "expected float to be in range 3 <= value < 5")],
+    );
+
+    let cddl = cddl_from_str("a = 3 ... 5.0", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Float(3.0), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Float(5.0), None).validate(),
+      &[("", "expected number to be in range 3 <= value < 5.0")],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_socket_alternates() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        a = $ext
+        $ext /= tstr
+        $ext /= int
+      "#
+    );
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Integer(1.into()), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Bool(true), None).validate(),
+      &[("", "expected type tstr"), ("", "expected type int")],
+    );
+
+    let cddl = indoc!(
+      r#"
+        a = [* $ext]
+        $ext /= tstr
+        $ext /= int
+      "#
+    );
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(
+      &cddl,
+      Value::Array(vec![Value::Text("x".into()), Value::Integer(1.into())]),
+      None,
+    )
+    .validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(
+        &cddl,
+        Value::Array(vec![Value::Text("x".into()), Value::Bool(true)]),
+        None,
+      )
+      .validate(),
+      &[("/1", "expected type tstr"), ("/1", "expected type int")],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_array_of_bool_literals() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("a = [* true]", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(
+      &cddl,
+      Value::Array(vec![Value::Bool(true), Value::Bool(true)]),
+      None,
+    )
+    .validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(
+        &cddl,
+        Value::Array(vec![Value::Bool(true), Value::Bool(false)]),
+        None,
+      )
+      .validate(),
+      &[("/1", "expected type true")],
+    );
+
+    let cddl = cddl_from_str("a = [true, false]", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(
+      &cddl,
+      Value::Array(vec![Value::Bool(true), Value::Bool(false)]),
+      None,
+    )
+    .validate()?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_integer_map_keys() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl =
+      cddl_from_str("x = { 1 => tstr, ? -2 => int }", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(
+      &cddl,
+      Value::Map(vec![(Value::Integer(1.into()), Value::Text("x".into()))]),
+      None,
+    )
+    .validate()?;
+    CBORValidator::new(
+      &cddl,
+      Value::Map(vec![
+        (Value::Integer(1.into()), Value::Text("x".into())),
+        (Value::Integer((-2).into()), Value::Integer(3.into())),
+      ]),
+      None,
+    )
+    .validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(
+        &cddl,
+        Value::Map(vec![(Value::Integer(1.into()), Value::Integer(1.into()))]),
+        None,
+      )
+      .validate(),
+      &[("/1", "expected type tstr")],
+    );
+    assert_validation_errors(
+      CBORValidator::new(
+        &cddl,
+        Value::Map(vec![(Value::Text("1".into()), Value::Text("x".into()))]),
+        None,
+      )
+      .validate(),
+      &[("", "object missing key: 1")],
+    );
+    assert_validation_errors(
+      CBORValidator::new(
+        &cddl,
+        Value::Map(vec![(Value::Integer(2.into()), Value::Text("x".into()))]),
+        None,
+      )
+      .validate(),
+      &[("", "object missing key: 1")],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "uri")]
+  fn validate_uri() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("a = uri", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(
+      &cddl,
+      Value::Tag(32, Box::new(Value::Text("https://example.com".into()))),
+      None,
+    )
+    .validate()?;
+    CBORValidator::new(&cddl, Value::Text("https://example.com".into()), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(
+        &cddl,
+        Value::Tag(32, Box::new(Value::Text("not a uri".into()))),
+        None,
+      )
+      .validate(),
+      &[(
+        "",
+        "expected URI data type, decoding error: invalid path character",
+      )],
+    );
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Text("not a uri".into()), None).validate(),
+      &[(
+        "",
+        "expected URI data type, decoding error: invalid path character",
+      )],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_simple_prelude_types() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("a = nil", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Null, None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Bool(false), None).validate(),
+      &[("", "expected type nil")],
+    );
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Integer(0.into()), None).validate(),
+      &[("", "expected type nil")],
+    );
+
+    let cddl = cddl_from_str("a = null", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Null, None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Bool(false), None).validate(),
+      &[("", "expected type null")],
+    );
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Integer(0.into()), None).validate(),
+      &[("", "expected type null")],
+    );
+
+    let cddl = cddl_from_str("a = bool", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Bool(true), None).validate()?;
+    CBORValidator::new(&cddl, Value::Bool(false), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Null, None).validate(),
+      &[("", "expected type bool")],
+    );
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Integer(0.into()), None).validate(),
+      &[("", "expected type bool")],
+    );
+
+    let cddl = cddl_from_str("a = true", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Bool(true), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Bool(false), None).validate(),
+      &[("", "expected type true")],
+    );
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Null, None).validate(),
+      &[("", "expected type true")],
+    );
+
+    let cddl = cddl_from_str("a = false", true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Bool(false), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Bool(true), None).validate(),
+      &[("", "expected type false")],
+    );
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Null, None).validate(),
+      &[("", "expected type false")],
+    );
+
+    let cddl = cddl_from_str("a = { b: nil, c: true }", true).map_err(json::Error::CDDLParsing)?;
+
+    assert_validation_errors(
+      CBORValidator::new(
+        &cddl,
+        Value::Map(vec![
+          (Value::Text("b".into()), Value::Null),
+          (Value::Text("c".into()), Value::Bool(false)),
+        ]),
+        None,
+      )
+      .validate(),
+      &[(r#"/"c""#, "expected type true")],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_regex_lookaround() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    for cddl in [
+      r#"a = tstr .regexp "foo(?=bar)""#,
+      r#"a = tstr .pcre "(?<!x)foo""#,
+    ] {
+      let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+      let mut cv = CBORValidator::new(&cddl, Value::Text("foobar".into()), None);
+      match cv.validate() {
+        Err(Error::Syntax(error)) => {
+          assert_eq!(error.kind, SyntaxErrorKind::RegexLookaround);
+          assert_eq!(error.detail, "regex uses unsupported lookaround");
+        }
+        result => panic!("expected lookaround syntax error, got {:?}", result),
+      }
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn error_predicates() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl =
+      cddl_from_str(r#"a = tstr .pcre "(?<!x)foo""#, true).map_err(json::Error::CDDLParsing)?;
+    let mut cv = CBORValidator::new(&cddl, Value::Text("foo".into()), None);
+    let error = cv.validate().unwrap_err();
+    assert!(error.is_syntax());
+    assert!(!error.is_occurrence());
+    assert!(error.as_target().is_none());
+
+    let cddl = cddl_from_str("a = { b: int }", true).map_err(json::Error::CDDLParsing)?;
+    let mut cv = CBORValidator::new(&cddl, Value::Map(Vec::new()), None);
+    let error = cv.validate().unwrap_err();
+    assert!(!error.is_syntax());
+    assert!(error.is_occurrence());
+
+    let error = validate_cbor_from_slice("a = int", &[0x82, 0x01], None).unwrap_err();
+    assert!(!error.is_syntax());
+    assert!(!error.is_occurrence());
+    assert!(error.as_target().is_some());
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_regex_matches_entire_string() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl =
+      cddl_from_str(r#"a = tstr .pcre "[0-9]+""#, true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Text("12".into()), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Text("a12b".into()), None).validate(),
+      &[("", r#"expected "a12b" to match regex "[0-9]+""#)],
+    );
+
+    let cddl = cddl_from_str(r#"a = tstr .pcre "^a|b""#, true).map_err(json::Error::CDDLParsing)?;
+
+    CBORValidator::new(&cddl, Value::Text("b".into()), None).validate()?;
+
+    assert_validation_errors(
+      CBORValidator::new(&cddl, Value::Text("bzz".into()), None).validate(),
+      &[("", r#"expected "bzz" to match regex "^a|b""#)],
+    );
+
+    Ok(())
+  }
 }
@@ -2,12 +2,16 @@
 #![cfg(not(feature = "lsp"))]
 
 use crate::{
-  ast::{Identifier, Operator, RangeCtlOp, Rule, Type2, CDDL},
-  token::ControlOperator,
+  ast::{GroupChoice, Identifier, Operator, RangeCtlOp, Rule, Type1, Type2, CDDL},
+  validator::{group_rule_from_ident, rule_from_ident, type_choices_from_group_choice},
 };
 
+use std::{collections::HashSet, ops::RangeInclusive};
+
+#[cfg(feature = "extensions")]
+use crate::ast::{Group, GroupEntry};
 #[cfg(feature = "additional-controls")]
-use crate::{ast::Type, validator::ByteValue};
+use crate::{ast::Type, token::ControlOperator, validator::ByteValue};
 #[cfg(feature = "additional-controls")]
 use itertools::Itertools;
 #[cfg(feature = "additional-controls")]
@@ -18,8 +22,23 @@ use pest_meta;
 pub fn string_literals_from_ident<'a>(
   cddl: &'a CDDL<'a>,
   ident: &Identifier,
+) -> Vec<&'a Type2<'a>> {
+  string_literals_from_ident_visited(cddl, ident, &mut HashSet::new())
+}
+
+/// Retrieve all text strings and byte string literals from a given rule
+/// identifier, skipping rules which have already been visited so that cyclic
+/// type choices terminate
+fn string_literals_from_ident_visited<'a: 'b, 'b>(
+  cddl: &'a CDDL<'a>,
+  ident: &Identifier<'b>,
+  visited: &mut HashSet<&'b str>,
 ) -> Vec<&'a Type2<'a>> {
   let mut literals = Vec::new();
+  if !visited.insert(ident.ident) {
+    return literals;
+  }
+
   for r in cddl.rules.iter() {
     if let Rule::Type { rule, .. } = r {
       if rule.name == *ident {
@@ -29,9 +48,9 @@ pub fn string_literals_from_ident<'a>(
             | t @ Type2::UTF8ByteString { .. }
             | t @ Type2::B16ByteString { .. }
             | t @ Type2::B64ByteString { .. } => literals.push(t),
-            Type2::Typename { ident, .. } => {
-              literals.append(&mut string_literals_from_ident(cddl, ident))
-            }
+            Type2::Typename { ident, .. } => literals.append(
+              &mut string_literals_from_ident_visited(cddl, ident, visited),
+            ),
             _ => continue,
           }
         }
@@ -45,7 +64,21 @@ pub fn string_literals_from_ident<'a>(
 /// Retrieve all numeric values from a given rule identifier. Used for
 /// proposed .cat control operator.
 pub fn numeric_values_from_ident<'a>(cddl: &'a CDDL<'a>, ident: &Identifier) -> Vec<&'a Type2<'a>> {
+  numeric_values_from_ident_visited(cddl, ident, &mut HashSet::new())
+}
+
+/// Retrieve all numeric values from a given rule identifier, skipping rules
+/// which have already been visited so that cyclic type choices terminate
+fn numeric_values_from_ident_visited<'a: 'b, 'b>(
+  cddl: &'a CDDL<'a>,
+  ident: &Identifier<'b>,
+  visited: &mut HashSet<&'b str>,
+) -> Vec<&'a Type2<'a>> {
   let mut literals = Vec::new();
+  if !visited.insert(ident.ident) {
+    return literals;
+  }
+
   for r in cddl.rules.iter() {
     if let Rule::Type { rule, .. } = r {
       if rule.name == *ident {
@@ -55,7 +88,7 @@ pub fn numeric_values_from_ident<'a>(cddl: &'a CDDL<'a>, ident: &Identifier) ->
             | t @ Type2::UintValue { .. }
             | t @ Type2::FloatValue { .. } => literals.push(t),
             Type2::Typename { ident, .. } => {
-              literals.append(&mut numeric_values_from_ident(cddl, ident))
+              literals.append(&mut numeric_values_from_ident_visited(cddl, ident, visited))
             }
             _ => continue,
           }
@@ -67,6 +100,108 @@ pub fn numeric_values_from_ident<'a>(cddl: &'a CDDL<'a>, ident: &Identifier) ->
   literals
 }
 
+/// Resolve a range bound to the numeric values it may take. Literal bounds
+/// resolve to themselves and named bounds to every numeric value of the rule
+pub fn range_bound_values<'a: 'b, 'b>(
+  cddl: &'a CDDL<'a>,
+  bound: &'b Type2<'a>,
+) -> Vec<&'b Type2<'a>> {
+  match bound {
+    Type2::Typename { ident, .. } => numeric_values_from_ident(cddl, ident),
+    _ => vec![bound],
+  }
+}
+
+/// Retrieve all bit positions from a given .bits controller as inclusive
+/// ranges. The controller may be a uint value, a range of uint values, a
+/// parenthesized type of either, a choice from a group of uint values or a rule
+/// identifier referencing any of these
+pub fn bit_positions_from_type2<'a>(
+  cddl: &'a CDDL<'a>,
+  controller: &Type2<'a>,
+) -> Result<Vec<RangeInclusive<u64>>, String> {
+  let mut positions = Vec::new();
+
+  match controller {
+    Type2::UintValue { value, .. } => positions.push(*value as u64..=*value as u64),
+    Type2::Typename { ident, .. } => match rule_from_ident(cddl, ident) {
+      Some(Rule::Type { rule, .. }) => {
+        for tc in rule.value.type_choices.iter() {
+          positions.append(&mut bit_positions_from_type1(cddl, &tc.type1)?);
+        }
+      }
+      Some(Rule::Group { rule, .. }) => {
+        for tc in type_choices_from_group_choice(cddl, &GroupChoice::new(vec![rule.entry.clone()]))
+        {
+          positions.append(&mut bit_positions_from_type1(cddl, &tc.type1)?);
+        }
+      }
+      None => return Err(format!(".bits controller rule {} not found", ident)),
+    },
+    Type2::ParenthesizedType { pt, .. } => {
+      for tc in pt.type_choices.iter() {
+        positions.append(&mut bit_positions_from_type1(cddl, &tc.type1)?);
+      }
+    }
+    Type2::ChoiceFromInlineGroup { group, .. } => {
+      for gc in group.group_choices.iter() {
+        for tc in type_choices_from_group_choice(cddl, gc) {
+          positions.append(&mut bit_positions_from_type1(cddl, &tc.type1)?);
+        }
+      }
+    }
+    Type2::ChoiceFromGroup { ident, .. } => match group_rule_from_ident(cddl, ident) {
+      Some(rule) => {
+        for tc in type_choices_from_group_choice(cddl, &GroupChoice::new(vec![rule.entry.clone()]))
+        {
+          positions.append(&mut bit_positions_from_type1(cddl, &tc.type1)?);
+        }
+      }
+      None => return Err(format!(".bits controller group rule {} not found", ident)),
+    },
+    _ => {
+      return Err(format!(
+        "invalid controller used for .bits operation, got {}",
+        controller
+      ))
+    }
+  }
+
+  Ok(positions)
+}
+
+fn bit_positions_from_type1<'a>(
+  cddl: &'a CDDL<'a>,
+  t1: &Type1<'a>,
+) -> Result<Vec<RangeInclusive<u64>>, String> {
+  match &t1.operator {
+    Some(Operator {
+      operator: RangeCtlOp::RangeOp { is_inclusive, .. },
+      type2: upper,
+      ..
+    }) => match (&t1.type2, upper) {
+      (Type2::UintValue { value: l, .. }, Type2::UintValue { value: u, .. }) => {
+        if *is_inclusive {
+          Ok(vec![*l as u64..=*u as u64])
+        } else if *u > 0 {
+          Ok(vec![*l as u64..=*u as u64 - 1])
+        } else {
+          Ok(Vec::new())
+        }
+      }
+      _ => Err(format!(
+        "range used as a .bits controller must have uint bounds, got {}",
+        t1
+      )),
+    },
+    None => bit_positions_from_type2(cddl, &t1.type2),
+    _ => Err(format!(
+      "invalid controller used for .bits operation, got {}",
+      t1
+    )),
+  }
+}
+
 #[cfg(feature = "additional-controls")]
 /// Concatenate target and controller. The Vec return type is to accomodate more
 /// than one type choice in the controller.
@@ -201,7 +336,9 @@ pub fn cat_operation<'a>(
         }
       }
 
-      return Err(format!("invalid target type in {} control operator", ctrl));
+      if literals.is_empty() {
+        return Err(format!("invalid target type in {} control operator", ctrl));
+      }
     }
     Type2::UTF8ByteString { value, .. } => match std::str::from_utf8(value) {
       Ok(value) => match controller {
@@ -590,6 +727,7 @@ fn dedent_bytes(source: &[u8], is_utf8_byte_string: bool) -> Result<Vec<u8>, Str
 
 /// Numeric addition of target and controller. The Vec return type is to
 /// accommodate more than one type choice in the controller
+#[cfg(feature = "additional-controls")]
 pub fn plus_operation<'a>(
   cddl: &'a CDDL<'a>,
   target: &Type2,
@@ -603,10 +741,17 @@ pub fn plus_operation<'a>(
       } => values.push((value + controller).into()),
       Type2::IntValue {
         value: controller, ..
-      } => values.push(((*value as isize + controller) as usize).into()),
+      } => {
+        let sum = *value as isize + controller;
+        if sum.is_negative() {
+          values.push(sum.into())
+        } else {
+          values.push((sum as usize).into())
+        }
+      }
       Type2::FloatValue {
         value: controller, ..
-      } => values.push(((*value as isize + *controller as isize) as usize).into()),
+      } => values.push((*value as f64 + controller).into()),
       Type2::Typename { ident, .. } => {
         let nv = numeric_values_from_ident(cddl, ident);
         if nv.is_empty() {
@@ -616,7 +761,6 @@ pub fn plus_operation<'a>(
           ));
         }
         for controller in nv.iter() {
-          println!("controller: {}", controller);
           values.append(&mut plus_operation(cddl, target, controller)?)
         }
       }
@@ -652,7 +796,7 @@ pub fn plus_operation<'a>(
       } => values.push((value + *controller as isize).into()),
       Type2::FloatValue {
         value: controller, ..
-      } => values.push((value + *controller as isize).into()),
+      } => values.push((*value as f64 + controller).into()),
       Type2::Typename { ident, .. } => {
         let nv = numeric_values_from_ident(cddl, ident);
         if nv.is_empty() {
@@ -662,7 +806,6 @@ pub fn plus_operation<'a>(
           ));
         }
         for controller in nv.iter() {
-          println!("controller: {}", controller);
           values.append(&mut plus_operation(cddl, target, controller)?)
         }
       }
@@ -693,6 +836,9 @@ pub fn plus_operation<'a>(
       Type2::IntValue {
         value: controller, ..
       } => values.push((value + *controller as f64).into()),
+      Type2::UintValue {
+        value: controller, ..
+      } => values.push((value + *controller as f64).into()),
       Type2::FloatValue {
         value: controller, ..
       } => values.push((value + controller).into()),
@@ -705,7 +851,6 @@ pub fn plus_operation<'a>(
           ));
         }
         for controller in nv.iter() {
-          println!("controller: {}", controller);
           values.append(&mut plus_operation(cddl, target, controller)?)
         }
       }
@@ -777,33 +922,89 @@ pub fn plus_operation<'a>(
   Ok(values)
 }
 
+/// Core rules from RFC 5234 Appendix B, made available to every .abnf grammar
+#[cfg(feature = "additional-controls")]
+const ABNF_CORE_RULES: &str = r#"ALPHA = %x41-5A / %x61-7A
+BIT = "0" / "1"
+CHAR = %x01-7F
+CR = %x0D
+CRLF = CR LF
+CTL = %x00-1F / %x7F
+DIGIT = %x30-39
+DQUOTE = %x22
+HEXDIG = DIGIT / "A" / "B" / "C" / "D" / "E" / "F"
+HTAB = %x09
+LF = %x0A
+LWSP = *(WSP / CRLF WSP)
+OCTET = %x00-FF
+SP = %x20
+VCHAR = %x21-7E
+WSP = SP / HTAB
+"#;
+
+/// Validates the target string against an ABNF grammar. The first line of the
+/// grammar names the rule to match, and the remaining lines define the rules.
+/// A grammar on a single line defines the rule to match. The entire target
+/// must match the named rule.
 #[cfg(feature = "additional-controls")]
 pub fn validate_abnf(abnf: &str, target: &str) -> Result<(), String> {
   let abnf = abnf.trim();
-  if let Some(idx) = abnf.find('\n') {
-    let (rule, abnf) = abnf.split_at(idx);
-    let rule = rule.trim();
-    let mut abnf = abnf.trim().to_string();
-    // Refer to https://docs.rs/abnf/0.13.0/abnf/fn.rulelist.html as to why it
-    // needs to end with a newline
-    abnf.push('\n');
-
-    let rules = abnf_to_pest::parse_abnf(&abnf).map_err(|e| e.to_string())?;
-    let mut w = Vec::new();
-    abnf_to_pest::render_rules_to_pest(rules)
-      .render(0, &mut w)
-      .unwrap();
-    let pest = String::from_utf8(w).unwrap();
-
-    let pairs = pest_meta::parser::parse(pest_meta::parser::Rule::grammar_rules, &pest)
-      .map_err(|e| e.to_string())?;
-
-    let ast = pest_meta::parser::consume_rules(pairs).unwrap();
-
-    let vm = pest_vm::Vm::new(pest_meta::optimizer::optimize(ast));
-
-    let rule = rule.replace('-', "_");
-    let _ = vm.parse(&rule, target).map_err(|e| e.to_string())?;
+  let (rule, abnf) = match abnf.find('\n') {
+    Some(idx) => {
+      let (rule, abnf) = abnf.split_at(idx);
+      (rule.trim(), abnf.trim())
+    }
+    None => (abnf.split('=').next().unwrap_or_default().trim(), abnf),
+  };
+  let rule = abnf_to_pest::escape_rulename(rule);
+  let mut abnf = abnf.to_string();
+  // Refer to https://docs.rs/abnf/0.13.0/abnf/fn.rulelist.html as to why it
+  // needs to end with a newline
+  abnf.push('\n');
+
+  let mut rules = abnf_to_pest::parse_abnf(&abnf).map_err(|e| e.to_string())?;
+  for (name, core_rule) in abnf_to_pest::parse_abnf(ABNF_CORE_RULES).map_err(|e| e.to_string())? {
+    if !rules.keys().any(|r| r.eq_ignore_ascii_case(&name)) {
+      rules.insert(name, core_rule);
+    }
+  }
+
+  if !rules.contains_key(&rule) {
+    return Err(format!("rule {} is not defined in the abnf grammar", rule));
+  }
+
+  let mut w = Vec::new();
+  abnf_to_pest::render_rules_to_pest(rules)
+    .render(0, &mut w)
+    .map_err(|e| e.to_string())?;
+  let pest = String::from_utf8(w).map_err(|e| e.to_string())?;
+
+  let pairs = pest_meta::parser::parse(pest_meta::parser::Rule::grammar_rules, &pest)
+    .map_err(|e| e.to_string())?;
+
+  fn format_errors<E: std::fmt::Display>(errors: Vec<E>) -> String {
+    errors
+      .iter()
+      .map(|e| e.to_string())
+      .collect::<Vec<_>>()
+      .join(", ")
+  }
+
+  // The pest VM panics on references to undefined rules, so they are
+  // reported here instead
+  pest_meta::validator::validate_pairs(pairs.clone()).map_err(format_errors)?;
+
+  let ast = pest_meta::parser::consume_rules(pairs).map_err(format_errors)?;
+
+  let vm = pest_vm::Vm::new(pest_meta::optimizer::optimize(ast));
+
+  let matched = vm.parse(&rule, target).map_err(|e| e.to_string())?;
+  let end = matched.last().map_or(0, |p| p.as_span().end());
+  if end != target.len() {
+    return Err(format!(
+      "unexpected input at position {}, expected end of input",
+      end
+    ));
   }
 
   Ok(())
@@ -836,6 +1037,203 @@ pub fn abnf_from_complex_controller<'a>(
   Err("invalid controller".to_string())
 }
 
+#[cfg(feature = "extensions")]
+/// Retrieve the text of every string literal a .join member or separator may
+/// take. Rule identifiers and parenthesized types contribute one string per
+/// type choice
+fn join_strings_from_type2(cddl: &CDDL, t2: &Type2) -> Result<Vec<String>, String> {
+  match t2 {
+    Type2::TextValue { value, .. } => Ok(vec![value.to_string()]),
+    Type2::UTF8ByteString { value, .. } => match std::str::from_utf8(value) {
+      Ok(value) => Ok(vec![value
+        .trim_start_matches('\'')
+        .trim_end_matches('\'')
+        .to_string()]),
+      Err(e) => Err(format!("error parsing byte string: {}", e)),
+    },
+    Type2::Typename { ident, .. } => {
+      let sl = string_literals_from_ident(cddl, ident);
+      if sl.is_empty() {
+        return Err(format!(
+          "type rule {} used in .join operation is not a string literal",
+          ident
+        ));
+      }
+
+      let mut strings = Vec::new();
+      for t2 in sl.iter() {
+        strings.append(&mut join_strings_from_type2(cddl, t2)?);
+      }
+
+      Ok(strings)
+    }
+    Type2::ParenthesizedType { pt, .. } => {
+      let mut strings = Vec::new();
+      for tc in pt.type_choices.iter() {
+        if tc.type1.operator.is_none() {
+          strings.append(&mut join_strings_from_type2(cddl, &tc.type1.type2)?);
+        }
+      }
+
+      Ok(strings)
+    }
+    _ => Err(format!(
+      "expected string literal in .join operation, got {}",
+      t2
+    )),
+  }
+}
+
+#[cfg(feature = "extensions")]
+/// Retrieve the arrays a .join target may take. Rule identifiers contribute
+/// one array per type choice, skipping rules which have already been visited
+/// so that cyclic type choices terminate
+fn join_target_groups<'b>(
+  cddl: &'b CDDL<'b>,
+  target: &'b Type2<'b>,
+  visited: &mut HashSet<&'b str>,
+) -> Result<Vec<&'b Group<'b>>, String> {
+  match target {
+    Type2::Array { group, .. } => Ok(vec![group]),
+    Type2::Typename { ident, .. } => {
+      let mut groups = Vec::new();
+      if !visited.insert(ident.ident) {
+        return Ok(groups);
+      }
+
+      for r in cddl.rules.iter() {
+        if let Rule::Type { rule, .. } = r {
+          if rule.name == *ident {
+            for tc in rule.value.type_choices.iter() {
+              if tc.type1.operator.is_none() {
+                groups.append(&mut join_target_groups(cddl, &tc.type1.type2, visited)?);
+              }
+            }
+          }
+        }
+      }
+
+      Ok(groups)
+    }
+    _ => Err(format!(
+      "target of .join operation must be an array, got {}",
+      target
+    )),
+  }
+}
+
+#[cfg(feature = "extensions")]
+/// Retrieve the strings each member of a .join target array may take
+fn join_members(cddl: &CDDL, group: &Group) -> Result<Vec<Vec<String>>, String> {
+  if group.group_choices.len() > 1 {
+    return Err("target array of .join operation cannot have group choices".to_string());
+  }
+
+  let mut members = Vec::new();
+  for gc in group.group_choices.iter() {
+    for (ge, _) in gc.group_entries.iter() {
+      match ge {
+        GroupEntry::ValueMemberKey { ge, .. } if ge.occur.is_none() && ge.member_key.is_none() => {
+          let mut strings = Vec::new();
+          for tc in ge.entry_type.type_choices.iter() {
+            if tc.type1.operator.is_some() {
+              return Err(format!(
+                "expected string literal in .join operation, got {}",
+                tc.type1
+              ));
+            }
+
+            strings.append(&mut join_strings_from_type2(cddl, &tc.type1.type2)?);
+          }
+          members.push(strings);
+        }
+        GroupEntry::TypeGroupname { ge, .. } if ge.occur.is_none() => {
+          let sl = string_literals_from_ident(cddl, &ge.name);
+          if sl.is_empty() {
+            return Err(format!(
+              "type rule {} used in .join operation is not a string literal",
+              ge.name
+            ));
+          }
+
+          let mut strings = Vec::new();
+          for t2 in sl.iter() {
+            strings.append(&mut join_strings_from_type2(cddl, t2)?);
+          }
+          members.push(strings);
+        }
+        _ => {
+          return Err(format!(
+            "expected string literal in .join operation, got {}",
+            ge
+          ))
+        }
+      }
+    }
+  }
+
+  Ok(members)
+}
+
+#[cfg(feature = "extensions")]
+/// Whether the value consists of one of the strings of each member, in
+/// order, with the separator in between. The value is matched member by
+/// member, so the joined strings are never built
+fn is_joined_by(value: &str, members: &[Vec<String>], separator: &str) -> bool {
+  match members.split_first() {
+    None => value.is_empty(),
+    Some((member, [])) => member.iter().any(|m| m == value),
+    Some((member, rest)) => member.iter().any(|m| {
+      matches!(
+        value
+          .strip_prefix(m.as_str())
+          .and_then(|v| v.strip_prefix(separator)),
+        Some(v) if is_joined_by(v, rest, separator)
+      )
+    }),
+  }
+}
+
+#[cfg(feature = "extensions")]
+/// Validates that the target string equals the members of the .join target
+/// array joined by the controller
+///
+/// ```cddl
+/// path = ["usr", "local", "bin"] .join "/"
+/// ```
+pub fn validate_join_control(
+  cddl: &CDDL,
+  target: &Type2,
+  controller: &Type2,
+  value: &str,
+) -> Result<(), String> {
+  let groups = join_target_groups(cddl, target, &mut HashSet::new())?;
+  if groups.is_empty() {
+    return Err(format!(
+      "target of .join operation must be an array, got {}",
+      target
+    ));
+  }
+
+  let members = groups
+    .iter()
+    .map(|group| join_members(cddl, group))
+    .collect::<Result<Vec<_>, _>>()?;
+  let separators = join_strings_from_type2(cddl, controller)?;
+
+  if members
+    .iter()
+    .any(|members| separators.iter().any(|s| is_joined_by(value, members, s)))
+  {
+    return Ok(());
+  }
+
+  Err(format!(
+    "expected value to match {} .join {}, got \"{}\"",
+    target, controller, value
+  ))
+}
+
 #[cfg(test)]
 #[cfg(not(target_arch = "wasm32"))]
 mod tests {
@@ -955,4 +1353,46 @@ mod tests {
 
     Ok(())
   }
+
+  #[cfg(feature = "additional-controls")]
+  #[test]
+  fn test_abnf_undefined_rule() {
+    assert!(validate_abnf("a\na = b", "x").is_err());
+    assert!(validate_abnf("c\na = %x61", "a").is_err());
+    assert!(validate_abnf("a\na = 1*ALPHA DIGIT", "ab1").is_ok());
+  }
+
+  #[cfg(feature = "additional-controls")]
+  #[test]
+  fn test_abnf_single_line() {
+    assert!(validate_abnf("b = 1*DIGIT", "12").is_ok());
+    assert!(validate_abnf("b = 1*DIGIT", "12a").is_err());
+    assert!(validate_abnf("b = 1*DIGIT", "").is_err());
+  }
+
+  #[cfg(feature = "extensions")]
+  #[test]
+  fn test_join() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl_str = indoc!(
+      r#"
+        path = ["usr", local, "bin"] .join sep
+        local = "local" / "opt"
+        sep = "/"
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl_str, true)?;
+
+    if let Some(Rule::Type { rule, .. }) = cddl.rules.first() {
+      let t1 = &rule.value.type_choices[0].type1;
+      if let Some(operator) = &t1.operator {
+        assert!(validate_join_control(&cddl, &t1.type2, &operator.type2, "usr/local/bin").is_ok());
+        assert!(validate_join_control(&cddl, &t1.type2, &operator.type2, "usr/opt/bin").is_ok());
+        assert!(validate_join_control(&cddl, &t1.type2, &operator.type2, "usr-opt-bin").is_err());
+        assert!(validate_join_control(&cddl, &t1.type2, &operator.type2, "usr/opt").is_err());
+      }
+    }
+
+    Ok(())
+  }
 }
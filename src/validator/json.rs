@@ -2,7 +2,10 @@
 #![cfg(feature = "json")]
 #![cfg(not(feature = "lsp"))]
 
-use super::*;
+use super::{
+  value::{compare_numeric, compare_ordering, range_contains},
+  *,
+};
 use crate::{
   ast::*,
   token,
@@ -11,22 +14,29 @@ use crate::{
 
 use std::{
   borrow::Cow,
-  collections::HashMap,
-  convert::TryFrom,
+  collections::{HashMap, HashSet},
   fmt::{self, Write},
+  ops::Range,
+  sync::Arc,
 };
 
-use chrono::{TimeZone, Utc};
+#[cfg(feature = "uri")]
+use std::convert::TryFrom;
+
 use serde_json::Value;
 
+#[cfg(feature = "extensions")]
+use control::validate_join_control;
 #[cfg(feature = "additional-controls")]
 use control::{abnf_from_complex_controller, cat_operation, plus_operation, validate_abnf};
+use control::{bit_positions_from_type2, range_bound_values};
 
 /// JSON validation Result
 pub type Result = std::result::Result<(), Error>;
 
 /// JSON validation error
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
   /// Zero or more validation errors
   Validation(Vec<ValidationError>),
@@ -38,6 +48,18 @@ pub enum Error {
   UTF8Parsing(std::str::Utf8Error),
   /// Disabled feature
   DisabledFeature(String),
+  /// Duplicate object key in the JSON document
+  DuplicateKey(String),
+  /// The CDDL document has no type rule to validate against
+  NoRootRule,
+  /// The CDDL document has no type rule with the requested name
+  RuleNotFound(String),
+  /// The JSON document nests arrays and objects deeper than the configured
+  /// maximum depth
+  MaxDepthExceeded(usize),
+  /// The CDDL document uses syntax which is invalid or isn't supported during
+  /// validation
+  Syntax(SyntaxError),
 }
 
 impl fmt::Display for Error {
@@ -54,6 +76,15 @@ impl fmt::Display for Error {
       Error::CDDLParsing(error) => write!(f, "error parsing CDDL: {}", error),
       Error::UTF8Parsing(error) => write!(f, "error pasing utf8: {}", error),
       Error::DisabledFeature(feature) => write!(f, "feature {} is not enabled", feature),
+      Error::DuplicateKey(error) => write!(f, "error parsing JSON: {}", error),
+      Error::NoRootRule => write!(f, "CDDL document contains no root type rule"),
+      Error::RuleNotFound(name) => write!(f, "CDDL document contains no type rule named {}", name),
+      Error::MaxDepthExceeded(max_depth) => write!(
+        f,
+        "JSON document exceeds the maximum nesting depth of {}",
+        max_depth
+      ),
+      Error::Syntax(error) => write!(f, "invalid CDDL syntax: {}", error),
     }
   }
 }
@@ -67,7 +98,75 @@ impl std::error::Error for Error {
   }
 }
 
+impl IntoIterator for Error {
+  type Item = ValidationError;
+  type IntoIter = std::vec::IntoIter<ValidationError>;
+
+  /// Iterate over the owned validation errors. Other errors yield nothing
+  fn into_iter(self) -> Self::IntoIter {
+    match self {
+      Error::Validation(errors) => errors.into_iter(),
+      _ => Vec::new().into_iter(),
+    }
+  }
+}
+
 impl Error {
+  /// Individual validation errors, or none if the error did not occur during
+  /// validation
+  pub fn leaf_errors(&self) -> Vec<&ValidationError> {
+    match self {
+      Error::Validation(errors) => errors.iter().collect(),
+      _ => Vec::new(),
+    }
+  }
+
+  /// Whether the CDDL document uses syntax which is invalid or isn't supported
+  /// during validation
+  pub fn is_syntax(&self) -> bool {
+    matches!(self, Error::Syntax(_))
+  }
+
+  /// Whether any of the validation errors reports an array item or object
+  /// member occurring fewer or more times than allowed, including a missing
+  /// required member
+  pub fn is_occurrence(&self) -> bool {
+    self.leaf_errors().iter().any(|e| e.is_occurrence)
+  }
+
+  /// The error raised while parsing the JSON document being validated, if
+  /// that is what failed
+  pub fn as_target(&self) -> Option<&serde_json::Error> {
+    match self {
+      Error::JSONParsing(error) => Some(error),
+      _ => None,
+    }
+  }
+
+  /// Describe each failure as a structured issue, taking the offending values
+  /// from the given validated document. Errors other than validation errors
+  /// are reported as a single issue at the root of the document
+  pub fn issues(&self, document: &Value) -> Vec<ValidationIssue> {
+    match self {
+      Error::Validation(errors) => errors
+        .iter()
+        .map(|e| ValidationIssue {
+          location: e.json_location.clone(),
+          expected: e.reason.clone(),
+          actual: document
+            .pointer(&e.json_location)
+            .cloned()
+            .unwrap_or(Value::Null),
+        })
+        .collect(),
+      _ => vec![ValidationIssue {
+        location: String::new(),
+        expected: self.to_string(),
+        actual: document.clone(),
+      }],
+    }
+  }
+
   fn from_validator(jv: &JSONValidator, reason: String) -> Self {
     Error::Validation(vec![ValidationError {
       cddl_location: jv.cddl_location.clone(),
@@ -76,7 +175,11 @@ impl Error {
       is_multi_type_choice: jv.is_multi_type_choice,
       is_group_to_choice_enum: jv.is_group_to_choice_enum,
       type_group_name_entry: jv.type_group_name_entry.map(|e| e.to_string()),
+      is_occurrence: false,
       is_multi_group_choice: jv.is_multi_group_choice,
+      rule_chain: jv.rule_chain.iter().map(|r| r.to_string()).collect(),
+      alternatives: Vec::new(),
+      source: None,
     }])
   }
 }
@@ -98,6 +201,33 @@ pub struct ValidationError {
   pub is_group_to_choice_enum: bool,
   /// Error is associated with a type/group name group entry
   pub type_group_name_entry: Option<String>,
+  /// Whether or not the error reports an array item or object member occurring fewer
+  /// or more times than allowed, including a missing required member
+  pub is_occurrence: bool,
+  /// Names of the rules referenced on the way from the root rule to the type
+  /// which failed to validate, ending with that type
+  pub rule_chain: Vec<String>,
+  /// The type choices whose failure the error is associated with, if the
+  /// errors of several failed type choices are reported together
+  pub alternatives: Vec<String>,
+  /// Underlying error which caused the validation to fail, such as a regex
+  /// compilation or decoding error
+  pub source: Option<Arc<dyn std::error::Error + Send + Sync>>,
+}
+
+impl PartialEq for ValidationError {
+  fn eq(&self, other: &Self) -> bool {
+    self.reason == other.reason
+      && self.cddl_location == other.cddl_location
+      && self.json_location == other.json_location
+      && self.is_multi_type_choice == other.is_multi_type_choice
+      && self.is_multi_group_choice == other.is_multi_group_choice
+      && self.is_group_to_choice_enum == other.is_group_to_choice_enum
+      && self.type_group_name_entry == other.type_group_name_entry
+      && self.is_occurrence == other.is_occurrence
+      && self.alternatives == other.alternatives
+      && self.source.as_ref().map(|e| e.to_string()) == other.source.as_ref().map(|e| e.to_string())
+  }
 }
 
 impl fmt::Display for ValidationError {
@@ -116,25 +246,45 @@ impl fmt::Display for ValidationError {
       let _ = write!(error_str, " group entry associated with rule \"{}\"", entry);
     }
 
+    let mut reason = String::new();
+    if !self.alternatives.is_empty() {
+      let alternatives = self
+        .alternatives
+        .iter()
+        .map(|a| format!("'{}'", a))
+        .collect::<Vec<_>>();
+      let _ = write!(reason, "alternative {}: ", alternatives.join(" / "));
+    }
+    reason.push_str(&self.reason);
+
+    // Only report the chain when the failing type was reached through at
+    // least one alias of the root rule
+    if self.rule_chain.len() > 2 {
+      let _ = write!(reason, " (via {})", self.rule_chain.join(" -> "));
+    }
+
     if self.json_location.is_empty() {
       return write!(
         f,
         "{} at the root of the JSON document: {}",
-        error_str, self.reason
+        error_str, reason
       );
     }
 
     write!(
       f,
       "{} at JSON location {}: {}",
-      error_str, self.json_location, self.reason
+      error_str, self.json_location, reason
     )
   }
 }
 
 impl std::error::Error for ValidationError {
   fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-    None
+    self
+      .source
+      .as_deref()
+      .map(|e| e as &(dyn std::error::Error + 'static))
   }
 }
 
@@ -147,11 +297,48 @@ impl ValidationError {
       is_multi_type_choice: jv.is_multi_type_choice,
       is_group_to_choice_enum: jv.is_group_to_choice_enum,
       type_group_name_entry: jv.type_group_name_entry.map(|e| e.to_string()),
+      is_occurrence: false,
       is_multi_group_choice: jv.is_multi_group_choice,
+      rule_chain: jv.rule_chain.iter().map(|r| r.to_string()).collect(),
+      alternatives: Vec::new(),
+      source: None,
     }
   }
 }
 
+/// A JSON validation failure in a structured form, e.g. for generating error
+/// responses
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationIssue {
+  /// Location in JSON (in JSONPointer notation) of the offending value
+  pub location: String,
+  /// Description of the expectation the value failed to meet
+  pub expected: String,
+  /// The offending value
+  pub actual: Value,
+}
+
+/// Options controlling the strictness of JSON validation
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ValidationOptions {
+  /// Whether object keys not described by the map type are accepted. By
+  /// default, such keys are reported as unexpected
+  pub allow_extra_keys: bool,
+  /// Whether JSON documents with duplicate keys in an object are rejected
+  /// before validation. Only applies when validating a JSON string, since
+  /// parsed values retain only the last of any duplicate keys
+  pub reject_duplicate_keys: bool,
+  /// Maximum nesting depth of arrays and objects in the JSON document.
+  /// Deeper documents are rejected before validation. By default, the depth
+  /// is not limited
+  pub max_depth: Option<usize>,
+  /// Whether strings encoding a number, e.g. `"42"`, are accepted by numeric
+  /// types such as `uint`, `int` and `float`, numeric ranges and controls on
+  /// numeric types if the number they encode is. By default, these only
+  /// accept JSON numbers
+  pub coerce_numeric_strings: bool,
+}
+
 /// JSON validator type
 #[derive(Clone)]
 pub struct JSONValidator<'a> {
@@ -196,6 +383,13 @@ pub struct JSONValidator<'a> {
   entry_counts: Option<Vec<EntryCount>>,
   // Collect map entry keys that have already been validated
   validated_keys: Option<Vec<String>>,
+  options: ValidationOptions,
+  // Rules being evaluated against the current JSON value, used to detect
+  // cyclic rule references
+  visited_rules: Vec<&'a str>,
+  // Rules referenced on the way to the prelude type currently being validated,
+  // reported with the errors of that type
+  rule_chain: Vec<&'a str>,
   // Collect map entry values that have yet to be validated
   values_to_validate: Option<Vec<Value>>,
   // Collect valid array indices when entries are type choices
@@ -214,8 +408,6 @@ pub struct JSONValidator<'a> {
   enabled_features: Option<Box<[JsValue]>>,
   #[cfg(feature = "additional-controls")]
   has_feature_errors: bool,
-  #[cfg(feature = "additional-controls")]
-  disabled_features: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug)]
@@ -253,6 +445,9 @@ impl<'a> JSONValidator<'a> {
       is_ctrl_map_equality: false,
       entry_counts: None,
       validated_keys: None,
+      options: ValidationOptions::default(),
+      visited_rules: Vec::new(),
+      rule_chain: Vec::new(),
       values_to_validate: None,
       valid_array_items: None,
       array_errors: None,
@@ -261,7 +456,6 @@ impl<'a> JSONValidator<'a> {
       is_multi_type_choice_type_rule_validating_array: false,
       enabled_features,
       has_feature_errors: false,
-      disabled_features: None,
     }
   }
 
@@ -292,6 +486,9 @@ impl<'a> JSONValidator<'a> {
       is_ctrl_map_equality: false,
       entry_counts: None,
       validated_keys: None,
+      options: ValidationOptions::default(),
+      visited_rules: Vec::new(),
+      rule_chain: Vec::new(),
       values_to_validate: None,
       valid_array_items: None,
       array_errors: None,
@@ -328,6 +525,9 @@ impl<'a> JSONValidator<'a> {
       is_ctrl_map_equality: false,
       entry_counts: None,
       validated_keys: None,
+      options: ValidationOptions::default(),
+      visited_rules: Vec::new(),
+      rule_chain: Vec::new(),
       values_to_validate: None,
       valid_array_items: None,
       array_errors: None,
@@ -336,7 +536,6 @@ impl<'a> JSONValidator<'a> {
       is_multi_type_choice_type_rule_validating_array: false,
       enabled_features,
       has_feature_errors: false,
-      disabled_features: None,
     }
   }
 
@@ -367,6 +566,9 @@ impl<'a> JSONValidator<'a> {
       is_ctrl_map_equality: false,
       entry_counts: None,
       validated_keys: None,
+      options: ValidationOptions::default(),
+      visited_rules: Vec::new(),
+      rule_chain: Vec::new(),
       values_to_validate: None,
       valid_array_items: None,
       array_errors: None,
@@ -376,6 +578,413 @@ impl<'a> JSONValidator<'a> {
     }
   }
 
+  /// Validate the JSON value using the given options
+  pub fn validate_with_options(&mut self, options: ValidationOptions) -> Result {
+    if let Some(max_depth) = options.max_depth {
+      if json_depth(&self.json) > max_depth {
+        return Err(Error::MaxDepthExceeded(max_depth));
+      }
+    }
+
+    self.options = options;
+    self.validate()
+  }
+
+  /// Validate against the type rule with the given name rather than the first
+  /// type rule of the CDDL document
+  pub fn validate_named(&mut self, rule_name: &str) -> Result {
+    let ident = Identifier {
+      ident: rule_name,
+      socket: None,
+      #[cfg(feature = "ast-span")]
+      span: Span::default(),
+    };
+
+    let rule = match type_rule_from_ident(self.cddl, &ident) {
+      Some(rule) => rule,
+      None => return Err(Error::RuleNotFound(rule_name.to_string())),
+    };
+
+    self.is_root = true;
+    self.visited_rules.push(rule.name.ident);
+    self.visit_type_rule(rule)?;
+    self.visited_rules.pop();
+    self.is_root = false;
+
+    if !self.errors.is_empty() {
+      return Err(Error::Validation(dedup_errors(&self.errors)));
+    }
+
+    Ok(())
+  }
+
+  /// Validate against the given type rather than a type rule of the CDDL
+  /// document. Identifiers within the type refer to the rules of the document
+  pub fn validate_type(&mut self, t: &Type<'a>) -> Result {
+    self.is_root = true;
+    self.visit_type(t)?;
+    self.is_root = false;
+
+    if !self.errors.is_empty() {
+      return Err(Error::Validation(dedup_errors(&self.errors)));
+    }
+
+    Ok(())
+  }
+
+  /// Keys of the validated JSON object which weren't matched by any member
+  /// key of the map type, i.e. those only accepted by a wildcard entry such
+  /// as `* tstr => any` or not accepted at all. Only the keys of the root
+  /// object are considered
+  pub fn extra_keys(&self) -> Vec<String> {
+    match &self.json {
+      Value::Object(o) => o
+        .keys()
+        .filter(|k| !matches!(&self.validated_keys, Some(keys) if keys.contains(k)))
+        .cloned()
+        .collect(),
+      _ => Vec::new(),
+    }
+  }
+
+  /// Restore the keys validated before a choice which failed to validate,
+  /// returning those validated by the choice itself. Keys of a failed choice
+  /// aren't considered matched when another choice validates
+  fn restore_validated_keys(&mut self, validated_keys: Option<Vec<String>>) -> Vec<String> {
+    let count = validated_keys.as_ref().map_or(0, Vec::len);
+    let choice_keys = match &self.validated_keys {
+      Some(keys) if keys.len() > count => keys[count..].to_vec(),
+      _ => Vec::new(),
+    };
+    self.validated_keys = validated_keys;
+
+    choice_keys
+  }
+
+  /// Collect a validation error reporting an array item or object member
+  /// occurring fewer or more times than allowed
+  fn add_occurrence_error(&mut self, reason: String) {
+    self.add_error(reason);
+    if let Some(error) = self.errors.last_mut() {
+      error.is_occurrence = true;
+    }
+  }
+
+  /// Collect a validation error caused by the given underlying error
+  fn add_error_with_source<E: std::error::Error + Send + Sync + 'static>(
+    &mut self,
+    reason: String,
+    source: E,
+  ) {
+    self.add_error(reason);
+    if let Some(error) = self.errors.last_mut() {
+      error.source = Some(Arc::new(source));
+    }
+  }
+
+  /// Keep only the errors collected for the given failed choices. If errors
+  /// of more than one choice are kept, each is associated with the
+  /// alternative it came from, if known and not already associated with a
+  /// nested one. The same error reported by several alternatives is kept once
+  fn retain_choice_errors(
+    &mut self,
+    initial_error_count: usize,
+    retained: &[(Option<String>, Range<usize>)],
+  ) {
+    let mut errors: Vec<ValidationError> = Vec::new();
+    for (alternative, range) in retained.iter() {
+      for error in self.errors[range.clone()].iter() {
+        match alternative {
+          Some(alternative) if retained.len() > 1 && error.alternatives.is_empty() => {
+            if let Some(existing) = errors.iter_mut().find(|e| {
+              let mut e = (*e).clone();
+              e.alternatives.clear();
+              e == *error
+            }) {
+              existing.alternatives.push(alternative.clone());
+            } else {
+              let mut error = error.clone();
+              error.alternatives.push(alternative.clone());
+              errors.push(error);
+            }
+          }
+          _ => errors.push(error.clone()),
+        }
+      }
+    }
+
+    self.errors.truncate(initial_error_count);
+    self.errors.extend(errors);
+  }
+
+  /// Validate against a generic rule with the given arguments bound to its
+  /// generic parameters. Arguments which themselves refer to a parameter of
+  /// the generic rule currently being evaluated are resolved first
+  fn validate_generic_rule(
+    &mut self,
+    name: &'a str,
+    rule: &Rule<'a>,
+    ga: &GenericArgs<'a>,
+  ) -> visitor::Result<Error> {
+    if let Some(reason) = generic_arity_mismatch(rule, ga) {
+      self.add_error(reason);
+      return Ok(());
+    }
+
+    let args = ga
+      .args
+      .iter()
+      .map(|arg| self.resolve_generic_arg(&arg.arg))
+      .collect::<Vec<_>>();
+
+    let prev_args = if let Some(gr) = self.generic_rules.iter_mut().find(|gr| gr.name == name) {
+      Some(std::mem::replace(&mut gr.args, args))
+    } else {
+      if let Some(params) = generic_params_from_rule(rule) {
+        self.generic_rules.push(GenericRule { name, params, args });
+      }
+
+      None
+    };
+
+    let prev_eval_generic_rule = self.eval_generic_rule.replace(name);
+    self.visit_rule(rule)?;
+    self.eval_generic_rule = prev_eval_generic_rule;
+
+    if let Some(prev_args) = prev_args {
+      if let Some(gr) = self.generic_rules.iter_mut().find(|gr| gr.name == name) {
+        gr.args = prev_args;
+      }
+    }
+
+    Ok(())
+  }
+
+  fn resolve_generic_arg(&self, arg: &Type1<'a>) -> Type1<'a> {
+    if let Type1 {
+      type2: Type2::Typename {
+        ident,
+        generic_args: None,
+        ..
+      },
+      operator: None,
+      ..
+    } = arg
+    {
+      if let Some(gr) = self
+        .eval_generic_rule
+        .and_then(|name| self.generic_rules.iter().find(|gr| gr.name == name))
+      {
+        if let Some(bound) = gr
+          .params
+          .iter()
+          .position(|gp| *gp == ident.ident)
+          .and_then(|idx| gr.args.get(idx))
+        {
+          return bound.clone();
+        }
+      }
+    }
+
+    arg.clone()
+  }
+
+  /// Validate the items of a JSON array against the entries of an array group
+  /// choice in order, where each entry consumes as many consecutive items as
+  /// its occurrence indicator allows
+  fn validate_array_items_positionally(&mut self, gc: &GroupChoice<'a>) -> visitor::Result<Error> {
+    let items = match &self.json {
+      Value::Array(a) => a.clone(),
+      _ => return Ok(()),
+    };
+
+    match match_array_items_positionally(gc, items.len(), |ge, idx| {
+      self.validate_array_item(ge, idx, &items[idx])
+    })? {
+      Some(PositionalMismatch::Item(mut item_errors)) => self.errors.append(&mut item_errors),
+      Some(PositionalMismatch::MissingItem(entry, idx)) => self.add_error(format!(
+        "expected array item {} at index {}, got end of array",
+        gc.group_entries[entry].0, idx
+      )),
+      Some(PositionalMismatch::UnexpectedItem(idx)) => self.add_error(format!(
+        "unexpected array item at index {}, got {}",
+        idx, items[idx]
+      )),
+      None => (),
+    }
+
+    Ok(())
+  }
+
+  /// Validate the items of a JSON array against the group choices of an inline
+  /// group, e.g. `[* (int // tstr)]` or `[(int, tstr // tstr, int)]`, where
+  /// each occurrence of the group consumes the consecutive items matched in
+  /// order by the entries of any one of its choices
+  fn validate_array_items_per_group_choice(
+    &mut self,
+    occur: Option<&Occurrence<'a>>,
+    choices: &Group<'a>,
+  ) -> visitor::Result<Error> {
+    let items = match &self.json {
+      Value::Array(a) => a.clone(),
+      _ => return Ok(()),
+    };
+
+    let (lower, upper) = occurrence_bounds(occur);
+    // Ends of the items matched by a group choice from a given start index
+    let mut ends = HashMap::new();
+    // Start indices and occurrence counts from which to match the remaining
+    // items
+    let mut pending = vec![(0, 0)];
+    let mut visited = HashSet::new();
+    while let Some((start, count)) = pending.pop() {
+      if start == items.len() && count >= lower {
+        return Ok(());
+      }
+
+      if count == upper || !visited.insert((start, count)) {
+        continue;
+      }
+
+      for choice in 0..choices.group_choices.len() {
+        for end in self.group_choice_ends(&mut ends, choices, choice, &items, start)? {
+          // An empty match only counts towards the minimum occurrences
+          if end > start || count < lower {
+            pending.push((end, count + 1));
+          }
+        }
+      }
+    }
+
+    // Report the mismatch where greedily matching the first choice with the
+    // most items fails
+    let mut start = 0;
+    let mut count = 0;
+    while start < items.len() && count < upper {
+      let mut next = None;
+      for choice in 0..choices.group_choices.len() {
+        next = self
+          .group_choice_ends(&mut ends, choices, choice, &items, start)?
+          .into_iter()
+          .find(|end| *end > start);
+        if next.is_some() {
+          break;
+        }
+      }
+
+      match next {
+        Some(end) => {
+          start = end;
+          count += 1;
+        }
+        None => break,
+      }
+    }
+
+    if count < upper && (start < items.len() || occur.is_none()) {
+      for gc in choices.group_choices.iter() {
+        let (_, max) = group_choice_length_bounds(gc);
+        let item_count = max.min(items.len() - start);
+        match match_array_items_positionally(gc, item_count, |ge, idx| {
+          self.validate_array_item(ge, start + idx, &items[start + idx])
+        })? {
+          Some(PositionalMismatch::Item(mut item_errors)) => self.errors.append(&mut item_errors),
+          Some(PositionalMismatch::MissingItem(entry, idx)) => self.add_error(format!(
+            "expected array item {} at index {}, got end of array",
+            gc.group_entries[entry].0,
+            start + idx
+          )),
+          Some(PositionalMismatch::UnexpectedItem(idx)) => self.add_error(format!(
+            "unexpected array item at index {}, got {}",
+            start + idx,
+            items[start + idx]
+          )),
+          None => (),
+        }
+      }
+    } else if let Some(o) = occur {
+      self.add_occurrence_error(format!(
+        "expected array with length per occurrence {}",
+        o.occur
+      ));
+    } else {
+      self.add_error(format!(
+        "unexpected array item at index {}, got {}",
+        start, items[start]
+      ));
+    }
+
+    Ok(())
+  }
+
+  /// Indices after the items matched by the entries of a group choice in
+  /// order, starting at the given item index, most first. Results are cached
+  /// per group choice and start index
+  fn group_choice_ends(
+    &self,
+    ends: &mut HashMap<(usize, usize), Vec<usize>>,
+    choices: &Group<'a>,
+    choice: usize,
+    items: &[Value],
+    start: usize,
+  ) -> std::result::Result<Vec<usize>, Error> {
+    if let Some(ends) = ends.get(&(choice, start)) {
+      return Ok(ends.clone());
+    }
+
+    let gc = &choices.group_choices[choice];
+    let (min, max) = group_choice_length_bounds(gc);
+    let mut choice_ends = Vec::new();
+    for item_count in (min..=max.min(items.len() - start)).rev() {
+      if match_array_items_positionally(gc, item_count, |ge, idx| {
+        self.validate_array_item(ge, start + idx, &items[start + idx])
+      })?
+      .is_none()
+      {
+        choice_ends.push(start + item_count);
+      }
+    }
+
+    ends.insert((choice, start), choice_ends.clone());
+
+    Ok(choice_ends)
+  }
+
+  /// Validate a single JSON array item against a group entry, returning the
+  /// resulting errors
+  fn validate_array_item(
+    &self,
+    ge: &GroupEntry<'a>,
+    idx: usize,
+    item: &Value,
+  ) -> std::result::Result<Vec<ValidationError>, Error> {
+    #[cfg(all(feature = "additional-controls", target_arch = "wasm32"))]
+    let mut jv = JSONValidator::new(self.cddl, item.clone(), self.enabled_features.clone());
+    #[cfg(all(feature = "additional-controls", not(target_arch = "wasm32")))]
+    let mut jv = JSONValidator::new(self.cddl, item.clone(), self.enabled_features);
+    #[cfg(not(feature = "additional-controls"))]
+    let mut jv = JSONValidator::new(self.cddl, item.clone());
+
+    jv.generic_rules = self.generic_rules.clone();
+    jv.options = self.options;
+    jv.eval_generic_rule = self.eval_generic_rule;
+    jv.is_multi_type_choice = self.is_multi_type_choice;
+    let _ = write!(jv.json_location, "{}/{}", self.json_location, idx);
+
+    match ge {
+      GroupEntry::ValueMemberKey { ge, .. } => jv.visit_type(&ge.entry_type)?,
+      GroupEntry::TypeGroupname { ge, .. } => {
+        match (&ge.generic_args, rule_from_ident(self.cddl, &ge.name)) {
+          (Some(ga), Some(rule)) => jv.validate_generic_rule(ge.name.ident, rule, ga)?,
+          _ => jv.visit_identifier(&ge.name)?,
+        }
+      }
+      _ => (),
+    }
+
+    Ok(jv.errors)
+  }
+
   fn validate_array_items(&mut self, token: &ArrayItemToken) -> visitor::Result<Error> {
     if let Value::Array(a) = &self.json {
       // Member keys are annotation only in an array context
@@ -405,6 +1014,7 @@ impl<'a> JSONValidator<'a> {
               let mut jv = JSONValidator::new(self.cddl, v.clone());
 
               jv.generic_rules = self.generic_rules.clone();
+              jv.options = self.options;
               jv.eval_generic_rule = self.eval_generic_rule;
               jv.is_multi_type_choice = self.is_multi_type_choice;
               jv.ctrl = self.ctrl;
@@ -415,7 +1025,10 @@ impl<'a> JSONValidator<'a> {
                 ArrayItemToken::Range(lower, upper, is_inclusive) => {
                   jv.visit_range(lower, upper, *is_inclusive)?
                 }
-                ArrayItemToken::Group(group) => jv.visit_group(group)?,
+                ArrayItemToken::Map(map) => match v {
+                  Value::Object(_) => jv.visit_type2(map)?,
+                  _ => jv.add_error(format!("expected map object {}, got {}", map, v)),
+                },
                 ArrayItemToken::Identifier(ident) => jv.visit_identifier(ident)?,
                 _ => (),
               }
@@ -451,6 +1064,7 @@ impl<'a> JSONValidator<'a> {
               let mut jv = JSONValidator::new(self.cddl, v.clone());
 
               jv.generic_rules = self.generic_rules.clone();
+              jv.options = self.options;
               jv.eval_generic_rule = self.eval_generic_rule;
               jv.is_multi_type_choice = self.is_multi_type_choice;
               jv.ctrl = self.ctrl;
@@ -461,7 +1075,10 @@ impl<'a> JSONValidator<'a> {
                 ArrayItemToken::Range(lower, upper, is_inclusive) => {
                   jv.visit_range(lower, upper, *is_inclusive)?
                 }
-                ArrayItemToken::Group(group) => jv.visit_group(group)?,
+                ArrayItemToken::Map(map) => match v {
+                  Value::Object(_) => jv.visit_type2(map)?,
+                  _ => jv.add_error(format!("expected map object {}, got {}", map, v)),
+                },
                 ArrayItemToken::Identifier(ident) => jv.visit_identifier(ident)?,
                 _ => (),
               }
@@ -470,13 +1087,13 @@ impl<'a> JSONValidator<'a> {
             } else if !allow_empty_array {
               self.add_error(token.error_msg(Some(idx)));
             }
-          } else if !self.is_multi_type_choice {
+          } else {
             self.add_error(format!("{}, got {}", token.error_msg(None), self.json));
           }
         }
         Err(errors) => {
           for e in errors.into_iter() {
-            self.add_error(e);
+            self.add_occurrence_error(e);
           }
         }
       }
@@ -514,10 +1131,10 @@ impl<'a> JSONValidator<'a> {
         {
           self.advance_to_next_entry = true;
           return Ok(());
-        } else if let Some(ControlOperator::NE) | Some(ControlOperator::DEFAULT) = &self.ctrl {
+        } else if let Some(ControlOperator::NE) = &self.ctrl {
           return Ok(());
         } else {
-          self.add_error(format!("object missing key: \"{}\"", t))
+          self.add_occurrence_error(format!("required key \"{}\" missing", t))
         }
 
         // Retrieve the value from key unless optional/zero or more, in which
@@ -537,10 +1154,10 @@ impl<'a> JSONValidator<'a> {
         {
           self.advance_to_next_entry = true;
           return Ok(());
-        } else if let Some(Token::NE) | Some(Token::DEFAULT) = &self.ctrl {
+        } else if let Some(ControlOperator::NE) = &self.ctrl {
           return Ok(());
         } else {
-          self.add_error(format!("object missing key: \"{}\"", t))
+          self.add_occurrence_error(format!("required key \"{}\" missing", t))
         }
       } else {
         self.add_error(format!(
@@ -552,2252 +1169,6116 @@ impl<'a> JSONValidator<'a> {
 
     Ok(())
   }
-}
 
-impl<'a, 'b> Validator<'a, 'b, Error> for JSONValidator<'a> {
-  /// Validate
-  fn validate(&mut self) -> std::result::Result<(), Error> {
-    for r in self.cddl.rules.iter() {
-      // First type rule is root
-      if let Rule::Type { rule, .. } = r {
-        if rule.generic_params.is_none() {
-          self.is_root = true;
-          self.visit_type_rule(rule)?;
-          self.is_root = false;
-          break;
-        }
+  /// The number encoded by the JSON string being validated, if any, with the
+  /// `coerce_numeric_strings` option
+  fn coerced_numeric_string(&self) -> Option<Value> {
+    match &self.json {
+      Value::String(s) if self.options.coerce_numeric_strings => {
+        s.parse::<serde_json::Number>().ok().map(Value::Number)
       }
+      _ => None,
     }
+  }
 
-    if !self.errors.is_empty() {
-      return Err(Error::Validation(self.errors.clone()));
+  /// Validate the JSON value against an identifier which doesn't refer to a
+  /// rule, i.e. a prelude type
+  fn validate_prelude_identifier(&mut self, ident: &Identifier<'a>) -> visitor::Result<Error> {
+    if is_ident_any_type(self.cddl, ident) {
+      return Ok(());
     }
 
-    Ok(())
-  }
+    match &self.json {
+      Value::Null if is_ident_null_data_type(self.cddl, ident) => Ok(()),
+      Value::Bool(b) => {
+        if is_ident_bool_data_type(self.cddl, ident) {
+          return Ok(());
+        }
 
-  fn add_error(&mut self, reason: String) {
-    self.errors.push(ValidationError {
-      reason,
-      cddl_location: self.cddl_location.clone(),
-      json_location: self.json_location.clone(),
-      is_multi_type_choice: self.is_multi_type_choice,
-      is_multi_group_choice: self.is_multi_group_choice,
-      is_group_to_choice_enum: self.is_group_to_choice_enum,
-      type_group_name_entry: self.type_group_name_entry.map(|e| e.to_string()),
-    });
-  }
-}
+        if ident_matches_bool_value(self.cddl, ident, *b) {
+          return Ok(());
+        }
 
-impl<'a, 'b> Visitor<'a, 'b, Error> for JSONValidator<'a> {
-  fn visit_type_rule(&mut self, tr: &TypeRule<'a>) -> visitor::Result<Error> {
-    if let Some(gp) = &tr.generic_params {
-      if let Some(gr) = self
-        .generic_rules
-        .iter_mut()
-        .find(|r| r.name == tr.name.ident)
-      {
-        gr.params = gp.params.iter().map(|p| p.param.ident).collect();
-      } else {
-        self.generic_rules.push(GenericRule {
-          name: tr.name.ident,
-          params: gp.params.iter().map(|p| p.param.ident).collect(),
-          args: vec![],
-        });
+        self.add_error(format!("expected type {}, got {}", ident, self.json));
+        Ok(())
       }
-    }
+      Value::Number(n) => {
+        if let Some(is_negative) = big_integer_sign(n) {
+          let is_valid = if is_ident_uint_data_type(self.cddl, ident) {
+            !is_negative
+          } else if is_ident_nint_data_type(self.cddl, ident) {
+            is_negative
+          } else {
+            is_ident_integer_data_type(self.cddl, ident)
+          };
 
-    let type_choice_alternates = type_choice_alternates_from_ident(self.cddl, &tr.name);
-    if !type_choice_alternates.is_empty() {
-      self.is_multi_type_choice = true;
+          if !is_valid {
+            self.add_error(format!("expected type {}, got {}", ident, self.json));
+          }
 
-      if self.json.is_array() {
-        self.is_multi_type_choice_type_rule_validating_array = true;
-      }
-    }
+          return Ok(());
+        }
 
-    let error_count = self.errors.len();
-    for t in type_choice_alternates {
-      let cur_errors = self.errors.len();
-      self.visit_type(t)?;
-      if self.errors.len() == cur_errors {
-        for _ in 0..self.errors.len() - error_count {
-          self.errors.pop();
-        }
-
-        return Ok(());
-      }
-    }
-
-    if tr.value.type_choices.len() > 1 && self.json.is_array() {
-      self.is_multi_type_choice_type_rule_validating_array = true;
-    }
-
-    self.visit_type(&tr.value)
-  }
-
-  fn visit_group_rule(&mut self, gr: &GroupRule<'a>) -> visitor::Result<Error> {
-    if let Some(gp) = &gr.generic_params {
-      if let Some(gr) = self
-        .generic_rules
-        .iter_mut()
-        .find(|r| r.name == gr.name.ident)
-      {
-        gr.params = gp.params.iter().map(|p| p.param.ident).collect();
-      } else {
-        self.generic_rules.push(GenericRule {
-          name: gr.name.ident,
-          params: gp.params.iter().map(|p| p.param.ident).collect(),
-          args: vec![],
-        });
-      }
-    }
-
-    let group_choice_alternates = group_choice_alternates_from_ident(self.cddl, &gr.name);
-    if !group_choice_alternates.is_empty() {
-      self.is_multi_group_choice = true;
-    }
-
-    let error_count = self.errors.len();
-    for ge in group_choice_alternates {
-      let cur_errors = self.errors.len();
-      self.visit_group_entry(ge)?;
-      if self.errors.len() == cur_errors {
-        for _ in 0..self.errors.len() - error_count {
-          self.errors.pop();
-        }
-
-        return Ok(());
-      }
-    }
-
-    self.visit_group_entry(&gr.entry)
-  }
-
-  fn visit_type(&mut self, t: &Type<'a>) -> visitor::Result<Error> {
-    if t.type_choices.len() > 1 {
-      self.is_multi_type_choice = true;
-    }
-
-    let initial_error_count = self.errors.len();
+        if is_ident_uint_data_type(self.cddl, ident) {
+          if n.is_u64() {
+            return Ok(());
+          }
+        } else if is_ident_nint_data_type(self.cddl, ident) {
+          if let Some(n) = n.as_i64() {
+            if n.is_negative() {
+              return Ok(());
+            }
+          } else if let Some(f) = n.as_f64().filter(|f| f.is_sign_negative()) {
+            if f.fract() != 0.0 {
+              self.add_error(format!(
+                "expected type {}, got {} which is not an integer",
+                ident, n
+              ));
 
-    for type_choice in t.type_choices.iter() {
-      // If validating an array whose elements are type choices (i.e. [ 1* tstr
-      // / integer ]), collect all errors and filter after the fact
-      if matches!(self.json, Value::Array(_))
-        && !self.is_multi_type_choice_type_rule_validating_array
-      {
-        let error_count = self.errors.len();
+              return Ok(());
+            }
 
-        self.visit_type_choice(type_choice)?;
+            if f < i64::MIN as f64 {
+              self.add_error(format!(
+                "expected type {}, got {} which is out of the supported integer range",
+                ident, n
+              ));
 
-        #[cfg(feature = "additional-controls")]
-        if self.errors.len() == error_count
-          && !self.has_feature_errors
-          && self.disabled_features.is_none()
-        {
-          // Disregard invalid type choice validation errors if one of the
-          // choices validates successfully
-          let type_choice_error_count = self.errors.len() - initial_error_count;
-          if type_choice_error_count > 0 {
-            for _ in 0..type_choice_error_count {
-              self.errors.pop();
+              return Ok(());
             }
           }
-        }
+        } else if is_ident_time_data_type(self.cddl, ident)
+          || is_ident_tdate_data_type(self.cddl, ident)
+        {
+          // A numeric tdate is treated as an epoch-based date, i.e. #6.1
+          if !is_valid_unix_timestamp(n) {
+            self.add_error(format!(
+              "expected {} data type, invalid UNIX timestamp {}",
+              ident, n,
+            ));
+          }
 
-        #[cfg(not(feature = "additional-controls"))]
-        if self.errors.len() == error_count {
-          // Disregard invalid type choice validation errors if one of the
-          // choices validates successfully
-          let type_choice_error_count = self.errors.len() - initial_error_count;
-          if type_choice_error_count > 0 {
-            for _ in 0..type_choice_error_count {
-              self.errors.pop();
+          return Ok(());
+        } else if is_ident_float_data_type(self.cddl, ident) && n.is_f64() {
+          if let Some(f) = n.as_f64() {
+            if !is_float_in_range(ident, f) {
+              self.add_error(format!(
+                "expected type {}, got {} which is out of range for {}",
+                ident, n, ident
+              ));
             }
           }
+
+          return Ok(());
+        } else if is_ident_integer_data_type(self.cddl, ident) && n.is_i64() {
+          return Ok(());
         }
 
-        continue;
+        self.add_error(format!("expected type {}, got {}", ident, self.json));
+        Ok(())
       }
+      Value::String(s) => {
+        if is_ident_numeric_data_type(self.cddl, ident) {
+          if let Some(n) = self.coerced_numeric_string() {
+            let json = std::mem::replace(&mut self.json, n);
+            let result = self.validate_prelude_identifier(ident);
+            self.json = json;
 
-      let error_count = self.errors.len();
-      self.visit_type_choice(type_choice)?;
-
-      #[cfg(feature = "additional-controls")]
-      if self.errors.len() == error_count
-        && !self.has_feature_errors
-        && self.disabled_features.is_none()
-      {
-        // Disregard invalid type choice validation errors if one of the
-        // choices validates successfully
-        let type_choice_error_count = self.errors.len() - initial_error_count;
-        if type_choice_error_count > 0 {
-          for _ in 0..type_choice_error_count {
-            self.errors.pop();
+            return result;
           }
         }
 
-        return Ok(());
-      }
-
-      #[cfg(not(feature = "additional-controls"))]
-      if self.errors.len() == error_count {
-        // Disregard invalid type choice validation errors if one of the
-        // choices validates successfully
-        let type_choice_error_count = self.errors.len() - initial_error_count;
-        if type_choice_error_count > 0 {
-          for _ in 0..type_choice_error_count {
-            self.errors.pop();
+        if is_ident_uri_data_type(self.cddl, ident) {
+          #[cfg(feature = "uri")]
+          if let Err(e) = uriparse::URI::try_from(&**s) {
+            self.add_error_with_source(format!("expected URI data type, decoding error: {}", e), e);
+          }
+        } else if is_ident_b64url_data_type(self.cddl, ident) {
+          if let Err(e) = base64_url::decode(s) {
+            self.add_error_with_source(
+              format!("expected base64 URL data type, decoding error: {}", e),
+              e,
+            );
+          }
+        } else if is_ident_byte_string_data_type(self.cddl, ident) {
+          // JSON has no byte strings, so they are expected to be base64
+          // encoded text
+          if let Err(e) = data_encoding::BASE64.decode(s.as_bytes()) {
+            self.add_error(format!(
+              "expected base64 encoded byte string, decoding error: {}",
+              e
+            ));
+          }
+        } else if is_ident_tdate_data_type(self.cddl, ident) {
+          #[cfg(feature = "tdate")]
+          if let Err(e) = chrono::DateTime::parse_from_rfc3339(s) {
+            self.add_error_with_source(
+              format!("expected tdate data type, decoding error: {}", e),
+              e,
+            );
           }
+        } else if is_ident_string_data_type(self.cddl, ident) {
+          return Ok(());
+        } else {
+          self.add_error(format!("expected type {}, got {}", ident, self.json));
         }
 
-        return Ok(());
+        Ok(())
       }
-    }
+      Value::Array(_) => self.validate_array_items(&ArrayItemToken::Identifier(ident)),
+      Value::Object(o) => match &self.occurrence {
+        #[cfg(feature = "ast-span")]
+        Some(Occur::Optional { .. }) | None => {
+          if token::lookup_ident(ident.ident)
+            .in_standard_prelude()
+            .is_some()
+          {
+            self.add_error(format!(
+              "expected object value of type {}, got object",
+              ident.ident
+            ));
+            return Ok(());
+          }
 
-    Ok(())
-  }
+          self.visit_value(&token::Value::TEXT(ident.ident.into()))
+        }
+        #[cfg(not(feature = "ast-span"))]
+        Some(Occur::Optional {}) | None => {
+          if token::lookup_ident(ident.ident)
+            .in_standard_prelude()
+            .is_some()
+          {
+            self.add_error(format!(
+              "expected object value of type {}, got object",
+              ident.ident
+            ));
+            return Ok(());
+          }
 
-  fn visit_group(&mut self, g: &Group<'a>) -> visitor::Result<Error> {
-    if g.group_choices.len() > 1 {
-      self.is_multi_group_choice = true;
-    }
+          self.visit_value(&token::Value::TEXT(ident.ident.into()))
+        }
+        Some(occur) => {
+          if is_ident_string_data_type(self.cddl, ident) {
+            let values_to_validate = o
+              .iter()
+              .filter_map(|(k, v)| match &self.validated_keys {
+                Some(keys) if !keys.contains(k) => Some(v.clone()),
+                Some(_) => None,
+                None => Some(v.clone()),
+              })
+              .collect::<Vec<_>>();
 
-    // Map equality/inequality validation
-    if self.is_ctrl_map_equality {
-      if let Some(t) = &self.ctrl {
-        if let Value::Object(o) = &self.json {
-          let entry_counts = entry_counts_from_group(self.cddl, g);
+            self.values_to_validate = Some(values_to_validate);
+          } else if is_ident_integer_data_type(self.cddl, ident)
+            || is_ident_uint_data_type(self.cddl, ident)
+            || is_ident_nint_data_type(self.cddl, ident)
+          {
+            // JSON object keys are always strings, so integer keys are
+            // expected to be encoded as their decimal representation
+            let mut values_to_validate = Vec::new();
+            let mut invalid_keys = Vec::new();
+            for (k, v) in o.iter() {
+              if matches!(&self.validated_keys, Some(keys) if keys.contains(k)) {
+                continue;
+              }
 
-          let len = o.len();
-          if let ControlOperator::EQ = t {
-            if !validate_entry_count(&entry_counts, len) {
-              for ec in entry_counts.iter() {
-                if let Some(occur) = &ec.entry_occurrence {
-                  self.add_error(format!(
-                    "map equality error. expected object with number of entries per occurrence {}",
-                    occur,
-                  ));
-                } else {
-                  self.add_error(format!(
-                    "map equality error, expected object with length {}, got {}",
-                    ec.count, len
-                  ));
-                }
+              if is_integer_key_of_type(self.cddl, ident, k) {
+                values_to_validate.push(v.clone());
+              } else {
+                invalid_keys.push(k.clone());
               }
-              return Ok(());
             }
-          } else if let ControlOperator::NE | ControlOperator::DEFAULT = t {
-            if !validate_entry_count(&entry_counts, len) {
-              for ec in entry_counts.iter() {
-                if let Some(occur) = &ec.entry_occurrence {
-                  self.add_error(format!(
-                    "map inequality error. expected object with number of entries not per occurrence {}",
-                    occur,
-                  ));
-                } else {
-                  self.add_error(format!(
-                    "map inequality error, expected object not with length {}, got {}",
-                    ec.count, len
-                  ));
-                }
-              }
-              return Ok(());
+
+            for k in invalid_keys.iter() {
+              let error = ValidationError::from_validator(
+                self,
+                format!("expected object key of type {}, got {:?}", ident, k),
+              );
+              self.errors.push(error);
             }
-          }
-        }
-      }
-    }
 
-    self.is_ctrl_map_equality = false;
+            self.values_to_validate = Some(values_to_validate);
+          }
 
-    let initial_error_count = self.errors.len();
-    for group_choice in g.group_choices.iter() {
-      let error_count = self.errors.len();
-      self.visit_group_choice(group_choice)?;
-      if self.errors.len() == error_count {
-        // Disregard invalid group choice validation errors if one of the
-        // choices validates successfully
-        let group_choice_error_count = self.errors.len() - initial_error_count;
-        if group_choice_error_count > 0 {
-          for _ in 0..group_choice_error_count {
-            self.errors.pop();
-          }
-        }
-
-        return Ok(());
-      }
-    }
-
-    Ok(())
-  }
-
-  fn visit_group_choice(&mut self, gc: &GroupChoice<'a>) -> visitor::Result<Error> {
-    if self.is_group_to_choice_enum {
-      let initial_error_count = self.errors.len();
-      for tc in type_choices_from_group_choice(self.cddl, gc).iter() {
-        let error_count = self.errors.len();
-        self.visit_type_choice(tc)?;
-        if self.errors.len() == error_count {
-          let type_choice_error_count = self.errors.len() - initial_error_count;
-          if type_choice_error_count > 0 {
-            for _ in 0..type_choice_error_count {
-              self.errors.pop();
+          #[cfg(feature = "ast-span")]
+          if let Occur::ZeroOrMore { .. } | Occur::OneOrMore { .. } = occur {
+            if let Occur::OneOrMore { .. } = occur {
+              if o.is_empty() {
+                self.add_occurrence_error(format!(
+                  "object cannot be empty, one or more entries with key type {} required",
+                  ident
+                ));
+                return Ok(());
+              }
             }
-          }
-          return Ok(());
-        }
-      }
-
-      return Ok(());
-    }
-
-    for (idx, ge) in gc.group_entries.iter().enumerate() {
-      if let Some(current_index) = self.group_entry_idx.as_mut() {
-        if idx != 0 {
-          *current_index += 1;
-        }
-      } else {
-        self.group_entry_idx = Some(idx);
-      }
+          } else if let Occur::Exact { lower, upper, .. } = occur {
+            if let Some(values_to_validate) = &self.values_to_validate {
+              if let Some(lower) = lower {
+                if let Some(upper) = upper {
+                  if values_to_validate.len() < *lower || values_to_validate.len() > *upper {
+                    if lower == upper {
+                      self.add_occurrence_error(format!(
+                        "object must contain exactly {} entries of key of type {}",
+                        lower, ident,
+                      ));
+                    } else {
+                      self.add_occurrence_error(format!(
+                        "object must contain between {} and {} entries of key of type {}",
+                        lower, upper, ident,
+                      ));
+                    }
 
-      self.visit_group_entry(&ge.0)?;
-    }
+                    return Ok(());
+                  }
+                }
 
-    Ok(())
-  }
+                if values_to_validate.len() < *lower {
+                  self.add_occurrence_error(format!(
+                    "object must contain at least {} entries of key of type {}",
+                    lower, ident,
+                  ));
 
-  fn visit_range(
-    &mut self,
-    lower: &Type2,
-    upper: &Type2,
-    is_inclusive: bool,
-  ) -> visitor::Result<Error> {
-    if matches!(&self.json, Value::Array(_)) {
-      return self.validate_array_items(&ArrayItemToken::Range(lower, upper, is_inclusive));
-    }
+                  return Ok(());
+                }
+              }
 
-    match lower {
-      Type2::IntValue { value: l, .. } => match upper {
-        Type2::IntValue { value: u, .. } => {
-          let error_str = if is_inclusive {
-            format!(
-              "expected integer to be in range {} <= value <= {}, got {}",
-              l, u, self.json
-            )
-          } else {
-            format!(
-              "expected integer to be in range {} < value < {}, got {}",
-              l, u, self.json
-            )
-          };
+              if let Some(upper) = upper {
+                if values_to_validate.len() > *upper {
+                  self.add_occurrence_error(format!(
+                    "object must contain no more than {} entries of key of type {}",
+                    upper, ident,
+                  ));
 
-          match &self.json {
-            Value::Number(n) => {
-              if let Some(i) = n.as_i64() {
-                if is_inclusive {
-                  if i < *l as i64 || i > *u as i64 {
-                    self.add_error(error_str);
-                  } else {
-                    return Ok(());
-                  }
-                } else if i <= *l as i64 || i >= *u as i64 {
-                  self.add_error(error_str);
-                  return Ok(());
-                } else {
                   return Ok(());
                 }
-              } else {
-                self.add_error(error_str);
-                return Ok(());
               }
-            }
-            _ => {
-              self.add_error(error_str);
+
               return Ok(());
             }
           }
-        }
-        Type2::UintValue { value: u, .. } => {
-          let error_str = if is_inclusive {
-            format!(
-              "expected integer to be in range {} <= value <= {}, got {}",
-              l, u, self.json
-            )
-          } else {
-            format!(
-              "expected integer to be in range {} < value < {}, got {}",
-              l, u, self.json
-            )
-          };
 
-          match &self.json {
-            Value::Number(n) => {
-              if let Some(i) = n.as_i64() {
-                if is_inclusive {
-                  if i < *l as i64 || i > *u as i64 {
-                    self.add_error(error_str);
-                  } else {
-                    return Ok(());
-                  }
-                } else if i <= *l as i64 || i >= *u as i64 {
-                  self.add_error(error_str);
-                  return Ok(());
-                } else {
-                  return Ok(());
-                }
-              } else {
-                self.add_error(error_str);
+          #[cfg(not(feature = "ast-span"))]
+          if let Occur::ZeroOrMore {} | Occur::OneOrMore {} = occur {
+            if let Occur::OneOrMore {} = occur {
+              if o.is_empty() {
+                self.add_occurrence_error(format!(
+                  "object cannot be empty, one or more entries with key type {} required",
+                  ident
+                ));
                 return Ok(());
               }
             }
-            _ => {
-              self.add_error(error_str);
-              return Ok(());
-            }
-          }
-        }
-        _ => {
-          self.add_error(format!(
-            "invalid cddl range. upper value must be an integer type. got {}",
-            upper
-          ));
-          return Ok(());
-        }
-      },
-      Type2::UintValue { value: l, .. } => match upper {
-        Type2::UintValue { value: u, .. } => {
-          let error_str = if is_inclusive {
-            format!(
-              "expected uint to be in range {} <= value <= {}, got {}",
-              l, u, self.json
-            )
-          } else {
-            format!(
-              "expected uint to be in range {} < value < {}, got {}",
-              l, u, self.json
-            )
-          };
+          } else if let Occur::Exact { lower, upper } = occur {
+            if let Some(values_to_validate) = &self.values_to_validate {
+              if let Some(lower) = lower {
+                if let Some(upper) = upper {
+                  if values_to_validate.len() < *lower || values_to_validate.len() > *upper {
+                    if lower == upper {
+                      self.add_occurrence_error(format!(
+                        "object must contain exactly {} entries of key of type {}",
+                        lower, ident,
+                      ));
+                    } else {
+                      self.add_occurrence_error(format!(
+                        "object must contain between {} and {} entries of key of type {}",
+                        lower, upper, ident,
+                      ));
+                    }
 
-          match &self.json {
-            Value::Number(n) => {
-              if let Some(i) = n.as_u64() {
-                if is_inclusive {
-                  if i < *l as u64 || i > *u as u64 {
-                    self.add_error(error_str);
-                  } else {
                     return Ok(());
                   }
-                } else if i <= *l as u64 || i >= *u as u64 {
-                  self.add_error(error_str);
-                  return Ok(());
-                } else {
+                }
+
+                if values_to_validate.len() < *lower {
+                  self.add_occurrence_error(format!(
+                    "object must contain at least {} entries of key of type {}",
+                    lower, ident,
+                  ));
+
                   return Ok(());
                 }
-              } else {
-                self.add_error(error_str);
-                return Ok(());
               }
-            }
-            Value::String(s) => match self.ctrl {
-              Some(ControlOperator::SIZE) => {
-                let len = s.len();
-                let s = s.clone();
-                if is_inclusive {
-                  if s.len() < *l || s.len() > *u {
-                    self.add_error(format!(
-                      "expected \"{}\" string length to be in the range {} <= value <= {}, got {}",
-                      s, l, u, len
-                    ));
-                  }
 
-                  return Ok(());
-                } else if s.len() <= *l || s.len() >= *u {
-                  self.add_error(format!(
-                    "expected \"{}\" string length to be in the range {} < value < {}, got {}",
-                    s, l, u, len
+              if let Some(upper) = upper {
+                if values_to_validate.len() > *upper {
+                  self.add_occurrence_error(format!(
+                    "object must contain no more than {} entries of key of type {}",
+                    upper, ident,
                   ));
+
                   return Ok(());
                 }
               }
-              _ => {
-                self.add_error("string value cannot be validated against a range without the .size control operator".to_string());
-                return Ok(());
-              }
-            },
-            _ => {
-              self.add_error(error_str);
+
               return Ok(());
             }
           }
+
+          Ok(())
         }
-        _ => {
+      },
+      _ => {
+        if let Some(cut_value) = self.cut_value.take() {
           self.add_error(format!(
-            "invalid cddl range. upper value must be a uint type. got {}",
-            upper
+            "cut present for member key {}. expected type {}, got {}",
+            cut_value, ident, self.json
           ));
-          return Ok(());
+        } else {
+          self.add_error(format!("expected type {}, got {}", ident, self.json));
         }
-      },
-      Type2::FloatValue { value: l, .. } => match upper {
-        Type2::FloatValue { value: u, .. } => {
-          let error_str = if is_inclusive {
-            format!(
-              "expected float to be in range {} <= value <= {}, got {}",
-              l, u, self.json
-            )
-          } else {
-            format!(
-              "expected float to be in range {} < value < {}, got {}",
-              l, u, self.json
-            )
-          };
+        Ok(())
+      }
+    }
+  }
+}
 
-          match &self.json {
-            Value::Number(n) => {
-              if let Some(f) = n.as_f64() {
-                if is_inclusive {
-                  if f < *l || f > *u {
-                    self.add_error(error_str);
-                  } else {
-                    return Ok(());
-                  }
-                } else if f <= *l || f >= *u {
-                  self.add_error(error_str);
-                  return Ok(());
-                } else {
-                  return Ok(());
-                }
-              } else {
-                self.add_error(error_str);
-                return Ok(());
-              }
-            }
-            _ => {
-              self.add_error(error_str);
-              return Ok(());
-            }
-          }
+impl<'a, 'b> Validator<'a, 'b, Error> for JSONValidator<'a> {
+  /// Validate
+  fn validate(&mut self) -> std::result::Result<(), Error> {
+    let mut has_root = false;
+    for r in self.cddl.rules.iter() {
+      // First type rule is root
+      if let Rule::Type { rule, .. } = r {
+        if rule.generic_params.is_none() {
+          has_root = true;
+          self.is_root = true;
+          self.visited_rules.push(rule.name.ident);
+          self.visit_type_rule(rule)?;
+          self.visited_rules.pop();
+          self.is_root = false;
+          break;
         }
-        _ => {
-          self.add_error(format!(
-            "invalid cddl range. upper value must be a float type. got {}",
-            upper
-          ));
-          return Ok(());
+      }
+    }
+
+    if !has_root {
+      return Err(Error::NoRootRule);
+    }
+
+    if !self.errors.is_empty() {
+      return Err(Error::Validation(dedup_errors(&self.errors)));
+    }
+
+    Ok(())
+  }
+
+  fn add_error(&mut self, reason: String) {
+    self.errors.push(ValidationError {
+      reason,
+      cddl_location: self.cddl_location.clone(),
+      json_location: self.json_location.clone(),
+      is_multi_type_choice: self.is_multi_type_choice,
+      is_multi_group_choice: self.is_multi_group_choice,
+      is_group_to_choice_enum: self.is_group_to_choice_enum,
+      type_group_name_entry: self.type_group_name_entry.map(|e| e.to_string()),
+      is_occurrence: false,
+      rule_chain: self.rule_chain.iter().map(|r| r.to_string()).collect(),
+      alternatives: Vec::new(),
+      source: None,
+    });
+  }
+}
+
+impl<'a, 'b> Visitor<'a, 'b, Error> for JSONValidator<'a> {
+  fn visit_type_rule(&mut self, tr: &TypeRule<'a>) -> visitor::Result<Error> {
+    if let Some(gp) = &tr.generic_params {
+      if let Some(gr) = self
+        .generic_rules
+        .iter_mut()
+        .find(|r| r.name == tr.name.ident)
+      {
+        gr.params = gp.params.iter().map(|p| p.param.ident).collect();
+      } else {
+        self.generic_rules.push(GenericRule {
+          name: tr.name.ident,
+          params: gp.params.iter().map(|p| p.param.ident).collect(),
+          args: vec![],
+        });
+      }
+    }
+
+    let type_choice_alternates = type_choice_alternates_from_ident(self.cddl, &tr.name);
+    if !type_choice_alternates.is_empty() {
+      self.is_multi_type_choice = true;
+
+      if self.json.is_array() {
+        self.is_multi_type_choice_type_rule_validating_array = true;
+      }
+    }
+
+    let error_count = self.errors.len();
+    for t in type_choice_alternates {
+      let cur_errors = self.errors.len();
+      self.visit_type(t)?;
+      if self.errors.len() == cur_errors {
+        for _ in 0..self.errors.len() - error_count {
+          self.errors.pop();
         }
-      },
-      _ => {
-        self.add_error(
-          "invalid cddl range. upper and lower values must be either integers or floats"
-            .to_string(),
-        );
 
         return Ok(());
       }
     }
 
-    Ok(())
+    if tr.value.type_choices.len() > 1 && self.json.is_array() {
+      self.is_multi_type_choice_type_rule_validating_array = true;
+    }
+
+    self.visit_type(&tr.value)
   }
 
-  fn visit_control_operator(
-    &mut self,
-    target: &Type2<'a>,
-    ctrl: ControlOperator,
-    controller: &Type2<'a>,
-  ) -> visitor::Result<Error> {
-    if let Type2::Typename {
-      ident: target_ident,
-      ..
-    } = target
-    {
-      if let Type2::Typename {
-        ident: controller_ident,
-        ..
-      } = controller
+  fn visit_group_rule(&mut self, gr: &GroupRule<'a>) -> visitor::Result<Error> {
+    if let Some(gp) = &gr.generic_params {
+      if let Some(gr) = self
+        .generic_rules
+        .iter_mut()
+        .find(|r| r.name == gr.name.ident)
       {
-        if let Some(name) = self.eval_generic_rule {
-          if let Some(gr) = self
-            .generic_rules
-            .iter()
-            .cloned()
-            .find(|gr| gr.name == name)
-          {
-            for (idx, gp) in gr.params.iter().enumerate() {
-              if let Some(arg) = gr.args.get(idx) {
-                if *gp == target_ident.ident {
-                  let t2 = Type2::from(arg.clone());
+        gr.params = gp.params.iter().map(|p| p.param.ident).collect();
+      } else {
+        self.generic_rules.push(GenericRule {
+          name: gr.name.ident,
+          params: gp.params.iter().map(|p| p.param.ident).collect(),
+          args: vec![],
+        });
+      }
+    }
 
-                  if *gp == controller_ident.ident {
-                    return self.visit_control_operator(&t2, ctrl, &t2);
-                  }
+    let group_choice_alternates = group_choice_alternates_from_ident(self.cddl, &gr.name);
+    if !group_choice_alternates.is_empty() {
+      self.is_multi_group_choice = true;
+    }
 
-                  return self.visit_control_operator(&arg.type2, ctrl, controller);
-                }
-              }
-            }
-          }
+    let error_count = self.errors.len();
+    for ge in group_choice_alternates {
+      let cur_errors = self.errors.len();
+      self.visit_group_entry(ge)?;
+      if self.errors.len() == cur_errors {
+        for _ in 0..self.errors.len() - error_count {
+          self.errors.pop();
         }
+
+        return Ok(());
       }
+    }
 
-      if let Some(name) = self.eval_generic_rule {
-        if let Some(gr) = self
-          .generic_rules
-          .iter()
-          .cloned()
-          .find(|gr| gr.name == name)
-        {
-          for (idx, gp) in gr.params.iter().enumerate() {
-            if let Some(arg) = gr.args.get(idx) {
-              if *gp == target_ident.ident {
-                let t2 = Type2::from(arg.clone());
-                return self.visit_control_operator(&t2, ctrl, controller);
-              }
+    self.visit_group_entry(&gr.entry)
+  }
+
+  fn visit_type(&mut self, t: &Type<'a>) -> visitor::Result<Error> {
+    if t.type_choices.len() > 1 {
+      self.is_multi_type_choice = true;
+    }
+
+    let initial_error_count = self.errors.len();
+    let mut is_array_choice_valid = false;
+    let mut choice_errors = Vec::new();
+    let mut failed_choice_keys = Vec::new();
+
+    for type_choice in t.type_choices.iter() {
+      // If validating an array whose elements are type choices (i.e. [ 1* tstr
+      // / integer ]), collect all errors and filter after the fact
+      if matches!(self.json, Value::Array(_))
+        && !self.is_multi_type_choice_type_rule_validating_array
+      {
+        let error_count = self.errors.len();
+
+        self.visit_type_choice(type_choice)?;
+
+        #[cfg(feature = "additional-controls")]
+        if self.errors.len() == error_count && !self.has_feature_errors {
+          // Disregard invalid type choice validation errors if one of the
+          // choices validates successfully
+          let type_choice_error_count = self.errors.len() - initial_error_count;
+          if type_choice_error_count > 0 {
+            for _ in 0..type_choice_error_count {
+              self.errors.pop();
             }
           }
+
+          is_array_choice_valid = true;
         }
-      }
-    }
 
-    match ctrl {
-      ControlOperator::EQ => match target {
-        Type2::Typename { ident, .. } => {
-          if is_ident_string_data_type(self.cddl, ident)
-            || is_ident_numeric_data_type(self.cddl, ident)
-          {
-            return self.visit_type2(controller);
+        #[cfg(not(feature = "additional-controls"))]
+        if self.errors.len() == error_count {
+          // Disregard invalid type choice validation errors if one of the
+          // choices validates successfully
+          let type_choice_error_count = self.errors.len() - initial_error_count;
+          if type_choice_error_count > 0 {
+            for _ in 0..type_choice_error_count {
+              self.errors.pop();
+            }
           }
+
+          is_array_choice_valid = true;
         }
-        Type2::Array { group, .. } => {
-          if let Value::Array(_) = &self.json {
-            self.entry_counts = Some(entry_counts_from_group(self.cddl, group));
-            self.visit_type2(controller)?;
-            self.entry_counts = None;
-            return Ok(());
+
+        continue;
+      }
+
+      let validated_keys = self.validated_keys.clone();
+      let error_count = self.errors.len();
+      self.visit_type_choice(type_choice)?;
+      choice_errors.push((
+        expected_json_kind(self.cddl, &type_choice.type1.type2),
+        type2_discriminant_matches(
+          self.cddl,
+          &type_choice.type1.type2,
+          &self.json,
+          &mut Vec::new(),
+        ),
+        &type_choice.type1,
+        error_count..self.errors.len(),
+      ));
+
+      #[cfg(feature = "additional-controls")]
+      if self.errors.len() == error_count && !self.has_feature_errors {
+        // Disregard invalid type choice validation errors if one of the
+        // choices validates successfully
+        let type_choice_error_count = self.errors.len() - initial_error_count;
+        if type_choice_error_count > 0 {
+          for _ in 0..type_choice_error_count {
+            self.errors.pop();
           }
         }
-        Type2::Map { .. } => {
-          if let Value::Object(_) = &self.json {
-            self.ctrl = Some(ctrl);
-            self.is_ctrl_map_equality = true;
-            self.visit_type2(controller)?;
-            self.ctrl = None;
-            self.is_ctrl_map_equality = false;
-            return Ok(());
-          }
-        }
-        _ => self.add_error(format!(
-          "target for .eq operator must be a string, numerical, array or map data type, got {}",
-          target
-        )),
-      },
-      ControlOperator::NE => match target {
-        Type2::Typename { ident, .. } => {
-          if is_ident_string_data_type(self.cddl, ident)
-            || is_ident_numeric_data_type(self.cddl, ident)
-          {
-            self.ctrl = Some(ctrl);
-            self.visit_type2(controller)?;
-            self.ctrl = None;
-            return Ok(());
-          }
-        }
-        Type2::Array { .. } => {
-          if let Value::Array(_) = &self.json {
-            self.ctrl = Some(ctrl);
-            self.visit_type2(controller)?;
-            self.ctrl = None;
-            return Ok(());
-          }
-        }
-        Type2::Map { .. } => {
-          if let Value::Object(_) = &self.json {
-            self.ctrl = Some(ctrl);
-            self.is_ctrl_map_equality = true;
-            self.visit_type2(controller)?;
-            self.ctrl = None;
-            self.is_ctrl_map_equality = false;
-            return Ok(());
-          }
-        }
-        _ => self.add_error(format!(
-          "target for .ne operator must be a string, numerical, array or map data type, got {}",
-          target
-        )),
-      },
-      ControlOperator::LT | ControlOperator::GT | ControlOperator::GE | ControlOperator::LE => {
-        match target {
-          Type2::Typename { ident, .. } if is_ident_numeric_data_type(self.cddl, ident) => {
-            self.ctrl = Some(ctrl);
-            self.visit_type2(controller)?;
-            self.ctrl = None;
-          }
-          _ => {
-            self.add_error(format!(
-              "target for .lt, .gt, .ge or .le operator must be a numerical data type, got {}",
-              target
-            ));
-          }
-        }
-      }
-      ControlOperator::SIZE => match target {
-        Type2::Typename { ident, .. }
-          if is_ident_string_data_type(self.cddl, ident)
-            || is_ident_uint_data_type(self.cddl, ident) =>
-        {
-          self.ctrl = Some(ctrl);
-          self.visit_type2(controller)?;
-          self.ctrl = None;
-        }
-        _ => {
-          self.add_error(format!(
-            "target for .size must a string or uint data type, got {}",
-            target
-          ));
-        }
-      },
-      ControlOperator::AND => {
-        self.ctrl = Some(ctrl);
-        self.visit_type2(target)?;
-        self.visit_type2(controller)?;
-        self.ctrl = None;
+
+        return Ok(());
       }
-      ControlOperator::WITHIN => {
-        self.ctrl = Some(ctrl);
-        let error_count = self.errors.len();
-        self.visit_type2(target)?;
-        let no_errors = self.errors.len() == error_count;
-        self.visit_type2(controller)?;
-        if no_errors && self.errors.len() > error_count {
-          for _ in 0..self.errors.len() - error_count {
+
+      #[cfg(not(feature = "additional-controls"))]
+      if self.errors.len() == error_count {
+        // Disregard invalid type choice validation errors if one of the
+        // choices validates successfully
+        let type_choice_error_count = self.errors.len() - initial_error_count;
+        if type_choice_error_count > 0 {
+          for _ in 0..type_choice_error_count {
             self.errors.pop();
           }
-
-          self.add_error(format!(
-            "expected type {} .within type {}, got {}",
-            target, controller, self.json,
-          ));
         }
 
-        self.ctrl = None;
-      }
-      ControlOperator::DEFAULT => {
-        self.ctrl = Some(ctrl);
-        let error_count = self.errors.len();
-        self.visit_type2(target)?;
-        if self.errors.len() != error_count {
-          #[cfg(feature = "ast-span")]
-          if let Some(Occur::Optional { .. }) = self.occurrence.take() {
-            self.add_error(format!(
-              "expected default value {}, got {}",
-              controller, self.json
-            ));
-          }
-          #[cfg(not(feature = "ast-span"))]
-          if let Some(Occur::Optional {}) = self.occurrence.take() {
-            self.add_error(format!(
-              "expected default value {}, got {}",
-              controller, self.json
-            ));
-          }
-        }
-        self.ctrl = None;
-      }
-      ControlOperator::REGEXP | ControlOperator::PCRE => {
-        self.ctrl = Some(ctrl);
-        match target {
-          Type2::Typename { ident, .. } if is_ident_string_data_type(self.cddl, ident) => {
-            match self.json {
-              Value::String(_) | Value::Array(_) => self.visit_type2(controller)?,
-              _ => self.add_error(format!(
-                ".regexp/.pcre control can only be matched against JSON string, got {}",
-                self.json
-              )),
-            }
-          }
-          _ => self.add_error(format!(
-            ".regexp/.pcre control can only be matched against string data type, got {}",
-            target
-          )),
-        }
-        self.ctrl = None;
+        return Ok(());
       }
-      #[cfg(feature = "additional-controls")]
-      ControlOperator::CAT => {
-        self.ctrl = Some(ctrl);
 
-        match cat_operation(self.cddl, target, controller, false) {
-          Ok(values) => {
-            let error_count = self.errors.len();
+      let mut choice_keys = self.restore_validated_keys(validated_keys);
+      failed_choice_keys.append(&mut choice_keys);
+    }
 
-            for v in values.iter() {
-              let cur_errors = self.errors.len();
+    // Keys validated by the failed choices are kept if none of them validates
+    if !failed_choice_keys.is_empty() {
+      self
+        .validated_keys
+        .get_or_insert_with(Vec::new)
+        .append(&mut failed_choice_keys);
+    }
 
-              self.visit_type2(v)?;
+    // Neither do choices following the one which validated the array
+    if is_array_choice_valid {
+      self.errors.truncate(initial_error_count);
+    }
 
-              if self.errors.len() == cur_errors {
-                for _ in 0..self.errors.len() - error_count {
-                  self.errors.pop();
-                }
+    if is_array_choice_valid || choice_errors.is_empty() {
+      return Ok(());
+    }
 
-                break;
-              }
-            }
-          }
-          Err(e) => self.add_error(e),
-        }
+    // If only one of the failed choices admits values of the JSON value's
+    // kind, the errors of the other choices are noise
+    let value_kind = json_kind(&self.json);
+    let matching_kind = choice_errors
+      .iter()
+      .enumerate()
+      .filter(|(_, (kind, ..))| *kind == Some(value_kind))
+      .map(|(idx, _)| idx)
+      .collect::<Vec<_>>();
+    let retained = if choice_errors.len() > 1
+      && choice_errors.iter().all(|(kind, ..)| kind.is_some())
+      && matching_kind.len() == 1
+    {
+      matching_kind
+    } else if let Some(discriminated) = discriminated_choices(
+      &choice_errors
+        .iter()
+        .map(|(_, discriminant_matches, ..)| *discriminant_matches)
+        .collect::<Vec<_>>(),
+    ) {
+      discriminated
+    } else {
+      (0..choice_errors.len()).collect()
+    };
 
-        self.ctrl = None;
-      }
-      #[cfg(feature = "additional-controls")]
-      ControlOperator::DET => {
-        self.ctrl = Some(ctrl);
+    let retained = retained
+      .into_iter()
+      .map(|idx| {
+        let (.., type1, errors) = &choice_errors[idx];
+        (Some(type1.to_string()), errors.clone())
+      })
+      .collect::<Vec<_>>();
+    self.retain_choice_errors(initial_error_count, &retained);
 
-        match cat_operation(self.cddl, target, controller, true) {
-          Ok(values) => {
-            let error_count = self.errors.len();
+    Ok(())
+  }
 
-            for v in values.iter() {
-              let cur_errors = self.errors.len();
+  fn visit_group(&mut self, g: &Group<'a>) -> visitor::Result<Error> {
+    if g.group_choices.len() > 1 {
+      self.is_multi_group_choice = true;
+    }
 
-              self.visit_type2(v)?;
+    // Map equality/inequality validation
+    if self.is_ctrl_map_equality {
+      if let Some(t) = &self.ctrl {
+        if let Value::Object(o) = &self.json {
+          let entry_counts = entry_counts_from_group(self.cddl, g);
 
-              if self.errors.len() == cur_errors {
-                for _ in 0..self.errors.len() - error_count {
-                  self.errors.pop();
+          let len = o.len();
+          if let ControlOperator::EQ = t {
+            if !validate_entry_count(&entry_counts, len) {
+              for ec in entry_counts.iter() {
+                if let Some(occur) = &ec.entry_occurrence {
+                  self.add_error(format!(
+                    "map equality error. expected object with number of entries per occurrence {}",
+                    occur,
+                  ));
+                } else {
+                  self.add_error(format!(
+                    "map equality error, expected object with length {}, got {}",
+                    ec.count, len
+                  ));
                 }
-
-                break;
               }
+              return Ok(());
             }
-          }
-          Err(e) => self.add_error(e),
-        }
-
-        self.ctrl = None;
-      }
-      #[cfg(feature = "additional-controls")]
-      ControlOperator::PLUS => {
-        self.ctrl = Some(ctrl);
-
-        match plus_operation(self.cddl, target, controller) {
-          Ok(values) => {
-            let error_count = self.errors.len();
-            for v in values.iter() {
-              let cur_errors = self.errors.len();
-
-              self.visit_type2(v)?;
-              if self.errors.len() == cur_errors {
-                for _ in 0..self.errors.len() - error_count {
-                  self.errors.pop();
+          } else if let ControlOperator::NE = t {
+            if !validate_entry_count(&entry_counts, len) {
+              for ec in entry_counts.iter() {
+                if let Some(occur) = &ec.entry_occurrence {
+                  self.add_error(format!(
+                    "map inequality error. expected object with number of entries not per occurrence {}",
+                    occur,
+                  ));
+                } else {
+                  self.add_error(format!(
+                    "map inequality error, expected object not with length {}, got {}",
+                    ec.count, len
+                  ));
                 }
-
-                break;
               }
+              return Ok(());
             }
           }
-
-          Err(e) => self.add_error(e),
         }
-
-        self.ctrl = None;
       }
-      #[cfg(feature = "additional-controls")]
-      ControlOperator::ABNF => {
-        self.ctrl = Some(ctrl);
-
-        match target {
-          Type2::Typename { ident, .. } if is_ident_string_data_type(self.cddl, ident) => {
-            match self.json {
-              Value::String(_) | Value::Array(_) => {
-                if let Type2::ParenthesizedType { pt, .. } = controller {
-                  match abnf_from_complex_controller(self.cddl, pt) {
-                    Ok(values) => {
-                      let error_count = self.errors.len();
-                      for v in values.iter() {
-                        let cur_errors = self.errors.len();
-
-                        self.visit_type2(v)?;
+    }
 
-                        if self.errors.len() == cur_errors {
-                          for _ in 0..self.errors.len() - error_count {
-                            self.errors.pop();
-                          }
+    self.is_ctrl_map_equality = false;
 
-                          break;
-                        }
-                      }
-                    }
-                    Err(e) => self.add_error(e),
-                  }
-                } else {
-                  self.visit_type2(controller)?
-                }
-              }
-              _ => self.add_error(format!(
-                ".abnf control can only be matched against a JSON string, got {}",
-                self.json,
-              )),
-            }
+    let initial_error_count = self.errors.len();
+    let mut choice_errors = Vec::new();
+    let mut failed_choice_keys = Vec::new();
+    for group_choice in g.group_choices.iter() {
+      let validated_keys = self.validated_keys.clone();
+      let error_count = self.errors.len();
+      self.visit_group_choice(group_choice)?;
+      choice_errors.push((
+        group_choice_discriminant_matches(self.cddl, group_choice, &self.json, &mut Vec::new()),
+        error_count..self.errors.len(),
+      ));
+      if self.errors.len() == error_count {
+        // Disregard invalid group choice validation errors if one of the
+        // choices validates successfully
+        let group_choice_error_count = self.errors.len() - initial_error_count;
+        if group_choice_error_count > 0 {
+          for _ in 0..group_choice_error_count {
+            self.errors.pop();
           }
-          _ => self.add_error(format!(
-            ".abnf can only be matched against string data type, got {}",
-            target,
-          )),
         }
 
-        self.ctrl = None;
+        return Ok(());
       }
-      #[cfg(feature = "additional-controls")]
-      #[cfg(not(target_arch = "wasm32"))]
-      ControlOperator::FEATURE => {
-        self.ctrl = Some(ctrl);
-
-        if let Some(ef) = self.enabled_features {
-          let tv = text_value_from_type2(self.cddl, controller);
-          if let Some(Type2::TextValue { value, .. }) = tv {
-            if ef.contains(&&**value) {
-              let err_count = self.errors.len();
-              self.visit_type2(target)?;
-              if self.errors.len() > err_count {
-                self.has_feature_errors = true;
-              }
-              self.ctrl = None;
-            } else {
-              self
-                .disabled_features
-                .get_or_insert(vec![value.to_string()])
-                .push(value.to_string());
-            }
-          } else if let Some(Type2::UTF8ByteString { value, .. }) = tv {
-            let value = std::str::from_utf8(value).map_err(Error::UTF8Parsing)?;
-            if ef.contains(&value) {
-              let err_count = self.errors.len();
-              self.visit_type2(target)?;
-              if self.errors.len() > err_count {
-                self.has_feature_errors = true;
-              }
-              self.ctrl = None;
-            } else {
-              self
-                .disabled_features
-                .get_or_insert(vec![value.to_string()])
-                .push(value.to_string());
-            }
-          }
-        }
 
-        self.ctrl = None;
-      }
-      #[cfg(feature = "additional-controls")]
-      #[cfg(target_arch = "wasm32")]
-      ControlOperator::FEATURE => {
-        self.ctrl = Some(ctrl);
+      let mut choice_keys = self.restore_validated_keys(validated_keys);
+      failed_choice_keys.append(&mut choice_keys);
+    }
 
-        if let Some(ef) = &self.enabled_features {
-          let tv = text_value_from_type2(self.cddl, controller);
-          if let Some(Type2::TextValue { value, .. }) = tv {
-            if ef.contains(&JsValue::from(value.as_ref())) {
-              let err_count = self.errors.len();
-              self.visit_type2(target)?;
-              if self.errors.len() > err_count {
-                self.has_feature_errors = true;
-              }
-              self.ctrl = None;
-            } else {
-              self
-                .disabled_features
-                .get_or_insert(vec![value.to_string()])
-                .push(value.to_string());
-            }
-          } else if let Some(Type2::UTF8ByteString { value, .. }) = tv {
-            let value = std::str::from_utf8(value).map_err(Error::UTF8Parsing)?;
-            if ef.contains(&JsValue::from(value)) {
-              let err_count = self.errors.len();
-              self.visit_type2(target)?;
-              if self.errors.len() > err_count {
-                self.has_feature_errors = true;
-              }
-              self.ctrl = None;
-            } else {
-              self
-                .disabled_features
-                .get_or_insert(vec![value.to_string()])
-                .push(value.to_string());
-            }
-          }
-        }
+    // Keys validated by the failed choices are kept if none of them validates
+    if !failed_choice_keys.is_empty() {
+      self
+        .validated_keys
+        .get_or_insert_with(Vec::new)
+        .append(&mut failed_choice_keys);
+    }
 
-        self.ctrl = None;
-      }
-      _ => {
-        self.add_error(format!("unsupported control operator {}", ctrl));
-      }
+    if let Some(discriminated) = discriminated_choices(
+      &choice_errors
+        .iter()
+        .map(|(discriminant_matches, _)| *discriminant_matches)
+        .collect::<Vec<_>>(),
+    ) {
+      let retained = discriminated
+        .into_iter()
+        .map(|idx| (None, choice_errors[idx].1.clone()))
+        .collect::<Vec<_>>();
+      self.retain_choice_errors(initial_error_count, &retained);
     }
 
     Ok(())
   }
 
-  fn visit_type2(&mut self, t2: &Type2<'a>) -> visitor::Result<Error> {
-    match t2 {
-      Type2::TextValue { value, .. } => self.visit_value(&token::Value::TEXT(value.clone())),
-      Type2::Map { group, .. } => match &self.json {
-        Value::Object(o) => {
-          #[allow(clippy::needless_collect)]
-          let o = o.keys().cloned().collect::<Vec<_>>();
-
-          self.visit_group(group)?;
-
-          if self.values_to_validate.is_none() {
-            for k in o.into_iter() {
-              if let Some(keys) = &self.validated_keys {
-                if !keys.contains(&k) {
-                  self.add_error(format!("unexpected key {:?}", k));
-                }
-              }
+  fn visit_group_choice(&mut self, gc: &GroupChoice<'a>) -> visitor::Result<Error> {
+    if self.is_group_to_choice_enum {
+      let initial_error_count = self.errors.len();
+      for tc in type_choices_from_group_choice(self.cddl, gc).iter() {
+        let error_count = self.errors.len();
+        self.visit_type_choice(tc)?;
+        if self.errors.len() == error_count {
+          let type_choice_error_count = self.errors.len() - initial_error_count;
+          if type_choice_error_count > 0 {
+            for _ in 0..type_choice_error_count {
+              self.errors.pop();
             }
           }
-
-          self.is_cut_present = false;
-          self.cut_value = None;
-          Ok(())
-        }
-        Value::Array(_) => self.validate_array_items(&ArrayItemToken::Group(group)),
-        _ => {
-          self.add_error(format!("expected map object {}, got {}", t2, self.json));
-          Ok(())
+          return Ok(());
         }
-      },
-      Type2::Array { group, .. } => match &self.json {
-        Value::Array(a) => {
-          if group.group_choices.len() == 1
-            && group.group_choices[0].group_entries.is_empty()
-            && !a.is_empty()
-            && !matches!(
-              self.ctrl,
-              Some(ControlOperator::NE) | Some(ControlOperator::DEFAULT)
-            )
-          {
-            self.add_error(format!("expected empty array, got {}", self.json));
-            return Ok(());
-          }
+      }
 
-          self.entry_counts = Some(entry_counts_from_group(self.cddl, group));
-          self.visit_group(group)?;
-          self.entry_counts = None;
+      return Ok(());
+    }
 
-          if let Some(errors) = &mut self.array_errors {
-            if let Some(indices) = &self.valid_array_items {
-              for idx in indices.iter() {
-                errors.remove(idx);
-              }
-            }
+    for (idx, ge) in gc.group_entries.iter().enumerate() {
+      if let Some(current_index) = self.group_entry_idx.as_mut() {
+        if idx != 0 {
+          *current_index += 1;
+        }
+      } else {
+        self.group_entry_idx = Some(idx);
+      }
 
-            for error in errors.values_mut() {
-              self.errors.append(error);
-            }
-          }
+      self.visit_group_entry(&ge.0)?;
+    }
 
-          self.valid_array_items = None;
-          self.array_errors = None;
+    Ok(())
+  }
 
-          Ok(())
-        }
-        _ => {
-          self.add_error(format!("expected array type, got {}", self.json));
-          Ok(())
-        }
-      },
-      Type2::ChoiceFromGroup {
-        ident,
-        generic_args,
-        ..
-      } => {
-        if let Some(ga) = generic_args {
-          if let Some(rule) = rule_from_ident(self.cddl, ident) {
-            if let Some(gr) = self
-              .generic_rules
-              .iter_mut()
-              .find(|gr| gr.name == ident.ident)
-            {
-              for arg in ga.args.iter() {
-                gr.args.push((*arg.arg).clone());
-              }
-            } else if let Some(params) = generic_params_from_rule(rule) {
-              self.generic_rules.push(GenericRule {
-                name: ident.ident,
-                params,
-                args: ga.args.iter().cloned().map(|arg| *arg.arg).collect(),
-              });
-            }
+  fn visit_range(
+    &mut self,
+    lower: &'b Type2<'a>,
+    upper: &'b Type2<'a>,
+    is_inclusive: bool,
+  ) -> visitor::Result<Error> {
+    if matches!(&self.json, Value::Array(_)) {
+      return self.validate_array_items(&ArrayItemToken::Range(lower, upper, is_inclusive));
+    }
 
-            #[cfg(all(feature = "additional-controls", target_arch = "wasm32"))]
-            let mut jv =
-              JSONValidator::new(self.cddl, self.json.clone(), self.enabled_features.clone());
-            #[cfg(all(feature = "additional-controls", not(target_arch = "wasm32")))]
-            let mut jv = JSONValidator::new(self.cddl, self.json.clone(), self.enabled_features);
-            #[cfg(not(feature = "additional-controls"))]
-            let mut jv = JSONValidator::new(self.cddl, self.json.clone());
+    // Numeric strings are coerced before being compared against numeric bounds
+    if let Some(n) = self.coerced_numeric_string() {
+      if [lower, upper].iter().all(|bound| {
+        let values = range_bound_values(self.cddl, bound);
+        !values.is_empty()
+          && values.iter().all(|v| {
+            matches!(
+              v,
+              Type2::IntValue { .. } | Type2::UintValue { .. } | Type2::FloatValue { .. }
+            )
+          })
+      }) {
+        let json = std::mem::replace(&mut self.json, n);
+        let result = self.visit_range(lower, upper, is_inclusive);
+        self.json = json;
 
-            jv.generic_rules = self.generic_rules.clone();
-            jv.eval_generic_rule = Some(ident.ident);
-            jv.is_group_to_choice_enum = true;
-            jv.is_multi_type_choice = self.is_multi_type_choice;
-            jv.visit_rule(rule)?;
+        return result;
+      }
+    }
 
-            self.errors.append(&mut jv.errors);
+    // Range bounds referencing named rules are resolved to their numeric
+    // values. The value is in range if any pair of resolved bounds admits it
+    if matches!(lower, Type2::Typename { .. }) || matches!(upper, Type2::Typename { .. }) {
+      let lowers = range_bound_values(self.cddl, lower);
+      let uppers = range_bound_values(self.cddl, upper);
+
+      if lowers.is_empty() || uppers.is_empty() {
+        return Err(Error::Syntax(SyntaxError::new(
+          SyntaxErrorKind::InvalidRangeBound,
+          &format!(
+            "invalid cddl range. bounds must resolve to numeric values, got {} and {}",
+            lower, upper
+          ),
+        )));
+      }
 
+      let error_count = self.errors.len();
+      for l in lowers.iter() {
+        for u in uppers.iter() {
+          let cur_errors = self.errors.len();
+          self.visit_range(l, u, is_inclusive)?;
+          if self.errors.len() == cur_errors {
+            self.errors.truncate(error_count);
             return Ok(());
           }
         }
+      }
 
-        if group_rule_from_ident(self.cddl, ident).is_none() {
+      return Ok(());
+    }
+
+    // Ranges mixing integer and float bounds are compared as floats
+    if mixed_numeric_range_bounds(lower, upper).is_some() {
+      if range_contains(&self.json, lower, upper, is_inclusive) != Some(true) {
+        if is_inclusive {
           self.add_error(format!(
-            "rule {} must be a group rule to turn it into a choice",
-            ident
+            "expected number to be in range {} <= value <= {}, got {}",
+            lower, upper, self.json
+          ));
+        } else {
+          self.add_error(format!(
+            "expected number to be in range {} <= value < {}, got {}",
+            lower, upper, self.json
           ));
-          return Ok(());
         }
+      }
 
-        self.is_group_to_choice_enum = true;
-        self.visit_identifier(ident)?;
-        self.is_group_to_choice_enum = false;
+      return Ok(());
+    }
 
-        Ok(())
-      }
-      Type2::ChoiceFromInlineGroup { group, .. } => {
-        self.is_group_to_choice_enum = true;
-        self.visit_group(group)?;
-        self.is_group_to_choice_enum = false;
-        Ok(())
-      }
-      Type2::Typename {
-        ident,
-        generic_args,
-        ..
-      } => {
-        if let Some(ga) = generic_args {
-          if let Some(rule) = rule_from_ident(self.cddl, ident) {
-            if let Some(gr) = self
-              .generic_rules
-              .iter_mut()
-              .find(|gr| gr.name == ident.ident)
-            {
-              for arg in ga.args.iter() {
-                gr.args.push((*arg.arg).clone());
-              }
-            } else if let Some(params) = generic_params_from_rule(rule) {
-              self.generic_rules.push(GenericRule {
-                name: ident.ident,
-                params,
-                args: ga.args.iter().cloned().map(|arg| *arg.arg).collect(),
-              });
-            }
+    // Text ranges are compared lexicographically
+    if let (Type2::TextValue { value: l, .. }, Type2::TextValue { value: u, .. }) = (lower, upper) {
+      let is_in_range = match &self.json {
+        Value::String(s) if is_inclusive => s.as_str() >= l.as_ref() && s.as_str() <= u.as_ref(),
+        Value::String(s) => s.as_str() >= l.as_ref() && s.as_str() < u.as_ref(),
+        _ => false,
+      };
 
-            #[cfg(all(feature = "additional-controls", target_arch = "wasm32"))]
-            let mut jv =
-              JSONValidator::new(self.cddl, self.json.clone(), self.enabled_features.clone());
-            #[cfg(all(feature = "additional-controls", not(target_arch = "wasm32")))]
-            let mut jv = JSONValidator::new(self.cddl, self.json.clone(), self.enabled_features);
-            #[cfg(not(feature = "additional-controls"))]
-            let mut jv = JSONValidator::new(self.cddl, self.json.clone());
+      if !is_in_range {
+        if is_inclusive {
+          self.add_error(format!(
+            "expected text to be in range {} <= value <= {}, got {}",
+            lower, upper, self.json
+          ));
+        } else {
+          self.add_error(format!(
+            "expected text to be in range {} <= value < {}, got {}",
+            lower, upper, self.json
+          ));
+        }
+      }
 
-            jv.generic_rules = self.generic_rules.clone();
-            jv.eval_generic_rule = Some(ident.ident);
-            jv.is_multi_type_choice = self.is_multi_type_choice;
-            jv.visit_rule(rule)?;
+      return Ok(());
+    }
 
-            self.errors.append(&mut jv.errors);
+    // Integers beyond the 64-bit range are compared with integer bounds by
+    // their decimal representation
+    if let Value::Number(n) = &self.json {
+      if big_integer_sign(n).is_some() {
+        if let (
+          Type2::IntValue { .. } | Type2::UintValue { .. },
+          Type2::IntValue { .. } | Type2::UintValue { .. },
+        ) = (lower, upper)
+        {
+          let n = n.to_string();
+          let is_in_range = compare_integer_literals(&n, &lower.to_string()).is_ge()
+            && if is_inclusive {
+              compare_integer_literals(&n, &upper.to_string()).is_le()
+            } else {
+              compare_integer_literals(&n, &upper.to_string()).is_lt()
+            };
 
-            return Ok(());
+          if !is_in_range {
+            if is_inclusive {
+              self.add_error(format!(
+                "expected integer to be in range {} <= value <= {}, got {}",
+                lower, upper, self.json
+              ));
+            } else {
+              self.add_error(format!(
+                "expected integer to be in range {} <= value < {}, got {}",
+                lower, upper, self.json
+              ));
+            }
           }
-        }
 
-        let type_choice_alternates = type_choice_alternates_from_ident(self.cddl, ident);
-        if !type_choice_alternates.is_empty() {
-          self.is_multi_type_choice = true;
+          return Ok(());
         }
+      }
+    }
 
-        let error_count = self.errors.len();
-        for t in type_choice_alternates {
-          let cur_errors = self.errors.len();
-          self.visit_type(t)?;
-          if self.errors.len() == cur_errors {
-            for _ in 0..self.errors.len() - error_count {
-              self.errors.pop();
-            }
+    match lower {
+      Type2::IntValue { value: l, .. } => match upper {
+        Type2::IntValue { value: u, .. } => {
+          let error_str = if is_inclusive {
+            format!(
+              "expected integer to be in range {} <= value <= {}, got {}",
+              l, u, self.json
+            )
+          } else {
+            format!(
+              "expected integer to be in range {} <= value < {}, got {}",
+              l, u, self.json
+            )
+          };
 
-            return Ok(());
+          if range_contains(&self.json, lower, upper, is_inclusive) != Some(true) {
+            self.add_error(error_str);
           }
+
+          return Ok(());
         }
+        Type2::UintValue { value: u, .. } => {
+          let error_str = if is_inclusive {
+            format!(
+              "expected integer to be in range {} <= value <= {}, got {}",
+              l, u, self.json
+            )
+          } else {
+            format!(
+              "expected integer to be in range {} <= value < {}, got {}",
+              l, u, self.json
+            )
+          };
 
-        self.visit_identifier(ident)
-      }
-      Type2::IntValue { value, .. } => self.visit_value(&token::Value::INT(*value)),
-      Type2::UintValue { value, .. } => self.visit_value(&token::Value::UINT(*value)),
-      Type2::FloatValue { value, .. } => self.visit_value(&token::Value::FLOAT(*value)),
-      Type2::ParenthesizedType { pt, .. } => self.visit_type(pt),
-      Type2::Unwrap {
-        ident,
-        generic_args,
-        ..
-      } => {
-        // Per
-        // https://github.com/w3c/did-spec-registries/pull/138#issuecomment-719739215,
-        // strip tag and validate underlying type
-        if let Some(Type2::TaggedData { t, .. }) = tag_from_token(&lookup_ident(ident.ident)) {
-          return self.visit_type(&t);
+          if range_contains(&self.json, lower, upper, is_inclusive) != Some(true) {
+            self.add_error(error_str);
+          }
+
+          return Ok(());
+        }
+        _ => {
+          return Err(Error::Syntax(SyntaxError::new(
+            SyntaxErrorKind::InvalidRangeBound,
+            &format!(
+              "invalid cddl range. upper value must be an integer type. got {}",
+              upper
+            ),
+          )));
         }
+      },
+      Type2::UintValue { value: l, .. } => match upper {
+        Type2::UintValue { value: u, .. } => {
+          let error_str = if is_inclusive {
+            format!(
+              "expected uint to be in range {} <= value <= {}, got {}",
+              l, u, self.json
+            )
+          } else {
+            format!(
+              "expected uint to be in range {} <= value < {}, got {}",
+              l, u, self.json
+            )
+          };
 
-        if let Some(ga) = generic_args {
-          if let Some(rule) = unwrap_rule_from_ident(self.cddl, ident) {
-            if let Some(gr) = self
-              .generic_rules
-              .iter_mut()
-              .find(|gr| gr.name == ident.ident)
-            {
-              for arg in ga.args.iter() {
-                gr.args.push((*arg.arg).clone());
-              }
-            } else if let Some(params) = generic_params_from_rule(rule) {
-              self.generic_rules.push(GenericRule {
-                name: ident.ident,
-                params,
-                args: ga.args.iter().cloned().map(|arg| *arg.arg).collect(),
-              });
-            }
+          match &self.json {
+            Value::Number(n) => {
+              if let Some(i) = n.as_u64() {
+                // A uint .size range restricts the maximum number of bytes
+                // needed to represent the value
+                if let Some(ControlOperator::SIZE) = self.ctrl {
+                  let max_size = if is_inclusive {
+                    *u
+                  } else {
+                    u.saturating_sub(1)
+                  };
+                  match 256u128.checked_pow(max_size as u32) {
+                    Some(max) if (i as u128) >= max => self.add_error(format!(
+                      "expected value .size {}{}{}, got {}",
+                      l,
+                      if is_inclusive { ".." } else { "..." },
+                      u,
+                      n
+                    )),
+                    _ => (),
+                  }
 
-            #[cfg(all(feature = "additional-controls", target_arch = "wasm32"))]
-            let mut jv =
-              JSONValidator::new(self.cddl, self.json.clone(), self.enabled_features.clone());
-            #[cfg(all(feature = "additional-controls", not(target_arch = "wasm32")))]
-            let mut jv = JSONValidator::new(self.cddl, self.json.clone(), self.enabled_features);
-            #[cfg(not(feature = "additional-controls"))]
-            let mut jv = JSONValidator::new(self.cddl, self.json.clone());
+                  return Ok(());
+                }
 
-            jv.generic_rules = self.generic_rules.clone();
-            jv.eval_generic_rule = Some(ident.ident);
-            jv.is_multi_type_choice = self.is_multi_type_choice;
-            jv.visit_rule(rule)?;
+                if range_contains(&self.json, lower, upper, is_inclusive) != Some(true) {
+                  self.add_error(error_str);
+                }
 
-            self.errors.append(&mut jv.errors);
+                return Ok(());
+              } else {
+                self.add_error(error_str);
+                return Ok(());
+              }
+            }
+            Value::String(s) => match self.ctrl {
+              Some(ControlOperator::SIZE) => {
+                let len = s.len();
+                let s = s.clone();
+                if is_inclusive {
+                  if s.len() < *l || s.len() > *u {
+                    self.add_error(format!(
+                      "expected \"{}\" string length in UTF-8 bytes to be in the range {} <= value <= {}, got {}",
+                      s, l, u, len
+                    ));
+                  }
 
-            return Ok(());
+                  return Ok(());
+                } else if s.len() < *l || s.len() >= *u {
+                  self.add_error(format!(
+                    "expected \"{}\" string length in UTF-8 bytes to be in the range {} <= value < {}, got {}",
+                    s, l, u, len
+                  ));
+                  return Ok(());
+                }
+              }
+              _ => {
+                self.add_error("string value cannot be validated against a range without the .size control operator".to_string());
+                return Ok(());
+              }
+            },
+            _ => {
+              self.add_error(error_str);
+              return Ok(());
+            }
           }
         }
-
-        if let Some(rule) = unwrap_rule_from_ident(self.cddl, ident) {
-          return self.visit_rule(rule);
+        _ => {
+          return Err(Error::Syntax(SyntaxError::new(
+            SyntaxErrorKind::InvalidRangeBound,
+            &format!(
+              "invalid cddl range. upper value must be a uint type. got {}",
+              upper
+            ),
+          )));
         }
+      },
+      Type2::FloatValue { value: l, .. } => match upper {
+        Type2::FloatValue { value: u, .. } => {
+          let error_str = if is_inclusive {
+            format!(
+              "expected float to be in range {} <= value <= {}, got {}",
+              l, u, self.json
+            )
+          } else {
+            format!(
+              "expected float to be in range {} <= value < {}, got {}",
+              l, u, self.json
+            )
+          };
 
-        self.add_error(format!(
-          "cannot unwrap identifier {}, rule not found",
-          ident
-        ));
+          if range_contains(&self.json, lower, upper, is_inclusive) != Some(true) {
+            self.add_error(error_str);
+          }
 
-        Ok(())
-      }
-      #[cfg(feature = "ast-span")]
-      Type2::Any { .. } => Ok(()),
-      #[cfg(not(feature = "ast-span"))]
-      Type2::Any {} => Ok(()),
+          return Ok(());
+        }
+        _ => {
+          return Err(Error::Syntax(SyntaxError::new(
+            SyntaxErrorKind::InvalidRangeBound,
+            &format!(
+              "invalid cddl range. upper value must be a float type. got {}",
+              upper
+            ),
+          )));
+        }
+      },
       _ => {
-        self.add_error(format!(
-          "unsupported data type for validating JSON, got {}",
-          t2
-        ));
-        Ok(())
+        return Err(Error::Syntax(SyntaxError::new(
+          SyntaxErrorKind::InvalidRangeBound,
+          "invalid cddl range. upper and lower values must be either integers, floats or text strings",
+        )));
       }
     }
+
+    Ok(())
   }
 
-  fn visit_identifier(&mut self, ident: &Identifier<'a>) -> visitor::Result<Error> {
-    if let Some(name) = self.eval_generic_rule {
-      if let Some(gr) = self
-        .generic_rules
-        .iter()
-        .cloned()
-        .find(|gr| gr.name == name)
+  fn visit_control_operator(
+    &mut self,
+    target: &Type2<'a>,
+    ctrl: ControlOperator,
+    controller: &Type2<'a>,
+  ) -> visitor::Result<Error> {
+    if let Type2::Typename {
+      ident: target_ident,
+      ..
+    } = target
+    {
+      if let Type2::Typename {
+        ident: controller_ident,
+        ..
+      } = controller
       {
-        for (idx, gp) in gr.params.iter().enumerate() {
-          if *gp == ident.ident {
+        if let Some(name) = self.eval_generic_rule {
+          if let Some(gr) = self
+            .generic_rules
+            .iter()
+            .cloned()
+            .find(|gr| gr.name == name)
+          {
+            for (idx, gp) in gr.params.iter().enumerate() {
+              if let Some(arg) = gr.args.get(idx) {
+                if *gp == target_ident.ident {
+                  let t2 = Type2::from(arg.clone());
+
+                  if *gp == controller_ident.ident {
+                    return self.visit_control_operator(&t2, ctrl, &t2);
+                  }
+
+                  return self.visit_control_operator(&arg.type2, ctrl, controller);
+                }
+              }
+            }
+          }
+        }
+      }
+
+      if let Some(name) = self.eval_generic_rule {
+        if let Some(gr) = self
+          .generic_rules
+          .iter()
+          .cloned()
+          .find(|gr| gr.name == name)
+        {
+          for (idx, gp) in gr.params.iter().enumerate() {
             if let Some(arg) = gr.args.get(idx) {
-              return self.visit_type1(arg);
+              if *gp == target_ident.ident {
+                let t2 = Type2::from(arg.clone());
+                return self.visit_control_operator(&t2, ctrl, controller);
+              }
             }
           }
         }
       }
     }
 
-    // self.is_colon_shortcut_present is only true when the ident is part of a
-    // member key
-    if !self.is_colon_shortcut_present {
-      if let Some(r) = rule_from_ident(self.cddl, ident) {
-        return self.visit_rule(r);
-      }
-    }
+    // Numeric strings are coerced before controls on numeric types apply
+    if let Type2::Typename { ident, .. } = target {
+      if let Some(n) = self.coerced_numeric_string() {
+        if is_ident_numeric_data_type(self.cddl, ident) {
+          let json = std::mem::replace(&mut self.json, n);
+          let result = self.visit_control_operator(target, ctrl, controller);
+          self.json = json;
 
-    if is_ident_any_type(self.cddl, ident) {
-      return Ok(());
+          return result;
+        }
+      }
     }
 
-    match &self.json {
-      Value::Null if is_ident_null_data_type(self.cddl, ident) => Ok(()),
-      Value::Bool(b) => {
-        if is_ident_bool_data_type(self.cddl, ident) {
-          return Ok(());
+    match ctrl {
+      ControlOperator::EQ => match target {
+        Type2::Typename { ident, .. } => {
+          if is_ident_string_data_type(self.cddl, ident)
+            || is_ident_numeric_data_type(self.cddl, ident)
+          {
+            return self.visit_type2(controller);
+          }
         }
-
-        if ident_matches_bool_value(self.cddl, ident, *b) {
-          return Ok(());
+        Type2::Array { group, .. } => {
+          if let Value::Array(_) = &self.json {
+            self.entry_counts = Some(entry_counts_from_group(self.cddl, group));
+            self.visit_type2(controller)?;
+            self.entry_counts = None;
+            return Ok(());
+          }
         }
-
-        self.add_error(format!("expected type {}, got {}", ident, self.json));
-        Ok(())
-      }
-      Value::Number(n) => {
-        if is_ident_uint_data_type(self.cddl, ident) && n.is_u64() {
-          return Ok(());
-        } else if is_ident_nint_data_type(self.cddl, ident) {
-          if let Some(n) = n.as_i64() {
-            if n.is_negative() {
-              return Ok(());
-            }
+        Type2::Map { .. } => {
+          if let Value::Object(_) = &self.json {
+            self.ctrl = Some(ctrl);
+            self.is_ctrl_map_equality = true;
+            self.visit_type2(controller)?;
+            self.ctrl = None;
+            self.is_ctrl_map_equality = false;
+            return Ok(());
           }
-        } else if is_ident_time_data_type(self.cddl, ident) {
-          if let Some(n) = n.as_i64() {
-            if let chrono::LocalResult::None = Utc.timestamp_millis_opt(n * 1000) {
-              self.add_error(format!(
-                "expected time data type, invalid UNIX timestamp {}",
-                n,
-              ));
-            }
-
+        }
+        _ => self.add_error(format!(
+          "target for .eq operator must be a string, numerical, array or map data type, got {}",
+          target
+        )),
+      },
+      ControlOperator::NE => match target {
+        Type2::Typename { ident, .. } => {
+          if is_ident_string_data_type(self.cddl, ident)
+            || is_ident_numeric_data_type(self.cddl, ident)
+          {
+            self.ctrl = Some(ctrl);
+            self.visit_type2(controller)?;
+            self.ctrl = None;
+            return Ok(());
+          }
+        }
+        Type2::Array { .. } => {
+          if let Value::Array(_) = &self.json {
+            self.ctrl = Some(ctrl);
+            self.visit_type2(controller)?;
+            self.ctrl = None;
+            return Ok(());
+          }
+        }
+        Type2::Map { .. } => {
+          if let Value::Object(_) = &self.json {
+            self.ctrl = Some(ctrl);
+            self.is_ctrl_map_equality = true;
+            self.visit_type2(controller)?;
+            self.ctrl = None;
+            self.is_ctrl_map_equality = false;
             return Ok(());
-          } else if let Some(n) = n.as_f64() {
-            // truncates fractional milliseconds when validating
-            if let chrono::LocalResult::None = Utc.timestamp_millis_opt((n * 1000f64) as i64) {
-              self.add_error(format!(
-                "expected time data type, invalid UNIX timestamp {}",
-                n,
-              ));
-            }
           }
-        } else if (is_ident_integer_data_type(self.cddl, ident) && n.is_i64())
-          || (is_ident_float_data_type(self.cddl, ident) && n.is_f64())
-        {
-          return Ok(());
         }
+        _ => self.add_error(format!(
+          "target for .ne operator must be a string, numerical, array or map data type, got {}",
+          target
+        )),
+      },
+      ControlOperator::LT | ControlOperator::GT | ControlOperator::GE | ControlOperator::LE => {
+        match target {
+          Type2::Typename { ident, .. } if is_ident_numeric_data_type(self.cddl, ident) => {
+            if let Type2::TextValue { .. }
+            | Type2::UTF8ByteString { .. }
+            | Type2::B16ByteString { .. }
+            | Type2::B64ByteString { .. } = controller
+            {
+              return Err(Error::Syntax(SyntaxError::new(
+                SyntaxErrorKind::ControlOperatorTypeMismatch,
+                &format!(
+                  "controller for {} operator must be a numerical value, got {}",
+                  ctrl, controller
+                ),
+              )));
+            }
 
-        self.add_error(format!("expected type {}, got {}", ident, self.json));
-        Ok(())
-      }
-      Value::String(s) => {
-        if is_ident_uri_data_type(self.cddl, ident) {
-          if let Err(e) = uriparse::URI::try_from(&**s) {
-            self.add_error(format!("expected URI data type, decoding error: {}", e));
+            self.ctrl = Some(ctrl);
+            self.visit_type2(controller)?;
+            self.ctrl = None;
           }
-        } else if is_ident_b64url_data_type(self.cddl, ident) {
-          if let Err(e) = base64_url::decode(s) {
-            self.add_error(format!(
-              "expected base64 URL data type, decoding error: {}",
-              e
-            ));
+          _ => {
+            return Err(Error::Syntax(SyntaxError::new(
+              SyntaxErrorKind::ControlOperatorTypeMismatch,
+              &format!(
+                "target for .lt, .gt, .ge or .le operator must be a numerical data type, got {}",
+                target
+              ),
+            )));
           }
-        } else if is_ident_tdate_data_type(self.cddl, ident) {
-          if let Err(e) = chrono::DateTime::parse_from_rfc3339(s) {
-            self.add_error(format!("expected tdate data type, decoding error: {}", e));
+        }
+      }
+      ControlOperator::SIZE => match target {
+        Type2::Typename { ident, .. }
+          if is_ident_string_data_type(self.cddl, ident)
+            || is_ident_uint_data_type(self.cddl, ident) =>
+        {
+          self.ctrl = Some(ctrl);
+          self.visit_type2(controller)?;
+          self.ctrl = None;
+        }
+        _ => {
+          self.add_error(format!(
+            "target for .size must a string or uint data type, got {}",
+            target
+          ));
+        }
+      },
+      ControlOperator::BITS => match target {
+        Type2::Typename { ident, .. } if is_ident_uint_data_type(self.cddl, ident) => {
+          match &self.json {
+            Value::Number(n) => match n.as_u64() {
+              Some(v) => match bit_positions_from_type2(self.cddl, controller) {
+                Ok(positions) => {
+                  let is_outside = (0..u64::BITS as u64)
+                    .filter(|p| v & 1 << p != 0)
+                    .any(|p| !positions.iter().any(|r| r.contains(&p)));
+
+                  if is_outside {
+                    self.add_error(format!(
+                      "expected uint .bits {}, got {} with bits set outside of the allowed positions",
+                      controller, n
+                    ));
+                  }
+                }
+                Err(e) => self.add_error(e),
+              },
+              None => self.add_error(format!("expected uint .bits {}, got {}", controller, n)),
+            },
+            _ => self.add_error(format!(
+              ".bits control can only be matched against a JSON unsigned integer, got {}",
+              self.json
+            )),
           }
-        } else if is_ident_string_data_type(self.cddl, ident) {
-          return Ok(());
-        } else {
-          self.add_error(format!("expected type {}, got {}", ident, self.json));
+        }
+        _ => self.add_error(format!(
+          "target for .bits must be a uint data type, got {}",
+          target
+        )),
+      },
+      ControlOperator::AND => {
+        self.ctrl = Some(ctrl);
+        self.visit_type2(target)?;
+        self.visit_type2(controller)?;
+        self.ctrl = None;
+      }
+      ControlOperator::WITHIN => {
+        self.ctrl = Some(ctrl);
+        let error_count = self.errors.len();
+        self.visit_type2(target)?;
+        let no_errors = self.errors.len() == error_count;
+        self.visit_type2(controller)?;
+        if no_errors && self.errors.len() > error_count {
+          for _ in 0..self.errors.len() - error_count {
+            self.errors.pop();
+          }
+
+          self.add_error(format!(
+            "expected type {} .within type {}, got {}",
+            target, controller, self.json,
+          ));
         }
 
-        Ok(())
+        self.ctrl = None;
       }
-      Value::Array(_) => self.validate_array_items(&ArrayItemToken::Identifier(ident)),
-      Value::Object(o) => match &self.occurrence {
-        #[cfg(feature = "ast-span")]
-        Some(Occur::Optional { .. }) | None => {
-          if token::lookup_ident(ident.ident)
-            .in_standard_prelude()
-            .is_some()
-          {
+      ControlOperator::DEFAULT => {
+        // The controller only documents the value assumed when an optional
+        // member is absent, so any present value is validated against the
+        // target as is
+        let error_count = self.errors.len();
+        self.visit_type2(target)?;
+        if self.errors.len() != error_count {
+          #[cfg(feature = "ast-span")]
+          if let Some(Occur::Optional { .. }) = self.occurrence.take() {
             self.add_error(format!(
-              "expected object value of type {}, got object",
-              ident.ident
+              "expected default value {}, got {}",
+              controller, self.json
             ));
-            return Ok(());
           }
-
-          self.visit_value(&token::Value::TEXT(ident.ident.into()))
-        }
-        #[cfg(not(feature = "ast-span"))]
-        Some(Occur::Optional {}) | None => {
-          if token::lookup_ident(ident.ident)
-            .in_standard_prelude()
-            .is_some()
-          {
+          #[cfg(not(feature = "ast-span"))]
+          if let Some(Occur::Optional {}) = self.occurrence.take() {
             self.add_error(format!(
-              "expected object value of type {}, got object",
-              ident.ident
+              "expected default value {}, got {}",
+              controller, self.json
             ));
-            return Ok(());
           }
-
-          self.visit_value(&token::Value::TEXT(ident.ident.into()))
         }
-        Some(occur) => {
-          if is_ident_string_data_type(self.cddl, ident) {
-            let values_to_validate = o
-              .iter()
-              .filter_map(|(k, v)| match &self.validated_keys {
-                Some(keys) if !keys.contains(k) => Some(v.clone()),
-                Some(_) => None,
-                None => Some(v.clone()),
-              })
-              .collect::<Vec<_>>();
-
-            self.values_to_validate = Some(values_to_validate);
+        self.ctrl = None;
+      }
+      ControlOperator::REGEXP | ControlOperator::PCRE => {
+        self.ctrl = Some(ctrl);
+        match target {
+          Type2::Typename { ident, .. } if is_ident_string_data_type(self.cddl, ident) => {
+            match self.json {
+              Value::String(_) | Value::Array(_) => self.visit_type2(controller)?,
+              _ => self.add_error(format!(
+                ".regexp/.pcre control can only be matched against JSON string, got {}",
+                self.json
+              )),
+            }
           }
+          _ => self.add_error(format!(
+            ".regexp/.pcre control can only be matched against string data type, got {}",
+            target
+          )),
+        }
+        self.ctrl = None;
+      }
+      #[cfg(feature = "additional-controls")]
+      ControlOperator::CAT => {
+        self.ctrl = Some(ctrl);
 
-          #[cfg(feature = "ast-span")]
-          if let Occur::ZeroOrMore { .. } | Occur::OneOrMore { .. } = occur {
-            if let Occur::OneOrMore { .. } = occur {
-              if o.is_empty() {
-                self.add_error(format!(
-                  "object cannot be empty, one or more entries with key type {} required",
-                  ident
-                ));
-                return Ok(());
-              }
-            }
-          } else if let Occur::Exact { lower, upper, .. } = occur {
-            if let Some(values_to_validate) = &self.values_to_validate {
-              if let Some(lower) = lower {
-                if let Some(upper) = upper {
-                  if values_to_validate.len() < *lower || values_to_validate.len() > *upper {
-                    if lower == upper {
-                      self.add_error(format!(
-                        "object must contain exactly {} entries of key of type {}",
-                        lower, ident,
-                      ));
-                    } else {
-                      self.add_error(format!(
-                        "object must contain between {} and {} entries of key of type {}",
-                        lower, upper, ident,
-                      ));
-                    }
+        match cat_operation(self.cddl, target, controller, false) {
+          Ok(values) => {
+            let error_count = self.errors.len();
 
-                    return Ok(());
-                  }
-                }
+            for v in values.iter() {
+              let cur_errors = self.errors.len();
 
-                if values_to_validate.len() < *lower {
-                  self.add_error(format!(
-                    "object must contain at least {} entries of key of type {}",
-                    lower, ident,
-                  ));
+              self.visit_type2(v)?;
 
-                  return Ok(());
+              if self.errors.len() == cur_errors {
+                for _ in 0..self.errors.len() - error_count {
+                  self.errors.pop();
                 }
-              }
-
-              if let Some(upper) = upper {
-                if values_to_validate.len() > *upper {
-                  self.add_error(format!(
-                    "object must contain no more than {} entries of key of type {}",
-                    upper, ident,
-                  ));
 
-                  return Ok(());
-                }
+                break;
               }
-
-              return Ok(());
             }
           }
+          Err(e) => self.add_error(e),
+        }
 
-          #[cfg(not(feature = "ast-span"))]
-          if let Occur::ZeroOrMore {} | Occur::OneOrMore {} = occur {
-            if let Occur::OneOrMore {} = occur {
-              if o.is_empty() {
-                self.add_error(format!(
-                  "object cannot be empty, one or more entries with key type {} required",
-                  ident
-                ));
-                return Ok(());
-              }
-            }
-          } else if let Occur::Exact { lower, upper } = occur {
-            if let Some(values_to_validate) = &self.values_to_validate {
-              if let Some(lower) = lower {
-                if let Some(upper) = upper {
-                  if values_to_validate.len() < *lower || values_to_validate.len() > *upper {
-                    if lower == upper {
-                      self.add_error(format!(
-                        "object must contain exactly {} entries of key of type {}",
-                        lower, ident,
-                      ));
-                    } else {
-                      self.add_error(format!(
-                        "object must contain between {} and {} entries of key of type {}",
-                        lower, upper, ident,
-                      ));
-                    }
-
-                    return Ok(());
-                  }
-                }
+        self.ctrl = None;
+      }
+      #[cfg(feature = "additional-controls")]
+      ControlOperator::DET => {
+        self.ctrl = Some(ctrl);
 
-                if values_to_validate.len() < *lower {
-                  self.add_error(format!(
-                    "object must contain at least {} entries of key of type {}",
-                    lower, ident,
-                  ));
+        match cat_operation(self.cddl, target, controller, true) {
+          Ok(values) => {
+            let error_count = self.errors.len();
 
-                  return Ok(());
-                }
-              }
+            for v in values.iter() {
+              let cur_errors = self.errors.len();
 
-              if let Some(upper) = upper {
-                if values_to_validate.len() > *upper {
-                  self.add_error(format!(
-                    "object must contain no more than {} entries of key of type {}",
-                    upper, ident,
-                  ));
+              self.visit_type2(v)?;
 
-                  return Ok(());
+              if self.errors.len() == cur_errors {
+                for _ in 0..self.errors.len() - error_count {
+                  self.errors.pop();
                 }
-              }
 
-              return Ok(());
+                break;
+              }
             }
           }
-
-          Ok(())
-        }
-      },
-      _ => {
-        if let Some(cut_value) = self.cut_value.take() {
-          self.add_error(format!(
-            "cut present for member key {}. expected type {}, got {}",
-            cut_value, ident, self.json
-          ));
-        } else {
-          self.add_error(format!("expected type {}, got {}", ident, self.json));
+          Err(e) => self.add_error(e),
         }
-        Ok(())
-      }
-    }
-  }
-
-  fn visit_value_member_key_entry(
-    &mut self,
-    entry: &ValueMemberKeyEntry<'a>,
-  ) -> visitor::Result<Error> {
-    if let Some(occur) = &entry.occur {
-      self.visit_occurrence(occur)?;
-    }
-
-    let current_location = self.json_location.clone();
 
-    if let Some(mk) = &entry.member_key {
-      let error_count = self.errors.len();
-      self.is_member_key = true;
-      self.visit_memberkey(mk)?;
-      self.is_member_key = false;
-
-      // Move to next entry if member key validation fails
-      if self.errors.len() != error_count {
-        self.advance_to_next_entry = true;
-        return Ok(());
+        self.ctrl = None;
       }
-    }
+      #[cfg(feature = "additional-controls")]
+      ControlOperator::PLUS => {
+        self.ctrl = Some(ctrl);
 
-    if let Some(values) = &self.values_to_validate {
-      for v in values.iter() {
-        #[cfg(all(feature = "additional-controls", target_arch = "wasm32"))]
-        let mut jv = JSONValidator::new(self.cddl, v.clone(), self.enabled_features.clone());
-        #[cfg(all(feature = "additional-controls", not(target_arch = "wasm32")))]
-        let mut jv = JSONValidator::new(self.cddl, v.clone(), self.enabled_features);
-        #[cfg(not(feature = "additional-controls"))]
-        let mut jv = JSONValidator::new(self.cddl, v.clone());
+        match plus_operation(self.cddl, target, controller) {
+          Ok(values) => {
+            let error_count = self.errors.len();
+            for v in values.iter() {
+              let cur_errors = self.errors.len();
 
-        jv.generic_rules = self.generic_rules.clone();
-        jv.eval_generic_rule = self.eval_generic_rule;
-        jv.is_multi_type_choice = self.is_multi_type_choice;
-        jv.is_multi_group_choice = self.is_multi_group_choice;
-        jv.json_location.push_str(&self.json_location);
-        jv.type_group_name_entry = self.type_group_name_entry;
-        jv.visit_type(&entry.entry_type)?;
+              self.visit_type2(v)?;
+              if self.errors.len() == cur_errors {
+                for _ in 0..self.errors.len() - error_count {
+                  self.errors.pop();
+                }
 
-        self.json_location = current_location.clone();
+                break;
+              }
+            }
+          }
 
-        self.errors.append(&mut jv.errors);
-        if entry.occur.is_some() {
-          self.occurrence = None;
+          Err(e) => self.add_error(e),
         }
+
+        self.ctrl = None;
       }
+      #[cfg(feature = "additional-controls")]
+      ControlOperator::ABNF => {
+        self.ctrl = Some(ctrl);
 
-      return Ok(());
-    }
+        match target {
+          Type2::Typename { ident, .. } if is_ident_string_data_type(self.cddl, ident) => {
+            match self.json {
+              Value::String(_) | Value::Array(_) => {
+                if let Type2::ParenthesizedType { pt, .. } = controller {
+                  match abnf_from_complex_controller(self.cddl, pt) {
+                    Ok(values) => {
+                      let error_count = self.errors.len();
+                      for v in values.iter() {
+                        let cur_errors = self.errors.len();
 
-    if let Some(v) = self.object_value.take() {
-      #[cfg(all(feature = "additional-controls", target_arch = "wasm32"))]
-      let mut jv = JSONValidator::new(self.cddl, v, self.enabled_features.clone());
-      #[cfg(all(feature = "additional-controls", not(target_arch = "wasm32")))]
-      let mut jv = JSONValidator::new(self.cddl, v, self.enabled_features);
-      #[cfg(not(feature = "additional-controls"))]
-      let mut jv = JSONValidator::new(self.cddl, v);
+                        self.visit_type2(v)?;
 
-      jv.generic_rules = self.generic_rules.clone();
-      jv.eval_generic_rule = self.eval_generic_rule;
-      jv.is_multi_type_choice = self.is_multi_type_choice;
-      jv.is_multi_group_choice = self.is_multi_group_choice;
-      jv.json_location.push_str(&self.json_location);
-      jv.type_group_name_entry = self.type_group_name_entry;
-      jv.visit_type(&entry.entry_type)?;
+                        if self.errors.len() == cur_errors {
+                          for _ in 0..self.errors.len() - error_count {
+                            self.errors.pop();
+                          }
 
-      self.json_location = current_location;
+                          break;
+                        }
+                      }
+                    }
+                    Err(e) => self.add_error(e),
+                  }
+                } else {
+                  self.visit_type2(controller)?
+                }
+              }
+              _ => self.add_error(format!(
+                ".abnf control can only be matched against a JSON string, got {}",
+                self.json,
+              )),
+            }
+          }
+          _ => self.add_error(format!(
+            ".abnf can only be matched against string data type, got {}",
+            target,
+          )),
+        }
 
-      self.errors.append(&mut jv.errors);
-      if entry.occur.is_some() {
-        self.occurrence = None;
+        self.ctrl = None;
       }
+      #[cfg(feature = "additional-controls")]
+      #[cfg(not(target_arch = "wasm32"))]
+      ControlOperator::FEATURE => {
+        self.ctrl = Some(ctrl);
 
-      Ok(())
-    } else if !self.advance_to_next_entry {
-      self.visit_type(&entry.entry_type)
-    } else {
-      Ok(())
-    }
-  }
-
-  fn visit_type_groupname_entry(
-    &mut self,
-    entry: &TypeGroupnameEntry<'a>,
-  ) -> visitor::Result<Error> {
-    self.type_group_name_entry = Some(entry.name.ident);
-
-    if let Some(ga) = &entry.generic_args {
-      if let Some(rule) = rule_from_ident(self.cddl, &entry.name) {
-        if let Some(gr) = self
-          .generic_rules
-          .iter_mut()
-          .find(|gr| gr.name == entry.name.ident)
-        {
-          for arg in ga.args.iter() {
-            gr.args.push((*arg.arg).clone());
+        if let Some(ef) = self.enabled_features {
+          let tv = text_value_from_type2(self.cddl, controller);
+          if let Some(Type2::TextValue { value, .. }) = tv {
+            if ef.contains(&&**value) {
+              let err_count = self.errors.len();
+              self.visit_type2(target)?;
+              if self.errors.len() > err_count {
+                self.has_feature_errors = true;
+              }
+              self.ctrl = None;
+            } else {
+              self.add_error(format!("feature \"{}\" is not enabled", value));
+            }
+          } else if let Some(Type2::UTF8ByteString { value, .. }) = tv {
+            let value = std::str::from_utf8(value).map_err(Error::UTF8Parsing)?;
+            if ef.contains(&value) {
+              let err_count = self.errors.len();
+              self.visit_type2(target)?;
+              if self.errors.len() > err_count {
+                self.has_feature_errors = true;
+              }
+              self.ctrl = None;
+            } else {
+              self.add_error(format!("feature \"{}\" is not enabled", value));
+            }
           }
-        } else if let Some(params) = generic_params_from_rule(rule) {
-          self.generic_rules.push(GenericRule {
-            name: entry.name.ident,
-            params,
-            args: ga.args.iter().cloned().map(|arg| *arg.arg).collect(),
-          });
+        } else {
+          // Without a configured feature set, the target is validated as if
+          // the feature were enabled
+          self.visit_type2(target)?;
         }
 
-        #[cfg(all(feature = "additional-controls", target_arch = "wasm32"))]
-        let mut jv =
-          JSONValidator::new(self.cddl, self.json.clone(), self.enabled_features.clone());
-        #[cfg(all(feature = "additional-controls", not(target_arch = "wasm32")))]
-        let mut jv = JSONValidator::new(self.cddl, self.json.clone(), self.enabled_features);
-        #[cfg(not(feature = "additional-controls"))]
-        let mut jv = JSONValidator::new(self.cddl, self.json.clone());
-
-        jv.generic_rules = self.generic_rules.clone();
-        jv.eval_generic_rule = Some(entry.name.ident);
-        jv.is_multi_type_choice = self.is_multi_type_choice;
-        jv.visit_rule(rule)?;
-
-        self.errors.append(&mut jv.errors);
-
-        return Ok(());
+        self.ctrl = None;
       }
-    }
-
-    let type_choice_alternates = type_choice_alternates_from_ident(self.cddl, &entry.name);
-    if !type_choice_alternates.is_empty() {
-      self.is_multi_type_choice = true;
-    }
+      #[cfg(feature = "additional-controls")]
+      #[cfg(target_arch = "wasm32")]
+      ControlOperator::FEATURE => {
+        self.ctrl = Some(ctrl);
 
-    let error_count = self.errors.len();
-    for t in type_choice_alternates {
-      let cur_errors = self.errors.len();
-      self.visit_type(t)?;
-      if self.errors.len() == cur_errors {
-        for _ in 0..self.errors.len() - error_count {
-          self.errors.pop();
+        if let Some(ef) = &self.enabled_features {
+          let tv = text_value_from_type2(self.cddl, controller);
+          if let Some(Type2::TextValue { value, .. }) = tv {
+            if ef.contains(&JsValue::from(value.as_ref())) {
+              let err_count = self.errors.len();
+              self.visit_type2(target)?;
+              if self.errors.len() > err_count {
+                self.has_feature_errors = true;
+              }
+              self.ctrl = None;
+            } else {
+              self.add_error(format!("feature \"{}\" is not enabled", value));
+            }
+          } else if let Some(Type2::UTF8ByteString { value, .. }) = tv {
+            let value = std::str::from_utf8(value).map_err(Error::UTF8Parsing)?;
+            if ef.contains(&JsValue::from(value)) {
+              let err_count = self.errors.len();
+              self.visit_type2(target)?;
+              if self.errors.len() > err_count {
+                self.has_feature_errors = true;
+              }
+              self.ctrl = None;
+            } else {
+              self.add_error(format!("feature \"{}\" is not enabled", value));
+            }
+          }
+        } else {
+          // Without a configured feature set, the target is validated as if
+          // the feature were enabled
+          self.visit_type2(target)?;
         }
 
-        return Ok(());
+        self.ctrl = None;
       }
-    }
-
-    let error_count = self.errors.len();
-    let group_choice_alternates = group_choice_alternates_from_ident(self.cddl, &entry.name);
-    if !group_choice_alternates.is_empty() {
-      self.is_multi_group_choice = true;
-    }
+      #[cfg(feature = "extensions")]
+      ControlOperator::JOIN => {
+        self.ctrl = Some(ctrl);
 
-    for ge in group_choice_alternates {
-      let cur_errors = self.errors.len();
-      self.visit_group_entry(ge)?;
-      if self.errors.len() == cur_errors {
-        for _ in 0..self.errors.len() - error_count {
-          self.errors.pop();
+        let error = match &self.json {
+          Value::String(s) => validate_join_control(self.cddl, target, controller, s).err(),
+          _ => Some(format!(
+            ".join control can only be matched against a JSON string, got {}",
+            self.json
+          )),
+        };
+        if let Some(e) = error {
+          self.add_error(e);
         }
 
-        return Ok(());
-      }
-    }
-
-    walk_type_groupname_entry(self, entry)?;
-    self.type_group_name_entry = None;
-
-    Ok(())
-  }
-
-  fn visit_memberkey(&mut self, mk: &MemberKey<'a>) -> visitor::Result<Error> {
-    match mk {
-      MemberKey::Type1 { is_cut, .. } => {
-        self.is_cut_present = *is_cut;
-        walk_memberkey(self, mk)?;
-        self.is_cut_present = false;
+        self.ctrl = None;
       }
-      MemberKey::Bareword { .. } => {
-        self.is_colon_shortcut_present = true;
-        walk_memberkey(self, mk)?;
-        self.is_colon_shortcut_present = false;
+      _ => {
+        self.add_error(format!("unsupported control operator {}", ctrl));
       }
-      _ => return walk_memberkey(self, mk),
     }
 
     Ok(())
   }
 
-  fn visit_value(&mut self, value: &token::Value<'a>) -> visitor::Result<Error> {
-    // FIXME: If during traversal the type being validated is supposed to be a value,
-    // this fails
-    if let Value::Array(_) = &self.json {
-      return self.validate_array_items(&ArrayItemToken::Value(value));
-    }
+  fn visit_type2(&mut self, t2: &Type2<'a>) -> visitor::Result<Error> {
+    match t2 {
+      Type2::TextValue { value, .. } => self.visit_value(&token::Value::TEXT(value.clone())),
+      Type2::Map { group, .. } => match &self.json {
+        Value::Object(o) => {
+          #[allow(clippy::needless_collect)]
+          let o = o.keys().cloned().collect::<Vec<_>>();
 
-    if let Value::Object(_) = &self.json {
-      return self.validate_object_value(value);
-    }
+          self.visit_group(group)?;
 
-    let error: Option<String> = match value {
-      token::Value::INT(v) => match &self.json {
-        Value::Number(n) => match n.as_i64() {
-          Some(i) => match &self.ctrl {
-            Some(ControlOperator::NE) | Some(ControlOperator::DEFAULT) if i != *v as i64 => None,
-            Some(ControlOperator::LT) if i < *v as i64 => None,
-            Some(ControlOperator::LE) if i <= *v as i64 => None,
-            Some(ControlOperator::GT) if i > *v as i64 => None,
-            Some(ControlOperator::GE) if i >= *v as i64 => None,
-            #[cfg(feature = "additional-controls")]
-            Some(ControlOperator::PLUS) => {
-              if i == *v as i64 {
-                None
-              } else {
-                Some(format!("expected computed .plus value {}, got {}", v, n))
-              }
-            }
-            #[cfg(feature = "additional-controls")]
-            None | Some(ControlOperator::FEATURE) => {
-              if i == *v as i64 {
-                None
-              } else {
-                Some(format!("expected value {}, got {}", v, n))
-              }
-            }
-            #[cfg(not(feature = "additional-controls"))]
-            None => {
-              if i == *v as i64 {
-                None
-              } else {
-                Some(format!("expected value {}, got {}", v, n))
+          if self.values_to_validate.is_none() {
+            for k in o.into_iter() {
+              if let Some(keys) = &self.validated_keys {
+                if !keys.contains(&k) && !self.options.allow_extra_keys {
+                  self.add_error(format!("unexpected key {:?}", k));
+                }
               }
             }
-            _ => Some(format!(
-              "expected value {} {}, got {}",
-              self.ctrl.unwrap(),
-              v,
-              n
-            )),
-          },
-          None => Some(format!("{} cannot be represented as an i64", n)),
-        },
-        _ => Some(format!("expected value {}, got {}", v, self.json)),
+          }
+
+          self.is_cut_present = false;
+          self.cut_value = None;
+          Ok(())
+        }
+        Value::Array(_) => self.validate_array_items(&ArrayItemToken::Map(t2)),
+        _ => {
+          self.add_error(format!("expected map object {}, got {}", t2, self.json));
+          Ok(())
+        }
       },
-      token::Value::UINT(v) => match &self.json {
-        Value::Number(n) => match n.as_u64() {
-          Some(i) => match &self.ctrl {
-            Some(ControlOperator::NE) | Some(ControlOperator::DEFAULT) if i != *v as u64 => None,
-            Some(ControlOperator::LT) if i < *v as u64 => None,
-            Some(ControlOperator::LE) if i <= *v as u64 => None,
-            Some(ControlOperator::GT) if i > *v as u64 => None,
-            Some(ControlOperator::GE) if i >= *v as u64 => None,
-            Some(ControlOperator::SIZE) => match 256u128.checked_pow(*v as u32) {
-              Some(n) if (i as u128) < n => None,
-              _ => Some(format!("expected value .size {}, got {}", v, n)),
-            },
-            #[cfg(feature = "additional-controls")]
-            Some(ControlOperator::PLUS) => {
-              if i == *v as u64 {
-                None
-              } else {
-                Some(format!("expected computed .plus value {}, got {}", v, n))
+      Type2::Array { group, .. } => match &self.json {
+        Value::Array(a) => {
+          if group.group_choices.len() == 1
+            && group.group_choices[0].group_entries.is_empty()
+            && !a.is_empty()
+            && !matches!(self.ctrl, Some(ControlOperator::NE))
+          {
+            self.add_error(format!("expected empty array, got {}", self.json));
+            return Ok(());
+          }
+
+          if group
+            .group_choices
+            .iter()
+            .all(|gc| is_positional_array_group_choice(self.cddl, gc))
+          {
+            let initial_error_count = self.errors.len();
+            for gc in group.group_choices.iter() {
+              let error_count = self.errors.len();
+              self.validate_array_items_positionally(gc)?;
+              if self.errors.len() == error_count {
+                self.errors.truncate(initial_error_count);
+                break;
               }
             }
-            #[cfg(feature = "additional-controls")]
-            None | Some(ControlOperator::FEATURE) => {
-              if i == *v as u64 {
-                None
-              } else {
-                Some(format!("expected value {}, got {}", v, n))
+
+            return Ok(());
+          }
+
+          if let Some((occur, choices)) = array_of_group_choices(group) {
+            return self.validate_array_items_per_group_choice(occur, choices);
+          }
+
+          // Occurrence indicators and positions of the array's entries don't
+          // apply beyond the array, e.g. to the next choice of a type choice
+          let outer_occurrence = self.occurrence.take();
+          let outer_entry_idx = self.group_entry_idx.take();
+          self.entry_counts = Some(entry_counts_from_group(self.cddl, group));
+          self.visit_group(group)?;
+          self.entry_counts = None;
+          self.occurrence = outer_occurrence;
+          self.group_entry_idx = outer_entry_idx;
+
+          if let Some(errors) = &mut self.array_errors {
+            if let Some(indices) = &self.valid_array_items {
+              for idx in indices.iter() {
+                errors.remove(idx);
               }
             }
-            #[cfg(not(feature = "additional-controls"))]
-            None => {
-              if i == *v as u64 {
-                None
-              } else {
-                Some(format!("expected value {}, got {}", v, n))
-              }
-            }
-            _ => Some(format!(
-              "expected value {} {}, got {}",
-              self.ctrl.unwrap(),
-              v,
-              n
-            )),
-          },
-          None => Some(format!("{} cannot be represented as a u64", n)),
-        },
-        Value::String(s) => match &self.ctrl {
-          Some(ControlOperator::SIZE) => {
-            if s.len() == *v {
-              None
-            } else {
-              Some(format!("expected \"{}\" .size {}, got {}", s, v, s.len()))
+
+            for error in errors.values_mut() {
+              self.errors.append(error);
             }
           }
-          _ => Some(format!("expected {}, got {}", v, s)),
-        },
-        _ => Some(format!("expected value {}, got {}", v, self.json)),
-      },
-      token::Value::FLOAT(v) => match &self.json {
-        Value::Number(n) => match n.as_f64() {
-          Some(f) => match &self.ctrl {
-            Some(ControlOperator::NE) | Some(ControlOperator::DEFAULT)
-              if (f - *v).abs() > std::f64::EPSILON =>
-            {
-              None
-            }
-            Some(ControlOperator::LT) if f < *v => None,
-            Some(ControlOperator::LE) if f <= *v => None,
-            Some(ControlOperator::GT) if f > *v => None,
-            Some(ControlOperator::GE) if f >= *v => None,
-            #[cfg(feature = "additional-controls")]
-            Some(ControlOperator::PLUS) => {
-              if (f - *v).abs() < std::f64::EPSILON {
-                None
-              } else {
-                Some(format!("expected computed .plus value {}, got {}", v, n))
-              }
-            }
-            #[cfg(feature = "additional-controls")]
-            None | Some(ControlOperator::FEATURE) => {
-              if (f - *v).abs() < std::f64::EPSILON {
-                None
-              } else {
-                Some(format!("expected value {}, got {}", v, n))
-              }
-            }
-            #[cfg(not(feature = "additional-controls"))]
-            None => {
-              if (f - *v).abs() < std::f64::EPSILON {
-                None
-              } else {
-                Some(format!("expected value {}, got {}", v, n))
-              }
-            }
-            _ => Some(format!(
-              "expected value {} {}, got {}",
-              self.ctrl.unwrap(),
-              v,
-              n
-            )),
-          },
-          None => Some(format!("{} cannot be represented as an i64", n)),
-        },
-        _ => Some(format!("expected value {}, got {}", v, self.json)),
+
+          self.valid_array_items = None;
+          self.array_errors = None;
+
+          Ok(())
+        }
+        _ => {
+          self.add_error(format!("expected array type {}, got {}", t2, self.json));
+          Ok(())
+        }
       },
-      token::Value::TEXT(t) => match &self.json {
-        Value::String(s) => match &self.ctrl {
-          Some(ControlOperator::NE) | Some(ControlOperator::DEFAULT) => {
-            if s != t {
-              None
-            } else {
-              Some(format!("expected {} .ne to \"{}\"", value, s))
-            }
+      Type2::ChoiceFromGroup {
+        ident,
+        generic_args,
+        ..
+      } => {
+        if let Some(ga) = generic_args {
+          if let Some(rule) = rule_from_ident(self.cddl, ident) {
+            let is_group_to_choice_enum = self.is_group_to_choice_enum;
+            self.is_group_to_choice_enum = true;
+            self.validate_generic_rule(ident.ident, rule, ga)?;
+            self.is_group_to_choice_enum = is_group_to_choice_enum;
+
+            return Ok(());
           }
-          Some(ControlOperator::REGEXP) | Some(ControlOperator::PCRE) => {
-            let re = regex::Regex::new(
-              &format_regex(
-                // Text strings must be JSON escaped per
-                // https://datatracker.ietf.org/doc/html/rfc8610#section-3.1
-                serde_json::from_str::<Value>(&format!("\"{}\"", t))
-                  .map_err(Error::JSONParsing)?
-                  .as_str()
-                  .ok_or_else(|| Error::from_validator(self, "malformed regex".to_string()))?,
-              )
-              .ok_or_else(|| Error::from_validator(self, "malformed regex".to_string()))?,
-            )
-            .map_err(|e| Error::from_validator(self, e.to_string()))?;
+        }
 
-            if re.is_match(s) {
-              None
-            } else {
-              Some(format!("expected \"{}\" to match regex \"{}\"", s, t))
-            }
+        if group_rule_from_ident(self.cddl, ident).is_none() {
+          self.add_error(format!(
+            "rule {} must be a group rule to turn it into a choice",
+            ident
+          ));
+          return Ok(());
+        }
+
+        let is_group_to_choice_enum = self.is_group_to_choice_enum;
+        self.is_group_to_choice_enum = true;
+        self.visit_identifier(ident)?;
+        self.is_group_to_choice_enum = is_group_to_choice_enum;
+
+        Ok(())
+      }
+      Type2::ChoiceFromInlineGroup { group, .. } => {
+        let is_group_to_choice_enum = self.is_group_to_choice_enum;
+        self.is_group_to_choice_enum = true;
+        self.visit_group(group)?;
+        self.is_group_to_choice_enum = is_group_to_choice_enum;
+        Ok(())
+      }
+      Type2::Typename {
+        ident,
+        generic_args,
+        ..
+      } => {
+        if let Some(ga) = generic_args {
+          if let Some(rule) = rule_from_ident(self.cddl, ident) {
+            return self.validate_generic_rule(ident.ident, rule, ga);
           }
-          #[cfg(feature = "additional-controls")]
-          Some(ControlOperator::ABNF) => validate_abnf(t, s)
-            .err()
-            .map(|e| format!("\"{}\" is not valid against abnf: {}", s, e)),
+        }
 
-          _ => {
-            #[cfg(feature = "additional-controls")]
-            if s == t {
-              None
-            } else if let Some(ControlOperator::CAT) | Some(ControlOperator::DET) = &self.ctrl {
-              Some(format!(
-                "expected value to match concatenated string {}, got \"{}\"",
-                value, s
-              ))
-            } else if let Some(ctrl) = &self.ctrl {
-              Some(format!("expected value {} {}, got \"{}\"", ctrl, value, s))
-            } else {
-              Some(format!("expected value {} got \"{}\"", value, s))
+        let type_choice_alternates = type_choice_alternates_from_ident(self.cddl, ident);
+        if !type_choice_alternates.is_empty() {
+          self.is_multi_type_choice = true;
+        }
+
+        // A socket or type extended with `/=` is the choice of its base
+        // definition, if any, and all of its alternates
+        if let Some(choices) = extended_type_choices(self.cddl, ident, &type_choice_alternates) {
+          if self.visited_rules.contains(&ident.ident) {
+            self.add_error(format!("cyclic rule reference: {}", ident));
+            return Ok(());
+          }
+
+          let t = Type {
+            type_choices: choices,
+            #[cfg(feature = "ast-span")]
+            span: ident.span,
+          };
+
+          self.visited_rules.push(ident.ident);
+          let result = self.visit_type(&t);
+          self.visited_rules.pop();
+
+          return result;
+        }
+
+        let error_count = self.errors.len();
+        for t in type_choice_alternates {
+          let cur_errors = self.errors.len();
+          self.visit_type(t)?;
+          if self.errors.len() == cur_errors {
+            for _ in 0..self.errors.len() - error_count {
+              self.errors.pop();
             }
 
-            #[cfg(not(feature = "additional-controls"))]
-            if s == t {
-              None
-            } else if let Some(ctrl) = &self.ctrl {
-              Some(format!("expected value {} {}, got \"{}\"", ctrl, value, s))
-            } else {
-              Some(format!("expected value {} got \"{}\"", value, s))
+            return Ok(());
+          }
+        }
+
+        self.visit_identifier(ident)
+      }
+      Type2::IntValue { value, .. } => self.visit_value(&token::Value::INT(*value)),
+      Type2::UintValue { value, .. } => self.visit_value(&token::Value::UINT(*value)),
+      Type2::FloatValue { value, .. } => self.visit_value(&token::Value::FLOAT(*value)),
+      Type2::ParenthesizedType { pt, .. } => self.visit_type(pt),
+      Type2::Unwrap {
+        ident,
+        generic_args,
+        ..
+      } => {
+        // Per
+        // https://github.com/w3c/did-spec-registries/pull/138#issuecomment-719739215,
+        // strip tag and validate underlying type
+        if let Some(Type2::TaggedData { t, .. }) = tag_from_token(&lookup_ident(ident.ident)) {
+          return self.visit_type(&t);
+        }
+
+        if let Some(ga) = generic_args {
+          if let Some(rule) = unwrap_rule_from_ident(self.cddl, ident) {
+            return self.validate_generic_rule(ident.ident, rule, ga);
+          }
+        }
+
+        if let Some(rule) = unwrap_rule_from_ident(self.cddl, ident) {
+          return self.visit_rule(rule);
+        }
+
+        Err(Error::Syntax(SyntaxError::new(
+          SyntaxErrorKind::UndefinedRule,
+          &format!("cannot unwrap identifier {}, rule not found", ident),
+        )))
+      }
+      // JSON has no notion of tags, so the tag number is ignored and the
+      // value is validated against the tagged content type
+      Type2::TaggedData { t, .. } => self.visit_type(t),
+      #[cfg(feature = "ast-span")]
+      Type2::Any { .. } => Ok(()),
+      #[cfg(not(feature = "ast-span"))]
+      Type2::Any {} => Ok(()),
+      _ => {
+        self.add_error(format!(
+          "unsupported data type for validating JSON, got {}",
+          t2
+        ));
+        Ok(())
+      }
+    }
+  }
+
+  fn visit_identifier(&mut self, ident: &Identifier<'a>) -> visitor::Result<Error> {
+    if let Some(name) = self.eval_generic_rule {
+      if let Some(gr) = self
+        .generic_rules
+        .iter()
+        .cloned()
+        .find(|gr| gr.name == name)
+      {
+        for (idx, gp) in gr.params.iter().enumerate() {
+          if *gp == ident.ident {
+            if let Some(arg) = gr.args.get(idx) {
+              return self.visit_type1(arg);
             }
           }
-        },
-        _ => Some(format!("expected value {}, got {}", t, self.json)),
-      },
-      token::Value::BYTE(token::ByteValue::UTF8(b)) => match &self.json {
-        Value::String(s) if s.as_bytes() == b.as_ref() => None,
-        _ => Some(format!("expected byte value {:?}, got {}", b, self.json)),
-      },
-      token::Value::BYTE(token::ByteValue::B16(b)) => match &self.json {
-        Value::String(s) if s.as_bytes() == b.as_ref() => None,
-        _ => Some(format!("expected byte value {:?}, got {}", b, self.json)),
-      },
-      token::Value::BYTE(token::ByteValue::B64(b)) => match &self.json {
-        Value::String(s) if s.as_bytes() == b.as_ref() => None,
-        _ => Some(format!("expected byte value {:?}, got {}", b, self.json)),
-      },
-    };
+        }
+      }
+    }
+
+    // self.is_colon_shortcut_present is only true when the ident is part of a
+    // member key
+    if !self.is_colon_shortcut_present {
+      if let Some(r) = rule_from_ident(self.cddl, ident) {
+        if self.visited_rules.contains(&ident.ident) {
+          self.add_error(format!("cyclic rule reference: {}", ident));
+          return Ok(());
+        }
+
+        self.visited_rules.push(ident.ident);
+        let result = self.visit_rule(r);
+        self.visited_rules.pop();
+
+        return result;
+      }
+    }
+
+    // Member keys aren't types, so only prelude types terminate the rule
+    // reference chain reported in errors
+    if self.is_colon_shortcut_present {
+      return self.validate_prelude_identifier(ident);
+    }
+
+    self.rule_chain = self.visited_rules.clone();
+    self.rule_chain.push(ident.ident);
+    let result = self.validate_prelude_identifier(ident);
+    self.rule_chain.clear();
+
+    result
+  }
+
+  fn visit_value_member_key_entry(
+    &mut self,
+    entry: &ValueMemberKeyEntry<'a>,
+  ) -> visitor::Result<Error> {
+    if let Some(occur) = &entry.occur {
+      self.visit_occurrence(occur)?;
+    }
+
+    let current_location = self.json_location.clone();
+
+    if let Some(mk) = &entry.member_key {
+      let error_count = self.errors.len();
+      self.is_member_key = true;
+      self.visit_memberkey(mk)?;
+      self.is_member_key = false;
+
+      // Move to next entry if member key validation fails
+      if self.errors.len() != error_count {
+        self.advance_to_next_entry = true;
+        return Ok(());
+      }
+    }
+
+    if let Some(values) = &self.values_to_validate {
+      for v in values.iter() {
+        #[cfg(all(feature = "additional-controls", target_arch = "wasm32"))]
+        let mut jv = JSONValidator::new(self.cddl, v.clone(), self.enabled_features.clone());
+        #[cfg(all(feature = "additional-controls", not(target_arch = "wasm32")))]
+        let mut jv = JSONValidator::new(self.cddl, v.clone(), self.enabled_features);
+        #[cfg(not(feature = "additional-controls"))]
+        let mut jv = JSONValidator::new(self.cddl, v.clone());
+
+        jv.generic_rules = self.generic_rules.clone();
+        jv.options = self.options;
+        jv.eval_generic_rule = self.eval_generic_rule;
+        jv.is_multi_type_choice = self.is_multi_type_choice;
+        jv.is_multi_group_choice = self.is_multi_group_choice;
+        jv.json_location.push_str(&self.json_location);
+        jv.type_group_name_entry = self.type_group_name_entry;
+        jv.visit_type(&entry.entry_type)?;
+
+        self.json_location = current_location.clone();
+
+        self.errors.append(&mut jv.errors);
+        if entry.occur.is_some() {
+          self.occurrence = None;
+        }
+      }
+
+      return Ok(());
+    }
+
+    if let Some(v) = self.object_value.take() {
+      let is_null = v.is_null();
+
+      #[cfg(all(feature = "additional-controls", target_arch = "wasm32"))]
+      let mut jv = JSONValidator::new(self.cddl, v, self.enabled_features.clone());
+      #[cfg(all(feature = "additional-controls", not(target_arch = "wasm32")))]
+      let mut jv = JSONValidator::new(self.cddl, v, self.enabled_features);
+      #[cfg(not(feature = "additional-controls"))]
+      let mut jv = JSONValidator::new(self.cddl, v);
+
+      jv.generic_rules = self.generic_rules.clone();
+      jv.options = self.options;
+      jv.eval_generic_rule = self.eval_generic_rule;
+      jv.is_multi_type_choice = self.is_multi_type_choice;
+      jv.is_multi_group_choice = self.is_multi_group_choice;
+      jv.json_location.push_str(&self.json_location);
+      jv.type_group_name_entry = self.type_group_name_entry;
+      jv.visit_type(&entry.entry_type)?;
+
+      // Distinguish an explicit null from an absent optional member
+      if is_null
+        && !jv.errors.is_empty()
+        && matches!(&entry.occur, Some(o) if is_optional_occurrence(&o.occur))
+      {
+        jv.errors.clear();
+        jv.add_error(format!(
+          "member present but null; expected {}",
+          entry.entry_type
+        ));
+      }
+
+      self.json_location = current_location;
+
+      self.errors.append(&mut jv.errors);
+      if entry.occur.is_some() {
+        self.occurrence = None;
+      }
+
+      Ok(())
+    } else if !self.advance_to_next_entry {
+      self.visit_type(&entry.entry_type)
+    } else {
+      Ok(())
+    }
+  }
+
+  fn visit_type_groupname_entry(
+    &mut self,
+    entry: &TypeGroupnameEntry<'a>,
+  ) -> visitor::Result<Error> {
+    self.type_group_name_entry = Some(entry.name.ident);
+
+    if let Some(ga) = &entry.generic_args {
+      if let Some(rule) = rule_from_ident(self.cddl, &entry.name) {
+        return self.validate_generic_rule(entry.name.ident, rule, ga);
+      }
+    }
+
+    let type_choice_alternates = type_choice_alternates_from_ident(self.cddl, &entry.name);
+    if !type_choice_alternates.is_empty() {
+      self.is_multi_type_choice = true;
+    }
+
+    let error_count = self.errors.len();
+    for t in type_choice_alternates {
+      let cur_errors = self.errors.len();
+      self.visit_type(t)?;
+      if self.errors.len() == cur_errors {
+        for _ in 0..self.errors.len() - error_count {
+          self.errors.pop();
+        }
+
+        return Ok(());
+      }
+    }
+
+    let error_count = self.errors.len();
+    let group_choice_alternates = group_choice_alternates_from_ident(self.cddl, &entry.name);
+    if !group_choice_alternates.is_empty() {
+      self.is_multi_group_choice = true;
+
+      // An optional plug within an object is absent when none of the member
+      // keys of its base definition or alternates are present
+      if let Value::Object(o) = &self.json {
+        if matches!(&entry.occur, Some(o) if is_optional_occurrence(&o.occur)) {
+          let base = group_rule_from_ident(self.cddl, &entry.name).map(|rule| &rule.entry);
+          let is_present = base
+            .into_iter()
+            .chain(group_choice_alternates.iter().copied())
+            .flat_map(|ge| member_keys_from_group_entry(self.cddl, ge))
+            .any(|k| o.contains_key(k));
+
+          if !is_present {
+            self.type_group_name_entry = None;
+            return Ok(());
+          }
+        }
+      }
+    }
+
+    let is_plug = rule_from_ident(self.cddl, &entry.name).is_none();
+    for ge in group_choice_alternates.iter().copied() {
+      let cur_errors = self.errors.len();
+      self.visit_group_entry(ge)?;
+      if self.errors.len() == cur_errors {
+        for _ in 0..self.errors.len() - error_count {
+          self.errors.pop();
+        }
+
+        return Ok(());
+      }
+    }
+
+    // A plug without a base definition only matches one of its alternates
+    if is_plug && !group_choice_alternates.is_empty() {
+      self.type_group_name_entry = None;
+      return Ok(());
+    }
+
+    // An optional group reference within an object is all-or-nothing: it is
+    // absent when none of its member keys are present, otherwise each of its
+    // members is validated as written. Groups without literal member keys,
+    // e.g. `* ( text => any )`, match any number of members instead
+    if let Value::Object(o) = &self.json {
+      let is_optional = matches!(&entry.occur, Some(o) if is_optional_occurrence(&o.occur));
+
+      if is_optional {
+        let member_keys = group_rule_from_ident(self.cddl, &entry.name)
+          .map(|rule| member_keys_from_group_entry(self.cddl, &rule.entry))
+          .filter(|keys| !keys.is_empty());
+
+        if let Some(member_keys) = member_keys {
+          let is_present = member_keys.iter().any(|k| o.contains_key(*k));
+
+          let error_count = self.errors.len();
+          self.occurrence = None;
+          self.visit_identifier(&entry.name)?;
+          if !is_present {
+            self.errors.truncate(error_count);
+          }
+          self.type_group_name_entry = None;
+
+          return Ok(());
+        }
+      }
+    }
+
+    walk_type_groupname_entry(self, entry)?;
+    self.type_group_name_entry = None;
+
+    Ok(())
+  }
+
+  fn visit_memberkey(&mut self, mk: &MemberKey<'a>) -> visitor::Result<Error> {
+    match mk {
+      MemberKey::Type1 { is_cut, .. } => {
+        self.is_cut_present = *is_cut;
+        walk_memberkey(self, mk)?;
+        self.is_cut_present = false;
+      }
+      MemberKey::Bareword { .. } => {
+        self.is_colon_shortcut_present = true;
+        walk_memberkey(self, mk)?;
+        self.is_colon_shortcut_present = false;
+      }
+      _ => return walk_memberkey(self, mk),
+    }
+
+    Ok(())
+  }
+
+  fn visit_value(&mut self, value: &token::Value<'a>) -> visitor::Result<Error> {
+    // FIXME: If during traversal the type being validated is supposed to be a value,
+    // this fails
+    if let Value::Array(_) = &self.json {
+      return self.validate_array_items(&ArrayItemToken::Value(value));
+    }
+
+    if let Value::Object(_) = &self.json {
+      return self.validate_object_value(value);
+    }
+
+    // Integers are compared by their decimal representation, so that negative
+    // integers and those beyond the 64-bit range are compared with literals of
+    // either sign
+    if let Value::Number(n) = &self.json {
+      if let Some(ordering) = compare_integer(n, value) {
+        let is_match = match self.ctrl {
+          None => Some(ordering.is_eq()),
+          Some(
+            ctrl @ (ControlOperator::NE
+            | ControlOperator::LT
+            | ControlOperator::LE
+            | ControlOperator::GT
+            | ControlOperator::GE),
+          ) => compare_ordering(ctrl, ordering),
+          Some(ControlOperator::SIZE) => match value {
+            token::Value::UINT(size) => Some(fits_size(n, *size)),
+            _ => None,
+          },
+          _ => None,
+        };
+
+        if let Some(is_match) = is_match {
+          match self.ctrl {
+            _ if is_match => (),
+            Some(ctrl) => self.add_error(format!("expected value {} {}, got {}", ctrl, value, n)),
+            None => self.add_error(format!("expected value {}, got {}", value, n)),
+          }
+
+          return Ok(());
+        }
+      }
+    }
+
+    let error: Option<String> = match value {
+      token::Value::INT(v) => match &self.json {
+        Value::Number(n) => match n.as_i64() {
+          Some(_) => match &self.ctrl {
+            Some(
+              ctrl @ (ControlOperator::NE
+              | ControlOperator::LT
+              | ControlOperator::LE
+              | ControlOperator::GT
+              | ControlOperator::GE),
+            ) if compare_numeric(&self.json, *ctrl, value) == Some(true) => None,
+            #[cfg(feature = "additional-controls")]
+            Some(ControlOperator::PLUS) => {
+              if compare_numeric(&self.json, ControlOperator::EQ, value) == Some(true) {
+                None
+              } else {
+                Some(format!("expected computed .plus value {}, got {}", v, n))
+              }
+            }
+            #[cfg(feature = "additional-controls")]
+            None | Some(ControlOperator::FEATURE) => {
+              if compare_numeric(&self.json, ControlOperator::EQ, value) == Some(true) {
+                None
+              } else {
+                Some(format!("expected value {}, got {}", v, n))
+              }
+            }
+            #[cfg(not(feature = "additional-controls"))]
+            None => {
+              if compare_numeric(&self.json, ControlOperator::EQ, value) == Some(true) {
+                None
+              } else {
+                Some(format!("expected value {}, got {}", v, n))
+              }
+            }
+            _ => Some(format!(
+              "expected value {} {}, got {}",
+              self.ctrl.unwrap(),
+              v,
+              n
+            )),
+          },
+          None => Some(format!("{} cannot be represented as an i64", n)),
+        },
+        _ => Some(format!("expected value {}, got {}", v, self.json)),
+      },
+      token::Value::UINT(v) => match &self.json {
+        Value::Number(n) => match n.as_u64() {
+          Some(i) => match &self.ctrl {
+            Some(
+              ctrl @ (ControlOperator::NE
+              | ControlOperator::LT
+              | ControlOperator::LE
+              | ControlOperator::GT
+              | ControlOperator::GE),
+            ) if compare_numeric(&self.json, *ctrl, value) == Some(true) => None,
+            Some(ControlOperator::SIZE) => match 256u128.checked_pow(*v as u32) {
+              Some(n) if (i as u128) < n => None,
+              _ => Some(format!("expected value .size {}, got {}", v, n)),
+            },
+            #[cfg(feature = "additional-controls")]
+            Some(ControlOperator::PLUS) => {
+              if compare_numeric(&self.json, ControlOperator::EQ, value) == Some(true) {
+                None
+              } else {
+                Some(format!("expected computed .plus value {}, got {}", v, n))
+              }
+            }
+            #[cfg(feature = "additional-controls")]
+            None | Some(ControlOperator::FEATURE) => {
+              if compare_numeric(&self.json, ControlOperator::EQ, value) == Some(true) {
+                None
+              } else {
+                Some(format!("expected value {}, got {}", v, n))
+              }
+            }
+            #[cfg(not(feature = "additional-controls"))]
+            None => {
+              if compare_numeric(&self.json, ControlOperator::EQ, value) == Some(true) {
+                None
+              } else {
+                Some(format!("expected value {}, got {}", v, n))
+              }
+            }
+            _ => Some(format!(
+              "expected value {} {}, got {}",
+              self.ctrl.unwrap(),
+              v,
+              n
+            )),
+          },
+          None => Some(format!("{} cannot be represented as a u64", n)),
+        },
+        Value::String(s) => match &self.ctrl {
+          Some(ControlOperator::SIZE) => {
+            if s.len() == *v {
+              None
+            } else {
+              Some(format!(
+                "expected \"{}\" .size {} (UTF-8 bytes), got {} bytes",
+                s,
+                v,
+                s.len()
+              ))
+            }
+          }
+          _ => Some(format!("expected {}, got {}", v, s)),
+        },
+        _ => Some(format!("expected value {}, got {}", v, self.json)),
+      },
+      token::Value::FLOAT(v) => match &self.json {
+        Value::Number(n) => match n.as_f64() {
+          Some(_) => match &self.ctrl {
+            Some(
+              ctrl @ (ControlOperator::NE
+              | ControlOperator::LT
+              | ControlOperator::LE
+              | ControlOperator::GT
+              | ControlOperator::GE),
+            ) if compare_numeric(&self.json, *ctrl, value) == Some(true) => None,
+            #[cfg(feature = "additional-controls")]
+            Some(ControlOperator::PLUS) => {
+              if compare_numeric(&self.json, ControlOperator::EQ, value) == Some(true) {
+                None
+              } else {
+                Some(format!("expected computed .plus value {}, got {}", v, n))
+              }
+            }
+            #[cfg(feature = "additional-controls")]
+            None | Some(ControlOperator::FEATURE) => {
+              if compare_numeric(&self.json, ControlOperator::EQ, value) == Some(true) {
+                None
+              } else {
+                Some(format!("expected value {}, got {}", v, n))
+              }
+            }
+            #[cfg(not(feature = "additional-controls"))]
+            None => {
+              if compare_numeric(&self.json, ControlOperator::EQ, value) == Some(true) {
+                None
+              } else {
+                Some(format!("expected value {}, got {}", v, n))
+              }
+            }
+            _ => Some(format!(
+              "expected value {} {}, got {}",
+              self.ctrl.unwrap(),
+              v,
+              n
+            )),
+          },
+          None => Some(format!("{} cannot be represented as an i64", n)),
+        },
+        _ => Some(format!("expected value {}, got {}", v, self.json)),
+      },
+      token::Value::TEXT(t) => match &self.json {
+        Value::String(s) => match &self.ctrl {
+          Some(ControlOperator::NE) => {
+            if s != t {
+              None
+            } else {
+              Some(format!("expected value .ne {}, got \"{}\"", value, s))
+            }
+          }
+          Some(ControlOperator::REGEXP) | Some(ControlOperator::PCRE) => {
+            let re = regex::Regex::new(
+              &format_regex(
+                // Text strings must be JSON escaped per
+                // https://datatracker.ietf.org/doc/html/rfc8610#section-3.1
+                serde_json::from_str::<Value>(&format!("\"{}\"", t))
+                  .map_err(Error::JSONParsing)?
+                  .as_str()
+                  .ok_or_else(|| Error::from_validator(self, "malformed regex".to_string()))?,
+              )
+              .map(|re| anchor_regex(&re))
+              .ok_or_else(|| {
+                Error::Syntax(SyntaxError::new(
+                  SyntaxErrorKind::RegexLookaround,
+                  "regex uses unsupported lookaround",
+                ))
+              })?,
+            )
+            .map_err(|e| {
+              let mut error = ValidationError::from_validator(self, e.to_string());
+              error.source = Some(Arc::new(e));
+              Error::Validation(vec![error])
+            })?;
+
+            if re.is_match(s) {
+              None
+            } else {
+              Some(format!("expected \"{}\" to match regex \"{}\"", s, t))
+            }
+          }
+          #[cfg(feature = "additional-controls")]
+          Some(ControlOperator::ABNF) => validate_abnf(t, s)
+            .err()
+            .map(|e| format!("\"{}\" is not valid against abnf: {}", s, e)),
+
+          _ => {
+            #[cfg(feature = "additional-controls")]
+            if s == t {
+              None
+            } else if let Some(ControlOperator::CAT) | Some(ControlOperator::DET) = &self.ctrl {
+              Some(format!(
+                "expected value to match concatenated string {}, got \"{}\"",
+                value, s
+              ))
+            } else if let Some(ctrl) = &self.ctrl {
+              Some(format!("expected value {} {}, got \"{}\"", ctrl, value, s))
+            } else {
+              Some(format!("expected value {} got \"{}\"", value, s))
+            }
+
+            #[cfg(not(feature = "additional-controls"))]
+            if s == t {
+              None
+            } else if let Some(ctrl) = &self.ctrl {
+              Some(format!("expected value {} {}, got \"{}\"", ctrl, value, s))
+            } else {
+              Some(format!("expected value {} got \"{}\"", value, s))
+            }
+          }
+        },
+        _ => Some(format!("expected value {}, got {}", t, self.json)),
+      },
+      token::Value::BYTE(token::ByteValue::UTF8(b)) => match &self.json {
+        Value::String(s) if s.as_bytes() == b.as_ref() => None,
+        _ => Some(format!("expected byte value {:?}, got {}", b, self.json)),
+      },
+      token::Value::BYTE(token::ByteValue::B16(b)) => match &self.json {
+        Value::String(s) if s.as_bytes() == b.as_ref() => None,
+        _ => Some(format!("expected byte value {:?}, got {}", b, self.json)),
+      },
+      token::Value::BYTE(token::ByteValue::B64(b)) => match &self.json {
+        Value::String(s) if s.as_bytes() == b.as_ref() => None,
+        _ => Some(format!("expected byte value {:?}, got {}", b, self.json)),
+      },
+    };
+
+    if let Some(e) = error {
+      self.add_error(e);
+    }
+
+    Ok(())
+  }
+
+  fn visit_occurrence(&mut self, o: &Occurrence) -> visitor::Result<Error> {
+    self.occurrence = Some(o.occur);
+
+    Ok(())
+  }
+}
+
+/// Whether the given occurrence indicator allows zero occurrences
+fn is_optional_occurrence(occur: &Occur) -> bool {
+  matches!(
+    occur,
+    Occur::Optional { .. }
+      | Occur::ZeroOrMore { .. }
+      | Occur::Exact {
+        lower: None | Some(0),
+        ..
+      }
+  )
+}
+
+/// Nesting depth of arrays and objects in the given JSON value. Scalars have a
+/// depth of 0. Computed without recursion so that arbitrarily deep values
+/// can't overflow the stack
+fn json_depth(value: &Value) -> usize {
+  let mut max_depth = 0;
+  let mut stack = vec![(value, 0)];
+
+  while let Some((value, depth)) = stack.pop() {
+    match value {
+      Value::Array(a) => {
+        max_depth = max_depth.max(depth + 1);
+        stack.extend(a.iter().map(|v| (v, depth + 1)));
+      }
+      Value::Object(o) => {
+        max_depth = max_depth.max(depth + 1);
+        stack.extend(o.values().map(|v| (v, depth + 1)));
+      }
+      _ => (),
+    }
+  }
+
+  max_depth
+}
+
+/// Check the given JSON string for objects with duplicate keys, which would
+/// otherwise silently be reduced to the last occurrence when parsed
+pub fn check_duplicate_keys(json: &str) -> Result {
+  match serde_json::from_str::<DuplicateKeyCheck>(json) {
+    Ok(_) => Ok(()),
+    // Any JSON value is accepted, so data errors can only originate from
+    // duplicate keys
+    Err(e) if e.is_data() => Err(Error::DuplicateKey(e.to_string())),
+    Err(e) => Err(Error::JSONParsing(e)),
+  }
+}
+
+struct DuplicateKeyCheck;
+
+impl<'de> serde::de::Deserialize<'de> for DuplicateKeyCheck {
+  fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+  where
+    D: serde::de::Deserializer<'de>,
+  {
+    deserializer.deserialize_any(DuplicateKeyVisitor)
+  }
+}
+
+struct DuplicateKeyVisitor;
+
+impl<'de> serde::de::Visitor<'de> for DuplicateKeyVisitor {
+  type Value = DuplicateKeyCheck;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "a JSON value")
+  }
+
+  fn visit_bool<E>(self, _: bool) -> std::result::Result<Self::Value, E> {
+    Ok(DuplicateKeyCheck)
+  }
+
+  fn visit_i64<E>(self, _: i64) -> std::result::Result<Self::Value, E> {
+    Ok(DuplicateKeyCheck)
+  }
+
+  fn visit_u64<E>(self, _: u64) -> std::result::Result<Self::Value, E> {
+    Ok(DuplicateKeyCheck)
+  }
+
+  fn visit_f64<E>(self, _: f64) -> std::result::Result<Self::Value, E> {
+    Ok(DuplicateKeyCheck)
+  }
+
+  fn visit_str<E>(self, _: &str) -> std::result::Result<Self::Value, E> {
+    Ok(DuplicateKeyCheck)
+  }
+
+  fn visit_unit<E>(self) -> std::result::Result<Self::Value, E> {
+    Ok(DuplicateKeyCheck)
+  }
+
+  fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error>
+  where
+    A: serde::de::SeqAccess<'de>,
+  {
+    while seq.next_element::<DuplicateKeyCheck>()?.is_some() {}
+
+    Ok(DuplicateKeyCheck)
+  }
+
+  fn visit_map<A>(self, mut map: A) -> std::result::Result<Self::Value, A::Error>
+  where
+    A: serde::de::MapAccess<'de>,
+  {
+    let mut keys = std::collections::HashSet::new();
+    while let Some(key) = map.next_key::<String>()? {
+      if keys.contains(&key) {
+        return Err(serde::de::Error::custom(format!(
+          "duplicate object key {:?}",
+          key
+        )));
+      }
+
+      map.next_value::<DuplicateKeyCheck>()?;
+      keys.insert(key);
+    }
+
+    Ok(DuplicateKeyCheck)
+  }
+}
+
+/// Name of the kind of a JSON value
+fn json_kind(value: &Value) -> &'static str {
+  match value {
+    Value::Null => "null",
+    Value::Bool(_) => "bool",
+    Value::Number(_) => "number",
+    Value::String(_) => "string",
+    Value::Array(_) => "array",
+    Value::Object(_) => "object",
+  }
+}
+
+/// The kind of JSON value a type admits, if it admits exactly one kind
+fn expected_json_kind(cddl: &CDDL, t2: &Type2) -> Option<&'static str> {
+  type2_json_kind(cddl, t2, &mut Vec::new())
+}
+
+/// The kind of JSON value a type admits, resolving rule references which
+/// aren't already being resolved further up in `visited_rules`
+fn type2_json_kind<'a>(
+  cddl: &'a CDDL,
+  t2: &'a Type2,
+  visited_rules: &mut Vec<&'a str>,
+) -> Option<&'static str> {
+  match t2 {
+    Type2::TextValue { .. }
+    | Type2::UTF8ByteString { .. }
+    | Type2::B16ByteString { .. }
+    | Type2::B64ByteString { .. } => Some("string"),
+    Type2::IntValue { .. } | Type2::UintValue { .. } | Type2::FloatValue { .. } => Some("number"),
+    Type2::Map { .. } => Some("object"),
+    Type2::Array { .. } => Some("array"),
+    Type2::ParenthesizedType { pt, .. } => {
+      type_choices_json_kind(cddl, pt.type_choices.iter(), visited_rules)
+    }
+    Type2::Typename {
+      ident,
+      generic_args: None,
+      ..
+    } => {
+      let mut rules = cddl
+        .rules
+        .iter()
+        .filter(|r| r.name() == ident.ident)
+        .peekable();
+      if rules.peek().is_none() {
+        return prelude_json_kind(cddl, ident);
+      }
+
+      if visited_rules.contains(&ident.ident) {
+        return None;
+      }
+
+      let mut type_choices = Vec::new();
+      for rule in rules {
+        match rule {
+          Rule::Type { rule, .. } if rule.generic_params.is_none() => {
+            type_choices.extend(rule.value.type_choices.iter())
+          }
+          _ => return None,
+        }
+      }
+
+      visited_rules.push(ident.ident);
+      let kind = type_choices_json_kind(cddl, type_choices.into_iter(), visited_rules);
+      visited_rules.pop();
+
+      kind
+    }
+    _ => None,
+  }
+}
+
+/// The kind of JSON value admitted by every one of the type choices
+fn type_choices_json_kind<'a>(
+  cddl: &'a CDDL,
+  mut type_choices: impl Iterator<Item = &'a TypeChoice<'a>>,
+  visited_rules: &mut Vec<&'a str>,
+) -> Option<&'static str> {
+  let kind = type2_json_kind(cddl, &type_choices.next()?.type1.type2, visited_rules)?;
+
+  if type_choices.all(|tc| type2_json_kind(cddl, &tc.type1.type2, visited_rules) == Some(kind)) {
+    Some(kind)
+  } else {
+    None
+  }
+}
+
+/// Indices of the failed choices whose literal-valued members, e.g.
+/// `type: "a"`, match the JSON object, given whether each choice's members
+/// match. Only applies if every choice has such members and at least one but
+/// not all of them match
+fn discriminated_choices(discriminant_matches: &[Option<bool>]) -> Option<Vec<usize>> {
+  if discriminant_matches.len() < 2
+    || !discriminant_matches.iter().all(|matches| matches.is_some())
+    || discriminant_matches
+      .iter()
+      .all(|matches| *matches == Some(true))
+    || !discriminant_matches.contains(&Some(true))
+  {
+    return None;
+  }
+
+  Some(
+    discriminant_matches
+      .iter()
+      .enumerate()
+      .filter(|(_, matches)| **matches == Some(true))
+      .map(|(idx, _)| idx)
+      .collect(),
+  )
+}
+
+/// Whether the literal-valued members of a map type, e.g. `type: "a"`, all
+/// match the corresponding members of a JSON object. `None` if the type isn't
+/// a map with at least one such member
+fn type2_discriminant_matches<'a>(
+  cddl: &'a CDDL,
+  t2: &'a Type2,
+  value: &Value,
+  visited_rules: &mut Vec<&'a str>,
+) -> Option<bool> {
+  match t2 {
+    Type2::Map { group, .. } => match &group.group_choices[..] {
+      [gc] => group_choice_discriminant_matches(cddl, gc, value, visited_rules),
+      _ => None,
+    },
+    Type2::ParenthesizedType { pt, .. } => match &pt.type_choices[..] {
+      [tc] => type2_discriminant_matches(cddl, &tc.type1.type2, value, visited_rules),
+      _ => None,
+    },
+    Type2::Typename {
+      ident,
+      generic_args: None,
+      ..
+    } if !visited_rules.contains(&ident.ident) => {
+      let rule = type_rule_from_ident(cddl, ident)?;
+      match &rule.value.type_choices[..] {
+        [tc] if rule.generic_params.is_none() => {
+          visited_rules.push(ident.ident);
+          let matches = type2_discriminant_matches(cddl, &tc.type1.type2, value, visited_rules);
+          visited_rules.pop();
+
+          matches
+        }
+        _ => None,
+      }
+    }
+    _ => None,
+  }
+}
+
+/// Whether the literal-valued members of a group choice, e.g. `type: "a"`,
+/// all match the corresponding members of a JSON object, including those of
+/// nested inline groups and referenced group rules. `None` if the group choice
+/// has no such members
+fn group_choice_discriminant_matches<'a>(
+  cddl: &'a CDDL,
+  gc: &'a GroupChoice,
+  value: &Value,
+  visited_rules: &mut Vec<&'a str>,
+) -> Option<bool> {
+  let o = match value {
+    Value::Object(o) => o,
+    _ => return None,
+  };
+
+  let mut matches = None;
+  for (ge, _) in gc.group_entries.iter() {
+    let entry_matches = match ge {
+      GroupEntry::ValueMemberKey { ge, .. } if ge.occur.is_none() => {
+        let key = match &ge.member_key {
+          Some(MemberKey::Bareword { ident, .. }) => ident.ident,
+          Some(MemberKey::Value {
+            value: token::Value::TEXT(t),
+            ..
+          }) => t.as_ref(),
+          _ => continue,
+        };
+
+        let literal = match &ge.entry_type.type_choices[..] {
+          [tc] if tc.type1.operator.is_none() => literal_json_value(&tc.type1.type2),
+          _ => None,
+        };
+        let literal = match literal {
+          Some(literal) => literal,
+          None => continue,
+        };
+
+        Some(o.get(key) == Some(&literal))
+      }
+      GroupEntry::InlineGroup { group, occur, .. } if occur.is_none() => {
+        match &group.group_choices[..] {
+          [gc] => group_choice_discriminant_matches(cddl, gc, value, visited_rules),
+          _ => None,
+        }
+      }
+      GroupEntry::TypeGroupname { ge, .. }
+        if ge.occur.is_none()
+          && ge.generic_args.is_none()
+          && !visited_rules.contains(&ge.name.ident) =>
+      {
+        match group_rule_from_ident(cddl, &ge.name) {
+          Some(GroupRule {
+            entry: GroupEntry::InlineGroup { group, .. },
+            generic_params: None,
+            ..
+          }) if group.group_choices.len() == 1 => {
+            visited_rules.push(ge.name.ident);
+            let matches = group_choice_discriminant_matches(
+              cddl,
+              &group.group_choices[0],
+              value,
+              visited_rules,
+            );
+            visited_rules.pop();
+
+            matches
+          }
+          _ => None,
+        }
+      }
+      _ => None,
+    };
+
+    if let Some(entry_matches) = entry_matches {
+      matches = Some(matches.unwrap_or(true) && entry_matches);
+    }
+  }
+
+  matches
+}
+
+/// The JSON value of a literal text or numeric type
+fn literal_json_value(t2: &Type2) -> Option<Value> {
+  match t2 {
+    Type2::TextValue { value, .. } => Some(Value::String(value.to_string())),
+    Type2::IntValue { value, .. } => Some(Value::from(*value as i64)),
+    Type2::UintValue { value, .. } => Some(Value::from(*value as u64)),
+    _ => None,
+  }
+}
+
+/// The kind of JSON value a prelude type admits, if it admits exactly one
+/// kind
+fn prelude_json_kind(cddl: &CDDL, ident: &Identifier) -> Option<&'static str> {
+  let kinds = [
+    (
+      is_ident_string_data_type(cddl, ident)
+        || is_ident_byte_string_data_type(cddl, ident)
+        || is_ident_uri_data_type(cddl, ident)
+        || is_ident_b64url_data_type(cddl, ident)
+        || is_ident_tdate_data_type(cddl, ident),
+      "string",
+    ),
+    (
+      is_ident_numeric_data_type(cddl, ident)
+        || is_ident_time_data_type(cddl, ident)
+        || is_ident_tdate_data_type(cddl, ident),
+      "number",
+    ),
+    (
+      is_ident_bool_data_type(cddl, ident)
+        || ident_matches_bool_value(cddl, ident, true)
+        || ident_matches_bool_value(cddl, ident, false),
+      "bool",
+    ),
+    (is_ident_null_data_type(cddl, ident), "null"),
+  ];
+
+  let mut matching = kinds.iter().filter(|(is_kind, _)| *is_kind);
+  match (matching.next(), matching.next()) {
+    (Some((_, kind)), None) => Some(kind),
+    _ => None,
+  }
+}
+
+/// The occurrence and inline group of an array consisting solely of a choice
+/// of groups whose entries each match array items, e.g. `[* (int // tstr)]` or
+/// `[(int, tstr // tstr, int)]`
+fn array_of_group_choices<'a, 'b>(
+  group: &'b Group<'a>,
+) -> Option<(Option<&'b Occurrence<'a>>, &'b Group<'a>)> {
+  match &group.group_choices[..] {
+    [gc] => match &gc.group_entries[..] {
+      [(GroupEntry::InlineGroup { occur, group, .. }, _)]
+        if group.group_choices.len() > 1
+          && group.group_choices.iter().all(|gc| {
+            !gc.group_entries.is_empty()
+              && gc.group_entries.iter().all(|(ge, _)| {
+                matches!(
+                  ge,
+                  GroupEntry::ValueMemberKey { .. } | GroupEntry::TypeGroupname { .. }
+                )
+              })
+          }) =>
+      {
+        Some((occur.as_ref(), group))
+      }
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+/// Minimum and maximum number of array items matched by the entries of a
+/// group choice
+fn group_choice_length_bounds(gc: &GroupChoice) -> (usize, usize) {
+  gc.group_entries
+    .iter()
+    .map(|(ge, _)| match ge {
+      GroupEntry::ValueMemberKey { ge, .. } => occurrence_bounds(ge.occur.as_ref()),
+      GroupEntry::TypeGroupname { ge, .. } => occurrence_bounds(ge.occur.as_ref()),
+      _ => (1, 1),
+    })
+    .fold((0, 0), |(min, max), (lower, upper)| {
+      (min.saturating_add(lower), max.saturating_add(upper))
+    })
+}
+
+/// Remove identical errors, e.g. those reported once per failing type or
+/// group choice, while preserving the order in which they were encountered
+fn dedup_errors(errors: &[ValidationError]) -> Vec<ValidationError> {
+  let mut deduped: Vec<ValidationError> = Vec::with_capacity(errors.len());
+  for e in errors.iter() {
+    if !deduped.contains(e) {
+      deduped.push(e.clone());
+    }
+  }
+
+  deduped
+}
+
+/// Whether the given object key is the canonical decimal representation of an
+/// integer of the given integer data type
+fn is_integer_key_of_type(cddl: &CDDL, ident: &Identifier, key: &str) -> bool {
+  if let Ok(n) = key.parse::<u64>() {
+    return n.to_string() == key && !is_ident_nint_data_type(cddl, ident);
+  }
+
+  if let Ok(n) = key.parse::<i64>() {
+    return n.to_string() == key && !is_ident_uint_data_type(cddl, ident);
+  }
+
+  false
+}
+
+/// Whether an integer outside of the range of `i64` and `u64` is negative.
+/// Such integers are only preserved as written with the
+/// `arbitrary-precision` feature, otherwise they are parsed as floats and
+/// `None` is returned
+fn big_integer_sign(n: &serde_json::Number) -> Option<bool> {
+  if n.is_i64() || n.is_u64() {
+    return None;
+  }
+
+  let n = n.to_string();
+  let digits = n.strip_prefix('-').unwrap_or(&n);
+  if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+    return None;
+  }
+
+  Some(n.starts_with('-'))
+}
+
+/// Compare two decimal integer literals of arbitrary size
+fn compare_integer_literals(a: &str, b: &str) -> std::cmp::Ordering {
+  use std::cmp::Ordering;
+
+  fn magnitude(digits: &str) -> &str {
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+      "0"
+    } else {
+      digits
+    }
+  }
+
+  let (a_negative, a) = match a.strip_prefix('-') {
+    Some(a) => (magnitude(a) != "0", magnitude(a)),
+    None => (false, magnitude(a)),
+  };
+  let (b_negative, b) = match b.strip_prefix('-') {
+    Some(b) => (magnitude(b) != "0", magnitude(b)),
+    None => (false, magnitude(b)),
+  };
+
+  let ordering = a.len().cmp(&b.len()).then_with(|| a.cmp(b));
+  match (a_negative, b_negative) {
+    (false, false) => ordering,
+    (true, true) => ordering.reverse(),
+    (true, false) => Ordering::Less,
+    (false, true) => Ordering::Greater,
+  }
+}
+
+/// Compare an integer of any size with an integer literal, returning `None`
+/// if either isn't an integer
+fn compare_integer(n: &serde_json::Number, literal: &token::Value) -> Option<std::cmp::Ordering> {
+  let literal = match literal {
+    token::Value::INT(v) => v.to_string(),
+    token::Value::UINT(v) => v.to_string(),
+    _ => return None,
+  };
+
+  if n.is_i64() || n.is_u64() || big_integer_sign(n).is_some() {
+    return Some(compare_integer_literals(&n.to_string(), &literal));
+  }
+
+  None
+}
+
+/// Whether an integer of any size is unsigned and fits in the given number of
+/// bytes, as required by `.size`
+fn fits_size(n: &serde_json::Number, size: usize) -> bool {
+  let n = n.to_string();
+  if n.starts_with('-') {
+    return false;
+  }
+
+  // 256 to the power of size has more digits than n
+  if size.saturating_mul(2) > n.len() {
+    return true;
+  }
+
+  // Little-endian decimal digits of 256 to the power of size
+  let mut limit = vec![1u32];
+  for _ in 0..size {
+    let mut carry = 0;
+    for digit in limit.iter_mut() {
+      let product = *digit * 256 + carry;
+      *digit = product % 10;
+      carry = product / 10;
+    }
+
+    while carry > 0 {
+      limit.push(carry % 10);
+      carry /= 10;
+    }
+  }
+
+  let limit = limit
+    .iter()
+    .rev()
+    .map(|d| char::from(b'0' + *d as u8))
+    .collect::<String>();
+
+  compare_integer_literals(&n, &limit).is_lt()
+}
+
+/// Whether the given number is a UNIX timestamp representable as a date.
+/// Fractional milliseconds are truncated
+fn is_valid_unix_timestamp(n: &serde_json::Number) -> bool {
+  let millis = match (n.as_i64(), n.as_f64()) {
+    (Some(n), _) => n.checked_mul(1000),
+    (None, Some(f)) if f.is_finite() => Some((f * 1000f64) as i64),
+    _ => None,
+  };
+
+  matches!(millis, Some(m) if is_valid_unix_timestamp_millis(m))
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+  #![allow(unused_imports)]
+
+  use super::*;
+  use indoc::indoc;
+
+  /// Assert that validation failed with errors reported at the given JSON
+  /// locations with the given reasons
+  fn assert_validation_errors(result: json::Result, expected: &[(&str, &str)]) {
+    match result {
+      Err(json::Error::Validation(errors)) => assert_eq!(
+        errors
+          .iter()
+          .map(|e| (e.json_location.as_str(), e.reason.as_str()))
+          .collect::<Vec<_>>(),
+        expected
+      ),
+      r => panic!("expected validation errors, got {:?}", r),
+    }
+  }
+
+  #[cfg(feature = "additional-controls")]
+  #[test]
+  fn validate_plus() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        interval<BASE> = (
+          "test" => BASE .plus a
+        )
+    
+        rect = {
+          interval<X>
+        }
+        X = 0
+        a = 10
+      "#
+    );
+    let json = r#"{ "test": 10 }"#;
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
+    if let Err(e) = &cddl {
+      println!("{}", e);
+    }
+
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+
+    let cddl = cddl.unwrap();
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    Ok(())
+  }
+
+  #[cfg(feature = "additional-controls")]
+  #[test]
+  fn validate_feature() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        v = JC<"v", 2>
+        JC<J, C> =  C .feature "cbor" / J .feature "json"
+      "#
+    );
+
+    let json = r#""v""#;
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
+    if let Err(e) = &cddl {
+      println!("{}", e);
+    }
+
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+
+    let cddl = cddl.unwrap();
+
+    let mut jv = JSONValidator::new(&cddl, json, Some(&["json"]));
+    jv.validate()?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_type_choice_alternate() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        tester = [ $vals ]
+        $vals /= 12
+        $vals /= 13
+      "#
+    );
+
+    let json = r#"[ 13 ]"#;
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
+    if let Err(e) = &cddl {
+      println!("{}", e);
+    }
+
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+
+    let cddl = cddl.unwrap();
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_group_choice_alternate() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        tester = $$vals
+        $$vals //= 18
+        $$vals //= 12
+      "#
+    );
+
+    let json = r#"15"#;
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
+    if let Err(e) = &cddl {
+      println!("{}", e);
+    }
+
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+
+    let cddl = cddl.unwrap();
+
+    // `tester` only names the group socket, so there is no type rule to
+    // validate against
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    assert!(matches!(jv.validate(), Err(json::Error::NoRootRule)));
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_group_choice_alternate_in_type_rule(
+  ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        tester = [ $$vals ]
+        $$vals //= 18
+        $$vals //= 12
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    let json =
+      serde_json::from_str::<serde_json::Value>("[12]").map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    let json =
+      serde_json::from_str::<serde_json::Value>("[15]").map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    assert!(jv.validate().is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_group_choice_alternate_in_array_1(
+  ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        tester = [$$val]
+        $$val //= (
+          type: 10,
+          data: uint,
+          t: 11
+        )
+        $$val //= (
+          type: 11,
+          data: tstr
+        )
+      "#
+    );
+
+    let json = r#"[10, 11, 11]"#;
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
+    if let Err(e) = &cddl {
+      println!("{}", e);
+    }
+
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+
+    let cddl = cddl.unwrap();
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_group_choice_alternate_in_array_2(
+  ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        tester = [$$val]
+        $$val //= (
+          type: 10,
+          extra,
+        )
+        extra = (
+          something: uint,
+        )
+      "#
+    );
+
+    let json = r#"[10, 1]"#;
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
+    if let Err(e) = &cddl {
+      println!("{}", e);
+    }
+
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+
+    let cddl = cddl.unwrap();
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn size_control_validation_error() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        start = Record
+        Record = {
+          id: Id
+        }
+        Id = uint .size 8
+      "#
+    );
+
+    let json = r#"{ "id": 5 }"#;
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
+    if let Err(e) = &cddl {
+      println!("{}", e);
+    }
+
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+
+    let cddl = cddl.unwrap();
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_occurrences_in_object() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        limited = { 1* tstr => tstr }
+      "#
+    );
+
+    let json = r#"{ "A": "B" }"#;
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
+    if let Err(e) = &cddl {
+      println!("{}", e);
+    }
+
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+
+    let cddl = cddl.unwrap();
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate().unwrap();
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_optional_occurrences_in_object() -> std::result::Result<(), Box<dyn std::error::Error>>
+  {
+    let cddl = indoc!(
+      r#"
+        argument = {
+          name: text,
+          ? valid: "yes" / "no",
+        }
+      "#
+    );
+
+    let json = r#"{
+      "name": "foo",
+      "valid": "no"
+    }"#;
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
+    if let Err(e) = &cddl {
+      println!("{}", e);
+    }
+
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+
+    let cddl = cddl.unwrap();
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate().unwrap();
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_size_control() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        record = {
+          code: tstr .size 4,
+          name: tstr .size (2..8),
+          port: uint .size 2,
+          id: uint .size (1..2),
+        }
+      "#
+    );
+
+    validate_json_from_str(
+      cddl,
+      r#"{ "code": "abcd", "name": "abc", "port": 65535, "id": 300 }"#,
+      None,
+    )?;
+
+    assert_validation_errors(
+      validate_json_from_str(
+        cddl,
+        r#"{ "code": "abc", "name": "abc", "port": 65535, "id": 300 }"#,
+        None,
+      ),
+      &[(
+        "/code",
+        r#"expected "abc" .size 4 (UTF-8 bytes), got 3 bytes"#,
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(
+        cddl,
+        r#"{ "code": "abcd", "name": "a", "port": 65535, "id": 300 }"#,
+        None,
+      ),
+      &[(
+        "/name",
+        r#"expected "a" string length in UTF-8 bytes to be in the range 2 <= value <= 8, got 1"#,
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(
+        cddl,
+        r#"{ "code": "abcd", "name": "abc", "port": 65536, "id": 300 }"#,
+        None,
+      ),
+      &[("/port", "expected value .size 2, got 65536")],
+    );
+    assert_validation_errors(
+      validate_json_from_str(
+        cddl,
+        r#"{ "code": "abcd", "name": "abc", "port": 65535, "id": 65536 }"#,
+        None,
+      ),
+      &[("/id", "expected value .size 1..2, got 65536")],
+    );
+
+    assert_validation_errors(
+      validate_json_from_str("flag = bool .size 2", "true", None),
+      &[(
+        "",
+        "target for .size must a string or uint data type, got bool",
+      )],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_bits_control() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        permissions = uint .bits flags
+        flags = &(
+          read: 0,
+          write: 1,
+        )
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    for json in ["0", "1", "3"] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      jv.validate()?;
+    }
+
+    for json in ["4", "7", "\"3\""] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      assert!(jv.validate().is_err());
+    }
+
+    let cddl = cddl_from_str("tcpflags = uint .bits (0 / 2 / 4..7)", true)
+      .map_err(json::Error::CDDLParsing)?;
+    let mut jv = JSONValidator::new(&cddl, serde_json::json!(245), None);
+    jv.validate()?;
+    let mut jv = JSONValidator::new(&cddl, serde_json::json!(2), None);
+    assert!(jv.validate().is_err());
+
+    // Wide ranges are matched against their bounds rather than expanded
+    let cddl =
+      cddl_from_str("a = uint .bits (8..4294967295)", true).map_err(json::Error::CDDLParsing)?;
+    let mut jv = JSONValidator::new(&cddl, serde_json::json!(u64::MAX - 255), None);
+    jv.validate()?;
+    let mut jv = JSONValidator::new(&cddl, serde_json::json!(1), None);
+    assert!(jv.validate().is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_regexp_against_member_and_array_values(
+  ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        person = {
+          name: tstr .regexp "[a-z]+",
+          ? aliases: [* tstr .regexp "[a-z]+"],
+        }
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    let json =
+      serde_json::from_str::<serde_json::Value>(r#"{ "name": "abc", "aliases": ["def", "ghi"] }"#)
+        .map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    let json = serde_json::from_str::<serde_json::Value>(r#"{ "name": "AB1" }"#)
+      .map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    let err = jv.validate().unwrap_err().to_string();
+    assert!(err.contains("/name"), "{}", err);
+
+    let json =
+      serde_json::from_str::<serde_json::Value>(r#"{ "name": "abc", "aliases": ["def", "AB1"] }"#)
+        .map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    let err = jv.validate().unwrap_err().to_string();
+    assert!(err.contains("/aliases/1"), "{}", err);
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_ne_control() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl =
+      cddl_from_str(r#"user = tstr .ne "admin""#, true).map_err(json::Error::CDDLParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, serde_json::json!("bob"), None);
+    jv.validate()?;
+
+    let mut jv = JSONValidator::new(&cddl, serde_json::json!("admin"), None);
+    assert_eq!(
+      jv.validate().unwrap_err().to_string(),
+      "error validating at the root of the JSON document: expected value .ne \"admin\", got \"admin\"\n"
+    );
+
+    let cddl = cddl_from_str("count = int .ne 0", true).map_err(json::Error::CDDLParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, serde_json::json!(1), None);
+    jv.validate()?;
+
+    let mut jv = JSONValidator::new(&cddl, serde_json::json!(0), None);
+    assert_eq!(
+      jv.validate().unwrap_err().to_string(),
+      "error validating at the root of the JSON document: expected value .ne 0, got 0\n"
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_eq_control_with_float_controller(
+  ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "a = float .eq 1.0";
+
+    // Integer JSON numbers are compared numerically with float controllers
+    validate_json_from_str(cddl, "1", None)?;
+    validate_json_from_str(cddl, "1.0", None)?;
+
+    let cddl = "a = float .eq 1.5";
+
+    validate_json_from_str(cddl, "1.5", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "1", None),
+      &[("", "expected value 1.5, got 1")],
+    );
+
+    assert_validation_errors(
+      validate_json_from_str("a = float .ne 1.0", "1", None),
+      &[("", "expected value .ne 1, got 1")],
+    );
+
+    validate_json_from_str("a = float .ne 1.5", "1", None)?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_default_control() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        config = {
+          ? timeout: uint .default 30,
+          ? mode: "fast" .default "fast",
+        }
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    for json in [
+      r#"{}"#,
+      r#"{ "timeout": 5 }"#,
+      r#"{ "mode": "fast" }"#,
+      r#"{ "timeout": 5, "mode": "fast" }"#,
+    ] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      jv.validate()?;
+    }
+
+    for json in [r#"{ "timeout": "x" }"#, r#"{ "mode": "slow" }"#] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      assert!(jv.validate().is_err());
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_float_precision() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        floats = {
+          ? half: float16,
+          ? single: float32,
+          ? half-single: float16-32,
+          ? single-double: float32-64,
+          ? double: float64,
+        }
+      "#
+    );
+
+    validate_json_from_str(
+      cddl,
+      r#"{ "half": 1.5, "single": 0.5, "half-single": 3.25, "single-double": 0.1, "double": 1e300 }"#,
+      None,
+    )?;
+    validate_json_from_str(cddl, r#"{ "half": 0.1, "half-single": 1e30 }"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "half": 1e300 }"#, None),
+      &[(
+        "/half",
+        "expected type float16, got 1e+300 which is out of range for float16",
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "half": 70000.0 }"#, None),
+      &[(
+        "/half",
+        "expected type float16, got 70000.0 which is out of range for float16",
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "single": 1e300 }"#, None),
+      &[(
+        "/single",
+        "expected type float32, got 1e+300 which is out of range for float32",
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "half-single": 1e300 }"#, None),
+      &[(
+        "/half-single",
+        "expected type float16-32, got 1e+300 which is out of range for float16-32",
+      )],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_nested_json_location() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        root = { myarray: [* item] }
+        item = { myotherkey: tstr }
+      "#
+    );
+
+    let json = r#"{ "myarray": [ { "myotherkey": "a" }, { "myotherkey": 1 } ] }"#;
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    match jv.validate() {
+      Err(json::Error::Validation(errors)) => {
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].json_location, "/myarray/1/myotherkey");
+        assert!(errors[0]
+          .to_string()
+          .starts_with("error validating at JSON location /myarray/1/myotherkey"));
+      }
+      r => panic!("expected validation error, got {:?}", r),
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_generic_args() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        messages = {
+          greeting: message<tstr>,
+          count: message<int>,
+          ? batch: [* message<int>],
+        }
+        message<t> = { body: t }
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    let json =
+      r#"{ "greeting": { "body": "x" }, "count": { "body": 1 }, "batch": [ { "body": 2 } ] }"#;
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    let json = r#"{ "greeting": { "body": "x" }, "count": { "body": "y" } }"#;
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    match jv.validate() {
+      Err(json::Error::Validation(errors)) => {
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].json_location, "/count/body");
+      }
+      r => panic!("expected validation error, got {:?}", r),
+    }
+
+    let json =
+      r#"{ "greeting": { "body": "x" }, "count": { "body": 1 }, "batch": [ { "body": "z" } ] }"#;
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    assert!(jv.validate().is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_inner_group_to_choice_enum() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        status = &( ok: 1, nested: &codes<4> // retry: 3 )
+        codes<t> = ( pending: t )
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    for json in ["1", "3", "4"] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      jv.validate()?;
+    }
+
+    let json = serde_json::from_str::<serde_json::Value>("5").map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    match jv.validate() {
+      Err(json::Error::Validation(errors)) => {
+        assert!(errors.iter().all(|e| e.is_group_to_choice_enum));
+      }
+      r => panic!("expected validation error, got {:?}", r),
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_dedup_errors() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        root = a / b
+        a = { x: int }
+        b = { x: int, ? y: tstr }
+      "#
+    );
+
+    let json = r#"{ "x": "s" }"#;
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    match jv.validate() {
+      Err(json::Error::Validation(errors)) => {
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].json_location, "/x");
+      }
+      r => panic!("expected validation error, got {:?}", r),
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_unwrap() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        outer = { a: int, ~inner }
+        inner = { b: tstr }
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    let json = r#"{ "a": 1, "b": "x" }"#;
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    for json in [r#"{ "a": 1, "b": 2 }"#, r#"{ "a": 1 }"#] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      assert!(jv.validate().is_err());
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_tagged_data() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        link = { href: #6.32(tstr) }
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    let json = r#"{ "href": "http://x" }"#;
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    let json = r#"{ "href": 1 }"#;
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    assert!(jv.validate().is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_and_control() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "value = uint .and (-10..100)";
+
+    validate_json_from_str(cddl, "5", None)?;
+
+    // satisfies only one of the two types, or neither
+    assert_validation_errors(
+      validate_json_from_str(cddl, "500", None),
+      &[(
+        "",
+        "expected integer to be in range -10 <= value <= 100, got 500",
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, "-5", None),
+      &[("", "expected type uint, got -5")],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, "-50", None),
+      &[
+        ("", "expected type uint, got -50"),
+        (
+          "",
+          "expected integer to be in range -10 <= value <= 100, got -50",
+        ),
+      ],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_within_control() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        value = (1..10) .within uint
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    let json = serde_json::from_str::<serde_json::Value>("5").map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    let json = serde_json::from_str::<serde_json::Value>("-1").map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    assert!(jv.validate().is_err());
+
+    // the target accepts the value but the controller does not
+    let cddl =
+      cddl_from_str("value = (-5..10) .within uint", true).map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>("-1").map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    match jv.validate() {
+      Err(e) => assert_eq!(
+        e.to_string(),
+        "error validating at the root of the JSON document: expected type (-5..10) .within type uint, got -1\n"
+      ),
+      Ok(()) => panic!("expected .within to reject -1"),
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_byte_string() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        payload = { data: bstr, ? raw: bytes }
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    let json = r#"{ "data": "aGVsbG8=", "raw": "" }"#;
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    for json in [r#"{ "data": "not base64!" }"#, r#"{ "data": 1 }"#] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      assert!(jv.validate().is_err());
+    }
+
+    Ok(())
+  }
+
+  #[cfg(feature = "additional-controls")]
+  #[test]
+  fn validate_cat_and_det_controls() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    for cddl in [
+      r#"value = "foo" .cat "bar""#,
+      r#"value = ("foo" / "baz") .cat "bar""#,
+      r#"value = "  foo" .det "bar""#,
+    ] {
+      let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+      let json = serde_json::from_str::<serde_json::Value>(r#""foobar""#)
+        .map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      jv.validate()?;
+
+      let json =
+        serde_json::from_str::<serde_json::Value>(r#""foo""#).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      assert!(jv.validate().is_err());
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_array_element_index() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("values = [3*int]", true).map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>(r#"[1, "x", 3]"#)
+      .map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    match jv.validate() {
+      Err(json::Error::Validation(errors)) => {
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].json_location, "/1");
+      }
+      r => panic!("expected validation error, got {:?}", r),
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_array_of_user_defined_items() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        reputons = [+ reputon]
+        reputon = { rater: tstr, rating: float }
+      "#
+    );
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    let json = serde_json::from_str::<serde_json::Value>(
+      r#"[{ "rater": "a", "rating": 0.5 }, { "rater": "b", "rating": "high" }]"#,
+    )
+    .map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    match jv.validate() {
+      Err(json::Error::Validation(errors)) => {
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].json_location, "/1/rating");
+      }
+      r => panic!("expected validation error, got {:?}", r),
+    }
+
+    let json = serde_json::from_str::<serde_json::Value>("[]").map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    match jv.validate() {
+      Err(json::Error::Validation(errors)) => {
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].reason, "array must have at least one item");
+      }
+      r => panic!("expected validation error, got {:?}", r),
+    }
+
+    let cddl = cddl_from_str("values = [+ value]\nvalue = (uint)", true)
+      .map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>(r#"[1, "x", 3]"#)
+      .map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    match jv.validate() {
+      Err(json::Error::Validation(errors)) => {
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].json_location, "/1");
+      }
+      r => panic!("expected validation error, got {:?}", r),
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_array_positional_occurrences() -> std::result::Result<(), Box<dyn std::error::Error>>
+  {
+    let cddl = cddl_from_str("record = [int, *tstr]", true).map_err(json::Error::CDDLParsing)?;
+
+    for json in [r#"[1, "a", "b"]"#, "[1]"] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      jv.validate()?;
+    }
+
+    let json =
+      serde_json::from_str::<serde_json::Value>("[1, 2]").map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    match jv.validate() {
+      Err(json::Error::Validation(errors)) => {
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].json_location, "/1");
+      }
+      r => panic!("expected validation error, got {:?}", r),
+    }
+
+    let cddl =
+      cddl_from_str("record = [* tstr, int, ? bool]", true).map_err(json::Error::CDDLParsing)?;
+
+    let json = serde_json::from_str::<serde_json::Value>(r#"["a", "b", 1]"#)
+      .map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    for json in [r#"["a", "b"]"#, r#"[1, true, false]"#] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      assert!(jv.validate().is_err());
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_exact_occurrence_scoped_to_entry(
+  ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("a = [ 2*3 int, tstr ]", true).map_err(json::Error::CDDLParsing)?;
+
+    for json in [r#"[1, 2, "x"]"#, r#"[1, 2, 3, "x"]"#] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      jv.validate()?;
+    }
+
+    for json in [r#"[1, "x"]"#, r#"[1, 2, 3, 4, "x"]"#, "[1, 2]"] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      assert!(jv.validate().is_err());
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_nested_array_items() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("a = [[int, int], tstr]", true).map_err(json::Error::CDDLParsing)?;
+
+    let json = serde_json::from_str::<serde_json::Value>(r#"[[1, 2], "x"]"#)
+      .map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    let json = serde_json::from_str::<serde_json::Value>(r#"[[1], "x"]"#)
+      .map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    match jv.validate() {
+      Err(json::Error::Validation(errors)) => {
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].json_location, "/0");
+      }
+      r => panic!("expected validation error, got {:?}", r),
+    }
+
+    let cddl = cddl_from_str("a = [* [int, tstr]]", true).map_err(json::Error::CDDLParsing)?;
+
+    let json = serde_json::from_str::<serde_json::Value>(r#"[[1, "a"], [2, "b"]]"#)
+      .map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_array_of_maps() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "a = [* { a: int }]";
+
+    validate_json_from_str(cddl, r#"[{ "a": 1 }, { "a": 2 }]"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"[{ "a": 1 }, { "a": "x" }]"#, None),
+      &[("/1/a", r#"expected type int, got "x""#)],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"[{ "a": 1, "b": 2 }]"#, None),
+      &[("/0", r#"unexpected key "b""#)],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"[{ "a": 1 }, []]"#, None),
+      &[("/1", "expected map object { a: int }, got []")],
+    );
+
+    let cddl = indoc!(
+      r#"
+        a = [* { a: int, * ext }]
+        ext = ( text => any )
+      "#
+    );
+
+    validate_json_from_str(cddl, r#"[{ "a": 1, "b": 2 }]"#, None)?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_with_options() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        person = { name: tstr, address: { city: tstr } }
+      "#
+    );
+
+    let json = r#"{ "name": "x", "age": 1, "address": { "city": "y", "zip": "z" } }"#;
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json.clone(), None);
+    match jv.validate_with_options(ValidationOptions::default()) {
+      Err(json::Error::Validation(errors)) => {
+        let reasons = errors.iter().map(|e| e.reason.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+          reasons,
+          ["unexpected key \"zip\"", "unexpected key \"age\""]
+        );
+      }
+      r => panic!("expected validation error, got {:?}", r),
+    }
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate_with_options(ValidationOptions {
+      allow_extra_keys: true,
+      ..Default::default()
+    })?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_cyclic_rule_reference() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        a = int / b
+        b = tstr / a
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    let json = serde_json::from_str::<serde_json::Value>("1").map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    let json =
+      serde_json::from_str::<serde_json::Value>("true").map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    match jv.validate() {
+      Err(json::Error::Validation(errors)) => {
+        assert!(errors
+          .iter()
+          .any(|e| e.reason == "cyclic rule reference: a"));
+      }
+      r => panic!("expected validation error, got {:?}", r),
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_mixed_numeric_range() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("ratio = 0 .. 1.5", true).map_err(json::Error::CDDLParsing)?;
+
+    for json in ["1.0", "0", "1.5"] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      jv.validate()?;
+    }
+
+    for json in ["2.0", "-0.5", r#""1""#] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      assert!(jv.validate().is_err());
+    }
+
+    let cddl = cddl_from_str("ratio = 0.5 ... 3", true).map_err(json::Error::CDDLParsing)?;
+
+    let json =
+      serde_json::from_str::<serde_json::Value>("0.5").map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    let json = serde_json::from_str::<serde_json::Value>("3").map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    assert!(jv.validate().is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_no_root_rule() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("entries = (a: int)", true).map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>(r#"{ "a": 1 }"#)
+      .map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    assert!(matches!(jv.validate(), Err(json::Error::NoRootRule)));
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_tdate_epoch() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "event = { at: tdate }";
+
+    validate_json_from_str(cddl, r#"{ "at": "2021-01-01T00:00:00Z" }"#, None)?;
+    validate_json_from_str(cddl, r#"{ "at": 1609459200 }"#, None)?;
+    validate_json_from_str(cddl, r#"{ "at": 1609459200.5 }"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "at": "1609459200" }"#, None),
+      &[(
+        "/at",
+        "expected tdate data type, decoding error: premature end of input",
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "at": 1e300 }"#, None),
+      &[(
+        "/at",
+        "expected tdate data type, invalid UNIX timestamp 1e+300",
+      )],
+    );
+
+    Ok(())
+  }
+
+  #[cfg(feature = "additional-controls")]
+  #[test]
+  fn validate_abnf_control() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        version = tstr .abnf ("version" .det rules)
+        rules = '
+          version = 1*DIGIT "." 1*DIGIT
+        '
+      "#
+    );
+
+    validate_json_from_str(cddl, r#""1.0""#, None)?;
+    validate_json_from_str(cddl, r#""12.34""#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""1.""#, None),
+      &[(
+        "",
+        "\"1.\" is not valid against abnf:  --> 1:3\n  |\n1 | 1.\n  |   ^---\n  |\n  = expected \"DIGIT\"",
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""1.0-beta""#, None),
+      &[(
+        "",
+        r#""1.0-beta" is not valid against abnf: unexpected input at position 3, expected end of input"#,
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""v1.0""#, None),
+      &[(
+        "",
+        "\"v1.0\" is not valid against abnf:  --> 1:1\n  |\n1 | v1.0\n  | ^---\n  |\n  = expected \"DIGIT\"",
+      )],
+    );
+
+    let cddl = r#"a = tstr .abnf "b = 1*DIGIT""#;
+
+    validate_json_from_str(cddl, r#""12""#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""12a""#, None),
+      &[(
+        "",
+        r#""12a" is not valid against abnf: unexpected input at position 2, expected end of input"#,
+      )],
+    );
+
+    Ok(())
+  }
+
+  #[cfg(feature = "extensions")]
+  #[test]
+  fn validate_join_control() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        config = { path: ["usr", dir, "bin"] .join "/" }
+        dir = "local" / "opt"
+      "#
+    );
+
+    validate_json_from_str(cddl, r#"{ "path": "usr/local/bin" }"#, None)?;
+    validate_json_from_str(cddl, r#"{ "path": "usr/opt/bin" }"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "path": "usr-local-bin" }"#, None),
+      &[(
+        "/path",
+        r#"expected value to match [ "usr", dir, "bin" ] .join "/", got "usr-local-bin""#,
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "path": "usr/bin" }"#, None),
+      &[(
+        "/path",
+        r#"expected value to match [ "usr", dir, "bin" ] .join "/", got "usr/bin""#,
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "path": ["usr", "local", "bin"] }"#, None),
+      &[(
+        "/path",
+        r#".join control can only be matched against a JSON string, got ["usr","local","bin"]"#,
+      )],
+    );
+
+    Ok(())
+  }
+
+  #[cfg(feature = "extensions")]
+  #[test]
+  fn validate_join_control_with_cyclic_target(
+  ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        a = b .join "/"
+        b = ["x"] / c
+        c = ["y"] / b
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+
+    for json in [r#""x""#, r#""y""#] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      jv.validate()?;
+    }
+
+    let json =
+      serde_json::from_str::<serde_json::Value>(r#""z""#).map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    assert!(jv.validate().is_err());
+
+    Ok(())
+  }
+
+  #[cfg(feature = "additional-controls")]
+  #[test]
+  fn validate_feature_enabled_set() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        config = {
+          name: tstr,
+          ? extra: tstr .feature "experimental",
+        }
+      "#
+    );
+
+    let json = r#"{ "name": "a", "extra": "b" }"#;
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json.clone(), Some(&["experimental"]));
+    jv.validate()?;
+
+    let mut jv = JSONValidator::new(&cddl, json.clone(), Some(&[]));
+    assert!(jv.validate().is_err());
+
+    let json = serde_json::from_str::<serde_json::Value>(r#"{ "name": "a" }"#)
+      .map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, Some(&[]));
+    jv.validate()?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_named_range_bounds() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        r = lim .. max
+        lim = 4 / 2 / other
+        other = 3
+        max = 10
+      "#
+    );
+
+    validate_json_from_str(cddl, "2", None)?;
+    validate_json_from_str(cddl, "3", None)?;
+    validate_json_from_str(cddl, "10", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "1", None),
+      &[
+        ("", "expected uint to be in range 4 <= value <= 10, got 1"),
+        ("", "expected uint to be in range 2 <= value <= 10, got 1"),
+        ("", "expected uint to be in range 3 <= value <= 10, got 1"),
+      ],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, "11", None),
+      &[
+        ("", "expected uint to be in range 4 <= value <= 10, got 11"),
+        ("", "expected uint to be in range 2 <= value <= 10, got 11"),
+        ("", "expected uint to be in range 3 <= value <= 10, got 11"),
+      ],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_cyclic_named_range_bounds() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        r = a .. 10
+        a = 1 / b
+        b = 2 / a
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>("5").map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    let json = serde_json::from_str::<serde_json::Value>("0").map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    assert!(jv.validate().is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_negative_named_range_bounds() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "r = -10 .. -1";
+
+    validate_json_from_str(cddl, "-5", None)?;
+    validate_json_from_str(cddl, "-10", None)?;
+    validate_json_from_str(cddl, "-1", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "0", None),
+      &[(
+        "",
+        "expected integer to be in range -10 <= value <= -1, got 0",
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, "-11", None),
+      &[(
+        "",
+        "expected integer to be in range -10 <= value <= -1, got -11",
+      )],
+    );
+
+    let cddl = indoc!(
+      r#"
+        r = lo .. hi
+        lo = -10
+        hi = -1
+      "#
+    );
+
+    validate_json_from_str(cddl, "-5", None)?;
+    validate_json_from_str(cddl, "-10", None)?;
+    validate_json_from_str(cddl, "-1", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "0", None),
+      &[(
+        "",
+        "expected integer to be in range -10 <= value <= -1, got 0",
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, "-11", None),
+      &[(
+        "",
+        "expected integer to be in range -10 <= value <= -1, got -11",
+      )],
+    );
+
+    let cddl = indoc!(
+      r#"
+        r = lo .. hi
+        lo = min
+        min = -10
+        hi = -1
+      "#
+    );
+
+    validate_json_from_str(cddl, "-5", None)?;
+    validate_json_from_str(cddl, "-10", None)?;
+    validate_json_from_str(cddl, "-1", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "0", None),
+      &[(
+        "",
+        "expected integer to be in range -10 <= value <= -1, got 0",
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, "-11", None),
+      &[(
+        "",
+        "expected integer to be in range -10 <= value <= -1, got -11",
+      )],
+    );
+
+    let cddl = indoc!(
+      r#"
+        r = nint .and (lo .. hi)
+        lo = -10
+        hi = -1
+      "#
+    );
+
+    validate_json_from_str(cddl, "-5", None)?;
+    validate_json_from_str(cddl, "-10", None)?;
+    validate_json_from_str(cddl, "-1", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "0", None),
+      &[
+        ("", "expected type nint, got 0"),
+        (
+          "",
+          "expected integer to be in range -10 <= value <= -1, got 0",
+        ),
+      ],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, "-11", None),
+      &[(
+        "",
+        "expected integer to be in range -10 <= value <= -1, got -11",
+      )],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_integer_object_keys() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "m = { * uint => tstr }";
+
+    validate_json_from_str(cddl, r#"{ "1": "a", "2": "b" }"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "x": "a" }"#, None),
+      &[("", r#"expected object key of type uint, got "x""#)],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "-1": "a" }"#, None),
+      &[("", r#"expected object key of type uint, got "-1""#)],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "1": 2 }"#, None),
+      &[("", "expected type tstr, got 2")],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_array_upper_bound_occurrence() -> std::result::Result<(), Box<dyn std::error::Error>>
+  {
+    let cddl = "a = [ *3 int ]";
+
+    validate_json_from_str(cddl, "[]", None)?;
+    validate_json_from_str(cddl, "[1, 2]", None)?;
+    validate_json_from_str(cddl, "[1, 2, 3]", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "[1, 2, 3, 4]", None),
+      &[("", "array must have not more than 3 items")],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_nint_boundaries() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "n = nint";
+
+    validate_json_from_str(cddl, "-5", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "-0.5", None),
+      &[("", "expected type nint, got -0.5 which is not an integer")],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, "-1e30", None),
+      &[(
+        "",
+        "expected type nint, got -1e+30 which is out of the supported integer range",
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, "-5.0", None),
+      &[("", "expected type nint, got -5.0")],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_shape_in_error_message() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("person = { name: tstr, age: uint }", true)
+      .map_err(json::Error::CDDLParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, Value::from(1), None);
+    match jv.validate() {
+      Err(json::Error::Validation(errors)) => {
+        assert_eq!(
+          errors[0].reason,
+          "expected map object { name: tstr, age: uint }, got 1"
+        );
+      }
+      r => panic!("expected validation error, got {:?}", r),
+    }
+
+    let cddl = cddl_from_str("pair = [ int, tstr ]", true).map_err(json::Error::CDDLParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, Value::from(1), None);
+    match jv.validate() {
+      Err(json::Error::Validation(errors)) => {
+        assert_eq!(errors[0].reason, "expected array type [ int, tstr ], got 1");
+      }
+      r => panic!("expected validation error, got {:?}", r),
+    }
+
+    Ok(())
+  }
+
+  #[cfg(feature = "additional-controls")]
+  #[test]
+  fn validate_plus_literals() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "a = 3 .plus 1";
+
+    validate_json_from_str(cddl, "4", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "3", None),
+      &[("", "expected computed .plus value 4, got 3")],
+    );
+
+    validate_json_from_str("a = 3 .plus -5", "-2", None)?;
+
+    let cddl = "a = 1.5 .plus 1";
+
+    validate_json_from_str(cddl, "2.5", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "2", None),
+      &[("", "expected computed .plus value 2.5, got 2")],
+    );
+
+    let cddl = "a = 3 .plus 1.5";
+
+    validate_json_from_str(cddl, "4.5", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "4", None),
+      &[("", "expected computed .plus value 4.5, got 4")],
+    );
+
+    let cddl = "a = -3 .plus 1.5";
+
+    validate_json_from_str(cddl, "-1.5", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "-1", None),
+      &[("", "expected computed .plus value -1.5, got -1")],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_leaf_errors() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("person = { name: tstr, age: uint }", true)
+      .map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>(r#"{ "name": 1, "age": "a" }"#)
+      .map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    let error = jv.validate().unwrap_err();
+
+    assert_eq!(error.leaf_errors().len(), 2);
+    assert_eq!(
+      error
+        .into_iter()
+        .map(|e| e.json_location)
+        .collect::<Vec<_>>(),
+      ["/name", "/age"]
+    );
+
+    assert!(json::Error::NoRootRule.leaf_errors().is_empty());
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_group_reference_in_map() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        person = { name: tstr, address }
+        address = ( street: tstr )
+      "#
+    );
+
+    validate_json_from_str(cddl, r#"{ "name": "x", "street": "y" }"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "name": "x" }"#, None),
+      &[("", r#"required key "street" missing"#)],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "name": "x", "street": 1 }"#, None),
+      &[("/street", "expected type tstr, got 1")],
+    );
+
+    let cddl = indoc!(
+      r#"
+        person = { name: tstr, ? address }
+        address = ( street: tstr, city: tstr )
+      "#
+    );
+
+    validate_json_from_str(cddl, r#"{ "name": "x" }"#, None)?;
+    validate_json_from_str(cddl, r#"{ "name": "x", "street": "a", "city": "b" }"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "name": "x", "street": "a" }"#, None),
+      &[("", r#"required key "city" missing"#)],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "name": "x", "street": 1, "city": "b" }"#, None),
+      &[("/street", "expected type tstr, got 1")],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_optional_cut_member_with_wildcard(
+  ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = r#"a = { ? "optional-key" ^ => int, * tstr => any }"#;
+
+    validate_json_from_str(cddl, r#"{ "optional-key": 1 }"#, None)?;
+    validate_json_from_str(cddl, r#"{ "other-key": "x" }"#, None)?;
+    validate_json_from_str(cddl, "{}", None)?;
+
+    // The cut prevents the wildcard from accepting the mismatched member
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "optional-key": "notanint" }"#, None),
+      &[("/optional-key", r#"expected type int, got "notanint""#)],
+    );
+    assert_validation_errors(
+      validate_json_from_str(
+        cddl,
+        r#"{ "optional-key": "notanint", "other-key": "x" }"#,
+        None,
+      ),
+      &[("/optional-key", r#"expected type int, got "notanint""#)],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_missing_and_mismatched_members() -> std::result::Result<(), Box<dyn std::error::Error>>
+  {
+    let cddl = "person = { name: tstr, age: uint }";
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "name": "x" }"#, None),
+      &[("", r#"required key "age" missing"#)],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "name": "x", "age": "old" }"#, None),
+      &[("/age", r#"expected type uint, got "old""#)],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_group_reference_as_map_content() -> std::result::Result<(), Box<dyn std::error::Error>>
+  {
+    let cddl = indoc!(
+      r#"
+        config = { settings }
+        settings = ( host: tstr, port: uint )
+      "#
+    );
+
+    validate_json_from_str(cddl, r#"{ "host": "x", "port": 8080 }"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "host": "x" }"#, None),
+      &[("", r#"required key "port" missing"#)],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "port": 8080 }"#, None),
+      &[("", r#"required key "host" missing"#)],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "host": "x", "port": "y" }"#, None),
+      &[("/port", r#"expected type uint, got "y""#)],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "host": "x", "port": 8080, "tls": true }"#, None),
+      &[("", r#"unexpected key "tls""#)],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_nested_group_to_choice_enum() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        color = &palette
+        palette = ( basic, ( cyan: 2, magenta: 3 ), extra )
+        basic = ( red: 0, green: 1 )
+        extra = ( more )
+        more = ( black: 4 )
+      "#
+    );
+
+    validate_json_from_str(cddl, "0", None)?;
+    validate_json_from_str(cddl, "3", None)?;
+    validate_json_from_str(cddl, "4", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "5", None),
+      &[
+        ("", "expected value 0, got 5"),
+        ("", "expected value 1, got 5"),
+        ("", "expected value 2, got 5"),
+        ("", "expected value 3, got 5"),
+        ("", "expected value 4, got 5"),
+      ],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_max_depth() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl =
+      cddl_from_str("nested = [* nested] / int", true).map_err(json::Error::CDDLParsing)?;
+    let options = ValidationOptions {
+      max_depth: Some(10),
+      ..Default::default()
+    };
+
+    let json = format!("{}1{}", "[".repeat(50), "]".repeat(50));
+    let json =
+      serde_json::from_str::<serde_json::Value>(&json).map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    assert!(matches!(
+      jv.validate_with_options(options),
+      Err(json::Error::MaxDepthExceeded(10))
+    ));
+
+    let json = serde_json::from_str::<serde_json::Value>(r#"{ "a": [[1]] }"#)
+      .map_err(json::Error::JSONParsing)?;
+    assert_eq!(json_depth(&json), 3);
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_optional_member_null() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("person = { name: tstr, ? nick: tstr }", true)
+      .map_err(json::Error::CDDLParsing)?;
+
+    let json = serde_json::from_str::<serde_json::Value>(r#"{ "name": "x" }"#)
+      .map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    jv.validate()?;
+
+    let json = serde_json::from_str::<serde_json::Value>(r#"{ "name": "x", "nick": null }"#)
+      .map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    match jv.validate() {
+      Err(json::Error::Validation(errors)) => {
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].reason, "member present but null; expected tstr");
+        assert_eq!(errors[0].json_location, "/nick");
+      }
+      r => panic!("expected validation error, got {:?}", r),
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_error_source() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    use std::error::Error as _;
+
+    let cddl =
+      cddl_from_str(r#"a = tstr .regexp "[a-""#, true).map_err(json::Error::CDDLParsing)?;
+    let json =
+      serde_json::from_str::<serde_json::Value>(r#""abc""#).map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    let error = jv.validate().unwrap_err();
+    assert!(error.leaf_errors()[0].source().is_some());
+
+    let cddl = cddl_from_str("a = tdate", true).map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>(r#""not a date""#)
+      .map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    let error = jv.validate().unwrap_err();
+    assert!(error.leaf_errors()[0].source().is_some());
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_text_range() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = r#"a = "a" .. "z""#;
+
+    validate_json_from_str(cddl, r#""m""#, None)?;
+    validate_json_from_str(cddl, r#""z""#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""{""#, None),
+      &[(
+        "",
+        r#"expected text to be in range "a" <= value <= "z", got "{""#,
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, "1", None),
+      &[(
+        "",
+        r#"expected text to be in range "a" <= value <= "z", got 1"#,
+      )],
+    );
+
+    let cddl = r#"a = "a" ... "z""#;
+
+    validate_json_from_str(cddl, r#""a""#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""z""#, None),
+      &[(
+        "",
+        r#"expected text to be in range "a" <= value < "z", got "z""#,
+      )],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_generic_arity() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        a = pair<tstr, int>
+        pair<t, u> = [t, u]
+      "#
+    );
+
+    validate_json_from_str(cddl, r#"["x", 1]"#, None)?;
+
+    let cddl = indoc!(
+      r#"
+        a = pair<tstr>
+        pair<t, u> = [t, u]
+      "#
+    );
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"["x", 1]"#, None),
+      &[("", "rule pair expects 2 generic argument(s), got 1")],
+    );
+
+    let cddl = indoc!(
+      r#"
+        a = pair<tstr, int, int>
+        pair<t, u> = [t, u]
+      "#
+    );
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"["x", 1]"#, None),
+      &[("", "rule pair expects 2 generic argument(s), got 3")],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_exclusive_range_bounds() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "a = 3 ... 5";
+
+    validate_json_from_str(cddl, "3", None)?;
+    validate_json_from_str(cddl, "4", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "2", None),
+      &[("", "expected uint to be in range 3 <= value < 5, got 2")],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, "5", None),
+      &[("", "expected uint to be in range 3 <= value < 5, got 5")],
+    );
+
+    let cddl = "a = -5 ... -3";
+
+    validate_json_from_str(cddl, "-5", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "-3", None),
+      &[(
+        "",
+        "expected integer to be in range -5 <= value < -3, got -3",
+      )],
+    );
+
+    let cddl = "a = -1 ... 2";
+
+    validate_json_from_str(cddl, "-1", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "2", None),
+      &[("", "expected integer to be in range -1 <= value < 2, got 2")],
+    );
+
+    let cddl = "a = 3.0 ... 5.0";
+
+    validate_json_from_str(cddl, "3.0", None)?;
+    validate_json_from_str(cddl, "4.5", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "5.0", None),
+      &[("", "expected float to be in range 3 <= value < 5, got 5.0")],
+    );
+
+    let cddl = "a = 3 ... 5.0";
+
+    validate_json_from_str(cddl, "3", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "5", None),
+      &[("", "expected number to be in range 3 <= value < 5.0, got 5")],
+    );
+
+    validate_json_from_str("a = 3 .. 5", "5", None)?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_socket_alternates() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        a = $ext
+        $ext /= tstr
+        $ext /= int
+      "#
+    );
+
+    validate_json_from_str(cddl, r#""x""#, None)?;
+    validate_json_from_str(cddl, "1", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "true", None),
+      &[
+        ("", "expected type tstr, got true"),
+        ("", "expected type int, got true"),
+      ],
+    );
+
+    let cddl = indoc!(
+      r#"
+        a = [* $ext]
+        $ext /= tstr
+        $ext /= int
+      "#
+    );
+
+    validate_json_from_str(cddl, r#"["x", 1]"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"["x", true]"#, None),
+      &[
+        ("/1", "expected type tstr, got true"),
+        ("/1", "expected type int, got true"),
+      ],
+    );
+
+    let cddl = indoc!(
+      r#"
+        a = { k: $ext }
+        $ext /= tstr
+        $ext /= int
+      "#
+    );
+
+    validate_json_from_str(cddl, r#"{ "k": 1 }"#, None)?;
+
+    let cddl = indoc!(
+      r#"
+        a = ext
+        ext = tstr
+        ext /= int
+        $ext /= tstr
+        $ext /= int
+      "#
+    );
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "true", None),
+      &[
+        ("", "expected type tstr, got true"),
+        ("", "expected type int, got true"),
+      ],
+    );
+
+    let cddl = indoc!(
+      r#"
+        a = [* ext]
+        ext = tstr
+        ext /= int
+        $ext /= tstr
+        $ext /= int
+      "#
+    );
+
+    validate_json_from_str(cddl, r#"["x", 1]"#, None)?;
+
+    let cddl = indoc!(
+      r#"
+        a = { $$plug }
+        $$plug //= ( x: int )
+        $$plug //= ( y: tstr )
+      "#
+    );
+
+    validate_json_from_str(cddl, r#"{ "x": 1 }"#, None)?;
+    validate_json_from_str(cddl, r#"{ "y": "b" }"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "z": 1 }"#, None),
+      &[
+        ("", r#"required key "x" missing"#),
+        ("", r#"required key "y" missing"#),
+      ],
+    );
+
+    let cddl = indoc!(
+      r#"
+        a = { name: tstr, * $$plug }
+        $$plug //= ( x: int )
+        $$plug //= ( y: tstr )
+      "#
+    );
+
+    validate_json_from_str(cddl, r#"{ "name": "a" }"#, None)?;
+    validate_json_from_str(cddl, r#"{ "name": "a", "y": "b" }"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "name": "a", "x": "s" }"#, None),
+      &[
+        ("/x", r#"expected type int, got "s""#),
+        ("", r#"required key "y" missing"#),
+      ],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_regex_lookaround() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    for cddl in [
+      r#"a = tstr .regexp "foo(?=bar)""#,
+      r#"a = tstr .pcre "(?<!x)foo""#,
+    ] {
+      let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+      let json = serde_json::from_str::<serde_json::Value>(r#""foobar""#)
+        .map_err(json::Error::JSONParsing)?;
+
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      match jv.validate() {
+        Err(json::Error::Syntax(error)) => {
+          assert_eq!(error.kind, SyntaxErrorKind::RegexLookaround);
+          assert_eq!(error.detail, "regex uses unsupported lookaround");
+        }
+        result => panic!("expected lookaround syntax error, got {:?}", result),
+      }
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_invalid_range_bound() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str(r#"a = "a" .. 10"#, true).map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>("5").map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    match jv.validate() {
+      Err(json::Error::Syntax(error)) => {
+        assert_eq!(error.kind, SyntaxErrorKind::InvalidRangeBound);
+      }
+      result => panic!(
+        "expected invalid range bound syntax error, got {:?}",
+        result
+      ),
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_control_operator_type_mismatch() -> std::result::Result<(), Box<dyn std::error::Error>>
+  {
+    let cddl = cddl_from_str(r#"a = int .lt "x""#, true).map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>("5").map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    match jv.validate() {
+      Err(json::Error::Syntax(error)) => {
+        assert_eq!(error.kind, SyntaxErrorKind::ControlOperatorTypeMismatch);
+        assert_eq!(
+          error.detail,
+          "controller for .lt operator must be a numerical value, got \"x\""
+        );
+      }
+      result => panic!("expected control operator syntax error, got {:?}", result),
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_exact_one_occurrence() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let exact = cddl_from_str("a = [1*1 int]", true).map_err(json::Error::CDDLParsing)?;
+    let required = cddl_from_str("a = [int]", true).map_err(json::Error::CDDLParsing)?;
+
+    for json in ["[1]", "[]", "[1, 2]", r#"["a"]"#] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+
+      let mut jv = JSONValidator::new(&exact, json.clone(), None);
+      let exact_result = jv.validate().map_err(|e| e.to_string());
+      let mut jv = JSONValidator::new(&required, json, None);
+      let required_result = jv.validate().map_err(|e| e.to_string());
+
+      assert_eq!(exact_result, required_result);
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_rule_reference_chain() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = indoc!(
+      r#"
+        a = b
+        b = c
+        c = int
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+    let json =
+      serde_json::from_str::<serde_json::Value>(r#""x""#).map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    let error = jv.validate().unwrap_err();
+    assert_eq!(error.leaf_errors()[0].rule_chain, ["a", "b", "c", "int"]);
+    assert!(error.to_string().contains("a -> b -> c -> int"));
+
+    let cddl = cddl_from_str("a = int", true).map_err(json::Error::CDDLParsing)?;
+    let json =
+      serde_json::from_str::<serde_json::Value>(r#""x""#).map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    assert!(!jv.validate().unwrap_err().to_string().contains("->"));
+
+    // Errors which aren't raised by the prelude type at the end of a chain,
+    // such as missing keys, aren't annotated with one
+    let cddl = indoc!(
+      r#"
+        a = b
+        b = c
+        c = { z: int }
+      "#
+    );
+
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>("{}").map_err(json::Error::JSONParsing)?;
+
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    let error = jv.validate().unwrap_err();
+    assert!(error.leaf_errors()[0].rule_chain.is_empty());
+    assert!(!error.to_string().contains("->"));
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_array_of_group_choices() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "a = [* (int // tstr)]";
+
+    validate_json_from_str(cddl, r#"[1, "a", 2]"#, None)?;
+    validate_json_from_str(cddl, "[]", None)?;
+
+    let cddl = "a = [+ (int // tstr)]";
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "[]", None),
+      &[("", "expected array with length per occurrence +")],
+    );
+
+    let cddl = "a = [(int // tstr)]";
+
+    validate_json_from_str(cddl, r#"["a"]"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"[1, "a"]"#, None),
+      &[("", r#"unexpected array item at index 1, got "a""#)],
+    );
+
+    let cddl = "a = [* (int // tstr // bool)]";
+
+    validate_json_from_str(cddl, r#"[1, "a", true]"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "[1, null]", None),
+      &[
+        ("/1", "expected type int, got null"),
+        ("/1", "expected type tstr, got null"),
+        ("/1", "expected type bool, got null"),
+      ],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_array_of_group_choices_with_entry_occurrences(
+  ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "a = [(1*2 int // tstr)]";
+
+    validate_json_from_str(cddl, "[1, 2]", None)?;
+
+    let cddl = "a = [* (2*2 int // tstr)]";
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "[1]", None),
+      &[
+        (
+          "",
+          "expected array item 2*2 int at index 1, got end of array",
+        ),
+        ("/0", "expected type tstr, got 1"),
+      ],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_array_of_multi_entry_group_choices(
+  ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "a = [(int, tstr // tstr, int)]";
+
+    validate_json_from_str(cddl, r#"[1, "a"]"#, None)?;
+    validate_json_from_str(cddl, r#"["a", 1]"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"["a", "b"]"#, None),
+      &[
+        ("/0", r#"expected type int, got "a""#),
+        ("/1", r#"expected type int, got "b""#),
+      ],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, "[1]", None),
+      &[
+        ("", "expected array item tstr at index 1, got end of array"),
+        ("/0", "expected type tstr, got 1"),
+      ],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"[1, "a", 2]"#, None),
+      &[("", "unexpected array item at index 2, got 2")],
+    );
+
+    let cddl = "a = [* (int, tstr // bool)]";
+
+    validate_json_from_str(cddl, r#"[1, "a", true, 2, "b"]"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"[1, "a", 2]"#, None),
+      &[
+        ("", "expected array item tstr at index 3, got end of array"),
+        ("/2", "expected type bool, got 2"),
+      ],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "arbitrary-precision")]
+  fn validate_big_integers() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "a = uint";
+
+    validate_json_from_str(cddl, "18446744073709551616", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "-18446744073709551616", None),
+      &[("", "expected type uint, got -18446744073709551616")],
+    );
+
+    validate_json_from_str("a = int", "-18446744073709551616", None)?;
+
+    let cddl = "a = nint";
+
+    validate_json_from_str(cddl, "-18446744073709551616", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "18446744073709551616", None),
+      &[("", "expected type nint, got 18446744073709551616")],
+    );
+
+    assert_validation_errors(
+      validate_json_from_str("a = 0..100", "18446744073709551616", None),
+      &[(
+        "",
+        "expected integer to be in range 0 <= value <= 100, got 18446744073709551616",
+      )],
+    );
+
+    assert_validation_errors(
+      validate_json_from_str("a = tstr", "18446744073709551616", None),
+      &[("", "expected type tstr, got 18446744073709551616")],
+    );
+
+    let cddl = "a = int .lt 5";
+
+    validate_json_from_str(cddl, "-123456789012345678901234567890", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "123456789012345678901234567890", None),
+      &[(
+        "",
+        "expected value .lt 5, got 123456789012345678901234567890",
+      )],
+    );
+
+    let cddl = "a = int .ge 0";
+
+    validate_json_from_str(cddl, "123456789012345678901234567890", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "-123456789012345678901234567890", None),
+      &[(
+        "",
+        "expected value .ge 0, got -123456789012345678901234567890",
+      )],
+    );
+
+    validate_json_from_str("a = uint .ne 5", "123456789012345678901234567890", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str("a = uint .eq 5", "123456789012345678901234567890", None),
+      &[("", "expected value 5, got 123456789012345678901234567890")],
+    );
+
+    validate_json_from_str("a = uint .size 13", "123456789012345678901234567890", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str("a = uint .size 8", "123456789012345678901234567890", None),
+      &[(
+        "",
+        "expected value .size 8, got 123456789012345678901234567890",
+      )],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_integer_controls_of_either_sign(
+  ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    validate_json_from_str("a = int .lt 5", "-5", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str("a = int .ge 0", "-5", None),
+      &[("", "expected value .ge 0, got -5")],
+    );
+
+    validate_json_from_str("a = int .gt -10", "-5", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str("a = int .le -10", "-5", None),
+      &[("", "expected value .le -10, got -5")],
+    );
+
+    let cddl = "a = uint .size 2";
+
+    validate_json_from_str(cddl, "65535", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "65536", None),
+      &[("", "expected value .size 2, got 65536")],
+    );
+
+    assert_validation_errors(
+      validate_json_from_str("a = int .size 2", "-1", None),
+      &[(
+        "",
+        "target for .size must a string or uint data type, got int",
+      )],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn compare_big_integer_literals() {
+    use std::cmp::Ordering;
+
+    assert_eq!(
+      compare_integer_literals("18446744073709551616", "18446744073709551615"),
+      Ordering::Greater
+    );
+    assert_eq!(
+      compare_integer_literals("-18446744073709551616", "-5"),
+      Ordering::Less
+    );
+    assert_eq!(compare_integer_literals("-0", "0"), Ordering::Equal);
+    assert_eq!(compare_integer_literals("007", "7"), Ordering::Equal);
+    assert_eq!(compare_integer_literals("-10", "-9"), Ordering::Less);
+  }
+
+  #[test]
+  fn validate_optional_middle_array_item() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "a = [int, ? tstr, bool]";
+
+    validate_json_from_str(cddl, "[1, true]", None)?;
+    validate_json_from_str(cddl, r#"[1, "x", true]"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "[1, 2, true]", None),
+      &[("/1", "expected type bool, got 2")],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"[1, "x"]"#, None),
+      &[("", "expected array item bool at index 2, got end of array")],
+    );
+
+    validate_json_from_str("a = [? int, int]", "[1]", None)?;
+
+    let cddl = "a = [* int, int]";
+
+    validate_json_from_str(cddl, "[1, 2, 3]", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "[]", None),
+      &[("", "expected array item int at index 0, got end of array")],
+    );
+
+    validate_json_from_str("a = [* tstr, ? int, tstr]", r#"["a", "b"]"#, None)?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_array_of_bool_literals() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "a = [* true]";
+
+    validate_json_from_str(cddl, "[true, true]", None)?;
+    validate_json_from_str(cddl, "[]", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "[true, false]", None),
+      &[("/1", "expected type true, got false")],
+    );
+
+    let cddl = "a = [* false]";
+
+    validate_json_from_str(cddl, "[false, false]", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "[false, 0]", None),
+      &[("/1", "expected type false, got 0")],
+    );
+
+    validate_json_from_str("a = [+ true]", "[true]", None)?;
+
+    let cddl = "a = [true, false]";
+
+    validate_json_from_str(cddl, "[true, false]", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "[false, true]", None),
+      &[
+        ("/0", "expected type true, got false"),
+        ("/1", "expected type false, got true"),
+      ],
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_parenthesized_type_choice() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "x = (int / tstr)";
+
+    validate_json_from_str(cddl, "1", None)?;
+    validate_json_from_str(cddl, r#""a""#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, "true", None),
+      &[
+        ("", "expected type int, got true"),
+        ("", "expected type tstr, got true"),
+      ],
+    );
 
-    if let Some(e) = error {
-      self.add_error(e);
-    }
+    let cddl = "x = { a: (int / tstr) }";
+
+    validate_json_from_str(cddl, r#"{ "a": "b" }"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "a": true }"#, None),
+      &[
+        ("/a", "expected type int, got true"),
+        ("/a", "expected type tstr, got true"),
+      ],
+    );
 
     Ok(())
   }
 
-  fn visit_occurrence(&mut self, o: &Occurrence) -> visitor::Result<Error> {
-    self.occurrence = Some(o.occur);
+  #[test]
+  fn validate_text_size_in_bytes() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    validate_json_from_str("x = tstr .size 2", r#""é""#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str("x = tstr .size 1", r#""é""#, None),
+      &[("", r#"expected "é" .size 1 (UTF-8 bytes), got 2 bytes"#)],
+    );
+
+    validate_json_from_str("x = tstr .size (2..3)", r#""é""#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str("x = tstr .size (1..1)", r#""é""#, None),
+      &[(
+        "",
+        r#"expected "é" string length in UTF-8 bytes to be in the range 1 <= value <= 1, got 2"#,
+      )],
+    );
 
     Ok(())
   }
-}
 
-#[cfg(test)]
-#[cfg(not(target_arch = "wasm32"))]
-mod tests {
-  #![allow(unused_imports)]
+  #[test]
+  fn display_validation_errors_one_per_line() -> std::result::Result<(), Box<dyn std::error::Error>>
+  {
+    let cddl =
+      cddl_from_str("a = { b: int / tstr, c: bool }", true).map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>(r#"{ "b": null, "c": 1 }"#)
+      .map_err(json::Error::JSONParsing)?;
 
-  use super::*;
-  use indoc::indoc;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    let error = jv.validate().unwrap_err();
+    let output = error.to_string();
+
+    assert_eq!(output.lines().count(), error.leaf_errors().len());
+    assert!(output.lines().all(|l| !l.trim().is_empty()));
+    assert!(output.ends_with('\n') && !output.ends_with("\n\n"));
+
+    Ok(())
+  }
 
-  #[cfg(feature = "additional-controls")]
   #[test]
-  fn validate_plus() -> std::result::Result<(), Box<dyn std::error::Error>> {
+  fn validate_map_and_array_union() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "thing = [*int] / { a: int }";
+
+    validate_json_from_str(cddl, "[1, 2]", None)?;
+    validate_json_from_str(cddl, r#"{ "a": 1 }"#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"["x"]"#, None),
+      &[("/0", r#"expected type int, got "x""#)],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "a": "x" }"#, None),
+      &[("/a", r#"expected type int, got "x""#)],
+    );
+
+    validate_json_from_str("thing = { a: int } / [*int]", "[1, 2]", None)?;
+
+    assert_validation_errors(
+      validate_json_from_str("thing = [*int] / tstr", r#"["x"]"#, None),
+      &[("/0", r#"expected type int, got "x""#)],
+    );
+
+    assert_validation_errors(
+      validate_json_from_str("thing = int / tstr", r#"["x"]"#, None),
+      &[
+        ("", r#"expected type int, got ["x"]"#),
+        ("", r#"expected type tstr, got ["x"]"#),
+      ],
+    );
+
     let cddl = indoc!(
       r#"
-        interval<BASE> = (
-          "test" => BASE .plus a
-        )
-    
-        rect = {
-          interval<X>
-        }
-        X = 0
-        a = 10
+        thing = b
+        b = [*int]
+        b /= tstr
       "#
     );
-    let json = r#"{ "test": 10 }"#;
-
-    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
-    if let Err(e) = &cddl {
-      println!("{}", e);
-    }
 
-    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+    validate_json_from_str(cddl, "[1]", None)?;
 
-    let cddl = cddl.unwrap();
-    let mut jv = JSONValidator::new(&cddl, json, None);
-    jv.validate()?;
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"["x"]"#, None),
+      &[
+        ("/0", r#"expected type int, got "x""#),
+        ("", r#"expected type tstr, got ["x"]"#),
+      ],
+    );
 
     Ok(())
   }
 
-  #[cfg(feature = "additional-controls")]
   #[test]
-  fn validate_feature() -> std::result::Result<(), Box<dyn std::error::Error>> {
-    let cddl = indoc!(
-      r#"
-        v = JC<"v", 2>
-        JC<J, C> =  C .feature "cbor" / J .feature "json"
-      "#
+  fn validate_coerced_numeric_strings() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let coerce = ValidationOptions {
+      coerce_numeric_strings: true,
+      ..Default::default()
+    };
+
+    let cddl = "a = uint";
+
+    validate_json_from_str_with_options(cddl, r#""42""#, None, coerce)?;
+
+    // Numeric strings are only coerced with the option
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""42""#, None),
+      &[("", r#"expected type uint, got "42""#)],
+    );
+    assert_validation_errors(
+      validate_json_from_str_with_options(cddl, r#""-42""#, None, coerce),
+      &[("", "expected type uint, got -42")],
+    );
+    assert_validation_errors(
+      validate_json_from_str_with_options(cddl, r#""42a""#, None, coerce),
+      &[("", r#"expected type uint, got "42a""#)],
     );
 
-    let json = r#""v""#;
+    let cddl = "a = int";
 
-    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
-    if let Err(e) = &cddl {
-      println!("{}", e);
-    }
+    validate_json_from_str_with_options(cddl, r#""-42""#, None, coerce)?;
 
-    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+    let cddl = "a = float";
 
-    let cddl = cddl.unwrap();
+    validate_json_from_str_with_options(cddl, r#""4.2""#, None, coerce)?;
 
-    let mut jv = JSONValidator::new(&cddl, json, Some(&["json"]));
-    jv.validate()?;
+    let cddl = "a = { b: uint }";
 
-    Ok(())
-  }
+    validate_json_from_str_with_options(cddl, r#"{ "b": "42" }"#, None, coerce)?;
 
-  #[test]
-  fn validate_type_choice_alternate() -> std::result::Result<(), Box<dyn std::error::Error>> {
-    let cddl = indoc!(
-      r#"
-        tester = [ $vals ]
-        $vals /= 12
-        $vals /= 13
-      "#
+    let cddl = "a = tstr";
+
+    validate_json_from_str_with_options(cddl, r#""42""#, None, coerce)?;
+
+    let cddl = "a = 0..10";
+
+    validate_json_from_str_with_options(cddl, r#""5""#, None, coerce)?;
+
+    assert_validation_errors(
+      validate_json_from_str_with_options(cddl, r#""42""#, None, coerce),
+      &[("", "expected uint to be in range 0 <= value <= 10, got 42")],
     );
 
-    let json = r#"[ 13 ]"#;
+    let cddl = "a = uint .lt 10";
 
-    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
-    if let Err(e) = &cddl {
-      println!("{}", e);
-    }
+    validate_json_from_str_with_options(cddl, r#""5""#, None, coerce)?;
 
-    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+    assert_validation_errors(
+      validate_json_from_str_with_options(cddl, r#""42""#, None, coerce),
+      &[("", "expected value .lt 10, got 42")],
+    );
 
-    let cddl = cddl.unwrap();
+    let cddl = "a = uint .size 1";
 
-    let mut jv = JSONValidator::new(&cddl, json, None);
-    jv.validate()?;
+    validate_json_from_str_with_options(cddl, r#""42""#, None, coerce)?;
+
+    assert_validation_errors(
+      validate_json_from_str_with_options(cddl, r#""300""#, None, coerce),
+      &[("", "expected value .size 1, got 300")],
+    );
 
     Ok(())
   }
 
   #[test]
-  fn validate_group_choice_alternate() -> std::result::Result<(), Box<dyn std::error::Error>> {
-    let cddl = indoc!(
-      r#"
-        tester = $$vals
-        $$vals //= 18
-        $$vals //= 12
-      "#
+  #[cfg(feature = "uri")]
+  fn validate_uri() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = "a = uri";
+
+    validate_json_from_str(cddl, r#""https://example.com/a?b=c#d""#, None)?;
+    validate_json_from_str(cddl, r#""urn:isbn:0451450523""#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""not a uri""#, None),
+      &[(
+        "",
+        "expected URI data type, decoding error: invalid path character",
+      )],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""http://[::1""#, None),
+      &[(
+        "",
+        "expected URI data type, decoding error: invalid host IPv4 or registered name character",
+      )],
     );
 
-    let json = r#"15"#;
+    Ok(())
+  }
 
-    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
-    if let Err(e) = &cddl {
-      println!("{}", e);
-    }
+  #[test]
+  fn validate_type_choice_reports_matching_kind(
+  ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("a = tstr / 0..10 / bool", true).map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>("11").map_err(json::Error::JSONParsing)?;
 
-    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    let error = jv.validate().unwrap_err();
+    let reasons = error
+      .leaf_errors()
+      .iter()
+      .map(|e| e.reason.as_str())
+      .collect::<Vec<_>>();
+    assert_eq!(
+      reasons,
+      ["expected uint to be in range 0 <= value <= 10, got 11"]
+    );
 
-    let cddl = cddl.unwrap();
+    // Every failed choice is reported when the value's kind matches several
+    // of them
+    let cddl = cddl_from_str("a = 0..10 / 20..30", true).map_err(json::Error::CDDLParsing)?;
+    let json = serde_json::from_str::<serde_json::Value>("11").map_err(json::Error::JSONParsing)?;
 
     let mut jv = JSONValidator::new(&cddl, json, None);
-    jv.validate()?;
+    assert_eq!(jv.validate().unwrap_err().leaf_errors().len(), 2);
 
     Ok(())
   }
 
   #[test]
-  fn validate_group_choice_alternate_in_array_1(
+  fn validate_type_choice_kind_with_cyclic_rules(
   ) -> std::result::Result<(), Box<dyn std::error::Error>> {
     let cddl = indoc!(
       r#"
-        tester = [$$val]
-        $$val //= (
-          type: 10,
-          data: uint,
-          t: 11
-        )
-        $$val //= (
-          type: 11,
-          data: tstr
-        )
+        a = int / b
+        b = tstr / a
       "#
     );
 
-    let json = r#"[10, 11, 11]"#;
+    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
 
-    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
-    if let Err(e) = &cddl {
-      println!("{}", e);
+    for json in ["1", r#""x""#] {
+      let json =
+        serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+      let mut jv = JSONValidator::new(&cddl, json, None);
+      jv.validate()?;
     }
 
-    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
-
-    let cddl = cddl.unwrap();
-
+    let json =
+      serde_json::from_str::<serde_json::Value>("true").map_err(json::Error::JSONParsing)?;
     let mut jv = JSONValidator::new(&cddl, json, None);
-    jv.validate()?;
+    assert!(jv.validate().is_err());
 
     Ok(())
   }
 
   #[test]
-  fn validate_group_choice_alternate_in_array_2(
+  fn validate_tagged_union_reports_discriminated_choice(
   ) -> std::result::Result<(), Box<dyn std::error::Error>> {
     let cddl = indoc!(
       r#"
-        tester = [$$val]
-        $$val //= (
-          type: 10,
-          extra,
-        )
-        extra = (
-          something: uint,
-        )
+        envelope = { type: "a", data: a-data // type: "b", data: b-data }
+        a-data = { x: int }
+        b-data = { y: tstr }
       "#
     );
 
-    let json = r#"[10, 1]"#;
+    validate_json_from_str(cddl, r#"{ "type": "b", "data": { "y": "s" } }"#, None)?;
 
-    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
-    if let Err(e) = &cddl {
-      println!("{}", e);
-    }
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "type": "b", "data": { "y": 1 } }"#, None),
+      &[("/data/y", "expected type tstr, got 1")],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "type": "a", "data": { "y": "s" } }"#, None),
+      &[("/data", r#"required key "x" missing"#)],
+    );
+    // Without a matching discriminator, the errors of every choice are reported
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "type": "c", "data": {} }"#, None),
+      &[
+        ("/type", r#"expected value "a" got "c""#),
+        ("/data", r#"required key "x" missing"#),
+        ("/type", r#"expected value "b" got "c""#),
+        ("/data", r#"required key "y" missing"#),
+      ],
+    );
 
-    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+    let cddl = indoc!(
+      r#"
+        tagged = { type: "a", data: a-data } / { type: "b", data: b-data }
+        a-data = { x: int }
+        b-data = { y: tstr }
+      "#
+    );
 
-    let cddl = cddl.unwrap();
+    validate_json_from_str(cddl, r#"{ "type": "b", "data": { "y": "s" } }"#, None)?;
 
-    let mut jv = JSONValidator::new(&cddl, json, None);
-    jv.validate()?;
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "type": "b", "data": { "y": 1 } }"#, None),
+      &[("/data/y", "expected type tstr, got 1")],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "type": "a", "data": { "y": "s" } }"#, None),
+      &[("/data", r#"required key "x" missing"#)],
+    );
+    // Without a matching discriminator, the errors of every choice are reported
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#"{ "type": "c", "data": {} }"#, None),
+      &[
+        ("/type", r#"expected value "a" got "c""#),
+        ("/data", r#"required key "x" missing"#),
+        ("/type", r#"expected value "b" got "c""#),
+        ("/data", r#"required key "y" missing"#),
+      ],
+    );
 
     Ok(())
   }
 
   #[test]
-  fn size_control_validation_error() -> std::result::Result<(), Box<dyn std::error::Error>> {
-    let cddl = indoc!(
-      r#"
-        start = Record
-        Record = {
-          id: Id
-        }
-        Id = uint .size 8
-      "#
-    );
+  fn error_predicates() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let error =
+      validate_json_from_str(r#"a = tstr .regexp "foo(?=bar)""#, r#""foobar""#, None).unwrap_err();
+    assert!(error.is_syntax());
+    assert!(!error.is_occurrence());
+    assert!(error.as_target().is_none());
 
-    let json = r#"{ "id": 5 }"#;
+    let error = validate_json_from_str("a = { b: int }", "{}", None).unwrap_err();
+    assert!(!error.is_syntax());
+    assert!(error.is_occurrence());
+    assert!(error.as_target().is_none());
 
-    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
-    if let Err(e) = &cddl {
-      println!("{}", e);
-    }
+    let error = validate_json_from_str("a = [2*3 int]", "[1]", None).unwrap_err();
+    assert!(error.is_occurrence());
 
-    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+    let error = validate_json_from_str("a = [* { b: int }]", "[{}]", None).unwrap_err();
+    assert!(error.is_occurrence());
 
-    let cddl = cddl.unwrap();
+    let error = validate_json_from_str("a = { b: int }", r#"{ "b": "x" }"#, None).unwrap_err();
+    assert!(!error.is_occurrence());
 
-    let mut jv = JSONValidator::new(&cddl, json, None);
-    jv.validate()?;
+    let error = validate_json_from_str("a = { b: int }", "{", None).unwrap_err();
+    assert!(!error.is_syntax());
+    assert!(!error.is_occurrence());
+    assert!(matches!(error.as_target(), Some(e) if e.is_eof()));
 
     Ok(())
   }
 
   #[test]
-  fn validate_occurrences_in_object() -> std::result::Result<(), Box<dyn std::error::Error>> {
-    let cddl = indoc!(
-      r#"
-        limited = { 1* tstr => tstr }
-      "#
+  fn validate_regex_matches_entire_string() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = r#"a = tstr .pcre "[0-9]+""#;
+
+    validate_json_from_str(cddl, r#""12""#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""a12b""#, None),
+      &[("", r#"expected "a12b" to match regex "[0-9]+""#)],
     );
 
-    let json = r#"{ "A": "B" }"#;
+    let cddl = r#"a = tstr .regexp "[0-9]+""#;
 
-    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
-    if let Err(e) = &cddl {
-      println!("{}", e);
-    }
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""12b""#, None),
+      &[("", r#"expected "12b" to match regex "[0-9]+""#)],
+    );
 
-    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
+    let cddl = r#"a = tstr .regexp "a|b""#;
 
-    let cddl = cddl.unwrap();
+    validate_json_from_str(cddl, r#""b""#, None)?;
 
-    let mut jv = JSONValidator::new(&cddl, json, None);
-    jv.validate().unwrap();
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""ab""#, None),
+      &[("", r#"expected "ab" to match regex "a|b""#)],
+    );
+
+    let cddl = r#"a = tstr .pcre "^a|b""#;
+
+    validate_json_from_str(cddl, r#""a""#, None)?;
+    validate_json_from_str(cddl, r#""b""#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""bzz""#, None),
+      &[("", r#"expected "bzz" to match regex "^a|b""#)],
+    );
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""zza""#, None),
+      &[("", r#"expected "zza" to match regex "^a|b""#)],
+    );
+
+    let cddl = r#"a = tstr .pcre "^[0-9]+""#;
+
+    validate_json_from_str(cddl, r#""12""#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""12abc""#, None),
+      &[("", r#"expected "12abc" to match regex "^[0-9]+""#)],
+    );
+
+    let cddl = r#"a = tstr .pcre "[0-9]+$""#;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""a12""#, None),
+      &[("", r#"expected "a12" to match regex "[0-9]+$""#)],
+    );
+
+    let cddl = r#"a = tstr .pcre "^[0-9]+$""#;
+
+    validate_json_from_str(cddl, r#""12""#, None)?;
+
+    assert_validation_errors(
+      validate_json_from_str(cddl, r#""12b""#, None),
+      &[("", r#"expected "12b" to match regex "^[0-9]+$""#)],
+    );
 
     Ok(())
   }
 
   #[test]
-  fn validate_optional_occurrences_in_object() -> std::result::Result<(), Box<dyn std::error::Error>>
-  {
-    let cddl = indoc!(
-      r#"
-        argument = {
-          name: text,
-          ? valid: "yes" / "no",
-        }
-      "#
-    );
+  fn validate_type_choice_errors_name_alternatives(
+  ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("a = int / bool / uint", true).map_err(json::Error::CDDLParsing)?;
+    let json =
+      serde_json::from_str::<serde_json::Value>(r#""x""#).map_err(json::Error::JSONParsing)?;
 
-    let json = r#"{
-      "name": "foo",
-      "valid": "no"
-    }"#;
+    let mut jv = JSONValidator::new(&cddl, json, None);
+    let error = jv.validate().unwrap_err();
+    assert_eq!(
+      error
+        .leaf_errors()
+        .iter()
+        .map(|e| e.alternatives.join(" / "))
+        .collect::<Vec<_>>(),
+      ["int", "bool", "uint"]
+    );
 
-    let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing);
-    if let Err(e) = &cddl {
-      println!("{}", e);
+    let error = error.to_string();
+    for alternative in ["int", "bool", "uint"] {
+      assert!(error.contains(&format!(
+        "alternative '{}': expected type {}, got \"x\"",
+        alternative, alternative
+      )));
     }
 
-    let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
-
-    let cddl = cddl.unwrap();
+    // Errors of a single remaining choice aren't associated with it
+    let json = serde_json::from_str::<serde_json::Value>("-1").map_err(json::Error::JSONParsing)?;
+    let cddl = cddl_from_str("a = uint / tstr", true).map_err(json::Error::CDDLParsing)?;
 
     let mut jv = JSONValidator::new(&cddl, json, None);
-    jv.validate().unwrap();
+    let error = jv.validate().unwrap_err();
+    assert!(error.leaf_errors()[0].alternatives.is_empty());
 
     Ok(())
   }
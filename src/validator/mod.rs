@@ -6,17 +6,22 @@ pub mod cbor;
 pub mod json;
 
 mod control;
+/// Format agnostic operations on document values
+pub mod value;
 
 use crate::{
   ast::{
-    Group, GroupChoice, GroupEntry, GroupRule, Identifier, Occur, Rule, Type, Type2, TypeChoice,
-    TypeRule, CDDL,
+    GenericArgs, Group, GroupChoice, GroupEntry, GroupRule, Identifier, MemberKey, Occur,
+    Occurrence, Rule, Type, Type2, TypeChoice, TypeGroupnameEntry, TypeRule, CDDL,
   },
   token::*,
-  visitor::Visitor,
+  visitor::{self, Visitor},
 };
 
-use std::error::Error;
+use std::{
+  collections::{HashMap, HashSet},
+  error::Error,
+};
 
 #[cfg(feature = "cbor")]
 use cbor::CBORValidator;
@@ -26,10 +31,12 @@ use ciborium;
 use json::JSONValidator;
 use serde::de::Deserialize;
 
+#[cfg(target_arch = "wasm32")]
+#[cfg(feature = "ast-span")]
+use crate::lexer::Position;
 #[cfg(target_arch = "wasm32")]
 use crate::{
   error::ErrorMsg,
-  lexer::Position,
   parser::{self, Parser},
 };
 #[cfg(target_arch = "wasm32")]
@@ -43,6 +50,7 @@ use crate::cddl_from_str;
 #[cfg(target_arch = "wasm32")]
 #[derive(Serialize)]
 struct ParserError {
+  #[cfg(feature = "ast-span")]
   position: Position,
   msg: ErrorMsg,
 }
@@ -55,8 +63,53 @@ pub trait Validator<'a, 'b, E: Error>: Visitor<'a, 'b, E> {
   fn add_error(&mut self, reason: String);
 }
 
+/// CDDL syntax which is invalid or isn't supported during validation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+  /// Kind of invalid or unsupported syntax
+  pub kind: SyntaxErrorKind,
+  /// Description of the invalid or unsupported syntax
+  pub detail: String,
+}
+
+/// Kinds of CDDL syntax which are invalid or aren't supported during
+/// validation, so that they can be told apart without matching on the error
+/// message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SyntaxErrorKind {
+  /// A .regexp or .pcre pattern uses lookahead or lookbehind, which the regex
+  /// engine doesn't support
+  RegexLookaround,
+  /// A type references a rule which isn't defined
+  UndefinedRule,
+  /// The target or controller of a control operator has a data type which the
+  /// operator doesn't apply to
+  ControlOperatorTypeMismatch,
+  /// The bounds of a range aren't numeric values of matching types
+  InvalidRangeBound,
+}
+
+impl SyntaxError {
+  pub(crate) fn new(kind: SyntaxErrorKind, detail: &str) -> Self {
+    SyntaxError {
+      kind,
+      detail: detail.to_string(),
+    }
+  }
+}
+
+impl std::fmt::Display for SyntaxError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "{}", self.detail)
+  }
+}
+
+impl Error for SyntaxError {}
+
 impl CDDL<'_> {
   /// Validate the given document against the CDDL definition
+  #[cfg(feature = "json")]
   fn validate_json(
     &self,
     document: &[u8],
@@ -73,11 +126,31 @@ impl CDDL<'_> {
     #[cfg(feature = "additional-controls")]
     let mut jv = JSONValidator::new(self, json, enabled_features);
     #[cfg(not(feature = "additional-controls"))]
-    let mut jv = JSONValidator::new(&cddl, json);
+    let mut jv = JSONValidator::new(self, json);
 
     jv.validate().map_err(|e| e.into())
   }
 
+  /// Validate an already parsed JSON value against the given type, which
+  /// needn't be part of the CDDL definition, e.g. one built programmatically.
+  /// Identifiers within the type refer to the rules of the CDDL definition
+  #[cfg(feature = "json")]
+  #[cfg(not(target_arch = "wasm32"))]
+  pub fn validate_value_against_type(
+    &self,
+    t: &Type,
+    value: &serde_json::Value,
+    #[cfg(feature = "additional-controls")] enabled_features: Option<&[&str]>,
+  ) -> json::Result {
+    #[cfg(feature = "additional-controls")]
+    let mut jv = JSONValidator::new(self, value.clone(), enabled_features);
+    #[cfg(not(feature = "additional-controls"))]
+    let mut jv = JSONValidator::new(self, value.clone());
+
+    jv.validate_type(t)
+  }
+
+  #[cfg(feature = "cbor")]
   fn validate_cbor(
     &self,
     document: &[u8],
@@ -90,7 +163,11 @@ impl CDDL<'_> {
   ) -> Result<(), Box<dyn Error>> {
     let cbor: ciborium::value::Value = ciborium::de::from_reader(document)?;
 
+    #[cfg(feature = "additional-controls")]
     let mut cv = CBORValidator::new(self, cbor, enabled_features);
+    #[cfg(not(feature = "additional-controls"))]
+    let mut cv = CBORValidator::new(self, cbor);
+
     cv.validate().map_err(|e| e.into())
   }
 }
@@ -103,6 +180,29 @@ pub fn validate_json_from_str(
   json: &str,
   #[cfg(feature = "additional-controls")] enabled_features: Option<&[&str]>,
 ) -> json::Result {
+  validate_json_from_str_with_options(
+    cddl,
+    json,
+    #[cfg(feature = "additional-controls")]
+    enabled_features,
+    json::ValidationOptions::default(),
+  )
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "json")]
+/// Validate JSON string from a given CDDL document string using the given
+/// validation options
+pub fn validate_json_from_str_with_options(
+  cddl: &str,
+  json: &str,
+  #[cfg(feature = "additional-controls")] enabled_features: Option<&[&str]>,
+  options: json::ValidationOptions,
+) -> json::Result {
+  if options.reject_duplicate_keys {
+    json::check_duplicate_keys(json)?;
+  }
+
   let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
   let json = serde_json::from_str::<serde_json::Value>(json).map_err(json::Error::JSONParsing)?;
 
@@ -111,9 +211,118 @@ pub fn validate_json_from_str(
   #[cfg(not(feature = "additional-controls"))]
   let mut jv = JSONValidator::new(&cddl, json);
 
+  jv.validate_with_options(options)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "json")]
+/// Validate JSON read from the given reader against a given CDDL document
+/// string. The JSON is parsed directly from the reader rather than buffered
+/// into a string first
+pub fn validate_json_from_reader<R: std::io::Read>(
+  cddl: &str,
+  reader: R,
+  #[cfg(feature = "additional-controls")] enabled_features: Option<&[&str]>,
+) -> json::Result {
+  let cddl = cddl_from_str(cddl, true).map_err(json::Error::CDDLParsing)?;
+  let json =
+    serde_json::from_reader::<_, serde_json::Value>(reader).map_err(json::Error::JSONParsing)?;
+
+  #[cfg(feature = "additional-controls")]
+  let mut jv = JSONValidator::new(&cddl, json, enabled_features);
+  #[cfg(not(feature = "additional-controls"))]
+  let mut jv = JSONValidator::new(&cddl, json);
+
   jv.validate()
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "json")]
+/// Whether the JSON string is valid against the given CDDL document string.
+/// Any error, including a CDDL or JSON parsing error, yields `false`
+pub fn is_valid_json(cddl: &str, json: &str) -> bool {
+  validate_json_from_str(
+    cddl,
+    json,
+    #[cfg(feature = "additional-controls")]
+    None,
+  )
+  .is_ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "json")]
+/// Validate an already parsed JSON value against an already parsed CDDL
+/// document. Useful for validating many documents against the same schema
+/// without re-parsing it each time
+pub fn validate_json_value(
+  cddl: &CDDL,
+  value: &serde_json::Value,
+  #[cfg(feature = "additional-controls")] enabled_features: Option<&[&str]>,
+) -> json::Result {
+  #[cfg(feature = "additional-controls")]
+  let mut jv = JSONValidator::new(cddl, value.clone(), enabled_features);
+  #[cfg(not(feature = "additional-controls"))]
+  let mut jv = JSONValidator::new(cddl, value.clone());
+
+  jv.validate()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "json")]
+/// Validate an already parsed JSON value against an already parsed CDDL
+/// document, also returning the keys of the root object which weren't
+/// matched by any member key. Keys accepted by a wildcard entry such as
+/// `* tstr => any` are reported even though validation succeeds
+pub fn validate_json_reporting_extras(
+  cddl: &CDDL,
+  value: &serde_json::Value,
+  #[cfg(feature = "additional-controls")] enabled_features: Option<&[&str]>,
+) -> (json::Result, Vec<String>) {
+  #[cfg(feature = "additional-controls")]
+  let mut jv = JSONValidator::new(cddl, value.clone(), enabled_features);
+  #[cfg(not(feature = "additional-controls"))]
+  let mut jv = JSONValidator::new(cddl, value.clone());
+
+  let result = jv.validate();
+
+  (result, jv.extra_keys())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "json")]
+/// Validate newline-delimited JSON (JSONL) read from the given reader against
+/// an already parsed CDDL document. Each non-empty line is parsed and
+/// validated as it is read, yielding its 1-based line number and the result
+pub fn validate_jsonl<'a, R: std::io::BufRead + 'a>(
+  cddl: &'a CDDL<'a>,
+  reader: R,
+  #[cfg(feature = "additional-controls")] enabled_features: Option<&'a [&'a str]>,
+) -> impl Iterator<Item = (usize, json::Result)> + 'a {
+  reader
+    .lines()
+    .enumerate()
+    .map(|(idx, line)| (idx + 1, line))
+    .filter(|(_, line)| !matches!(line, Ok(l) if l.trim().is_empty()))
+    .map(move |(line_number, line)| {
+      let result = line
+        .map_err(|e| json::Error::JSONParsing(serde_json::Error::io(e)))
+        .and_then(|l| {
+          serde_json::from_str::<serde_json::Value>(&l).map_err(json::Error::JSONParsing)
+        })
+        .and_then(|value| {
+          validate_json_value(
+            cddl,
+            &value,
+            #[cfg(feature = "additional-controls")]
+            enabled_features,
+          )
+        });
+
+      (line_number, result)
+    })
+}
+
 #[cfg(target_arch = "wasm32")]
 #[cfg(feature = "additional-controls")]
 #[cfg(feature = "json")]
@@ -133,8 +342,14 @@ pub fn validate_json_from_str(
         &p.errors
           .iter()
           .filter_map(|e| {
-            if let parser::Error::PARSER { position, msg } = e {
+            if let parser::Error::PARSER {
+              #[cfg(feature = "ast-span")]
+              position,
+              msg,
+            } = e
+            {
               Some(ParserError {
+                #[cfg(feature = "ast-span")]
                 position: *position,
                 msg: msg.clone(),
               })
@@ -163,17 +378,23 @@ pub fn validate_json_from_str(
 #[wasm_bindgen]
 /// Validate JSON string from a given CDDL document string
 pub fn validate_json_from_str(cddl: &str, json: &str) -> std::result::Result<JsValue, JsValue> {
-  let mut l = Lexer::new(cddl);
-  let mut p = Parser::new((&mut l).iter(), cddl).map_err(|e| JsValue::from(e.to_string()))?;
+  let mut p = Parser::new(cddl, Box::new(crate::lexer::lexer_from_str(cddl).iter()))
+    .map_err(|e| JsValue::from(e.to_string()))?;
   let c = p.parse_cddl().map_err(|e| JsValue::from(e.to_string()))?;
   if !p.errors.is_empty() {
     return Err(
-      JsValue::from_serde(
+      serde_wasm_bindgen::to_value(
         &p.errors
           .iter()
           .filter_map(|e| {
-            if let parser::Error::PARSER { position, msg } = e {
+            if let parser::Error::PARSER {
+              #[cfg(feature = "ast-span")]
+              position,
+              msg,
+            } = e
+            {
               Some(ParserError {
+                #[cfg(feature = "ast-span")]
                 position: *position,
                 msg: msg.clone(),
               })
@@ -219,8 +440,7 @@ pub fn validate_cbor_from_slice(
 #[cfg(not(feature = "additional-controls"))]
 /// Validate CBOR slice from a given CDDL document string
 pub fn validate_cbor_from_slice(cddl: &str, cbor_slice: &[u8]) -> cbor::Result<std::io::Error> {
-  let mut lexer = lexer_from_str(cddl);
-  let cddl = cddl_from_str(&mut lexer, cddl, true).map_err(cbor::Error::CDDLParsing)?;
+  let cddl = cddl_from_str(cddl, true).map_err(cbor::Error::CDDLParsing)?;
   let cbor: ciborium::value::Value =
     ciborium::de::from_reader(cbor_slice).map_err(cbor::Error::CBORParsing)?;
 
@@ -228,6 +448,147 @@ pub fn validate_cbor_from_slice(cddl: &str, cbor_slice: &[u8]) -> cbor::Result<s
   cv.validate()
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "cbor")]
+/// Validate an already decoded CBOR value against an already parsed CDDL
+/// document. Useful for validating many values against the same schema
+/// without re-parsing it each time
+pub fn validate_cbor_value(
+  cddl: &CDDL,
+  value: &ciborium::value::Value,
+  #[cfg(feature = "additional-controls")] enabled_features: Option<&[&str]>,
+) -> cbor::Result<std::io::Error> {
+  #[cfg(feature = "additional-controls")]
+  let mut cv = CBORValidator::new(cddl, value.clone(), enabled_features);
+  #[cfg(not(feature = "additional-controls"))]
+  let mut cv = CBORValidator::new(cddl, value.clone());
+
+  cv.validate()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "cbor")]
+/// Whether the CBOR slice is valid against the given CDDL document string.
+/// Any error, including a CDDL parsing or CBOR decoding error, yields `false`
+pub fn is_valid_cbor(cddl: &str, cbor_slice: &[u8]) -> bool {
+  validate_cbor_from_slice(
+    cddl,
+    cbor_slice,
+    #[cfg(feature = "additional-controls")]
+    None,
+  )
+  .is_ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+self_cell::self_cell!(
+  // A parsed CDDL document together with the source text it borrows from
+  struct ParsedSchema {
+    owner: Box<str>,
+
+    #[covariant]
+    dependent: CDDL,
+  }
+
+  impl {Debug}
+);
+
+/// A parsed CDDL schema which owns its source text, so that it can be held
+/// and reused across any number of validations without being re-parsed
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+pub struct SchemaValidator {
+  schema: ParsedSchema,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SchemaValidator {
+  /// Parse the given CDDL document into a reusable schema
+  pub fn new(cddl: &str) -> std::result::Result<Self, String> {
+    let schema = ParsedSchema::try_new(cddl.into(), |source| cddl_from_str(source, true))?;
+
+    Ok(SchemaValidator { schema })
+  }
+
+  /// The parsed CDDL document
+  pub fn cddl(&self) -> &CDDL<'_> {
+    self.schema.borrow_dependent()
+  }
+
+  /// The CDDL source text the schema was parsed from
+  pub fn source(&self) -> &str {
+    self.schema.borrow_owner()
+  }
+
+  /// Validate an already parsed JSON value against the schema
+  #[cfg(feature = "json")]
+  pub fn validate_json(
+    &self,
+    value: &serde_json::Value,
+    #[cfg(feature = "additional-controls")] enabled_features: Option<&[&str]>,
+  ) -> json::Result {
+    validate_json_value(
+      self.cddl(),
+      value,
+      #[cfg(feature = "additional-controls")]
+      enabled_features,
+    )
+  }
+
+  /// Validate an already parsed JSON value against the type rule of the
+  /// schema with the given name
+  #[cfg(feature = "json")]
+  pub fn validate_named(
+    &self,
+    rule_name: &str,
+    value: &serde_json::Value,
+    #[cfg(feature = "additional-controls")] enabled_features: Option<&[&str]>,
+  ) -> json::Result {
+    #[cfg(feature = "additional-controls")]
+    let mut jv = JSONValidator::new(self.cddl(), value.clone(), enabled_features);
+    #[cfg(not(feature = "additional-controls"))]
+    let mut jv = JSONValidator::new(self.cddl(), value.clone());
+
+    jv.validate_named(rule_name)
+  }
+
+  /// Validate an already parsed JSON value against the schema, collecting
+  /// every failure as a structured issue. No issues means the value is valid
+  #[cfg(feature = "json")]
+  pub fn validate_collecting(
+    &self,
+    value: &serde_json::Value,
+    #[cfg(feature = "additional-controls")] enabled_features: Option<&[&str]>,
+  ) -> Vec<json::ValidationIssue> {
+    match self.validate_json(
+      value,
+      #[cfg(feature = "additional-controls")]
+      enabled_features,
+    ) {
+      Ok(()) => Vec::new(),
+      Err(e) => e.issues(value),
+    }
+  }
+
+  /// Validate a CBOR slice against the schema
+  #[cfg(feature = "cbor")]
+  pub fn validate_cbor(
+    &self,
+    cbor_slice: &[u8],
+    #[cfg(feature = "additional-controls")] enabled_features: Option<&[&str]>,
+  ) -> cbor::Result<std::io::Error> {
+    let cbor: ciborium::value::Value =
+      ciborium::de::from_reader(cbor_slice).map_err(cbor::Error::CBORParsing)?;
+
+    validate_cbor_value(
+      self.cddl(),
+      &cbor,
+      #[cfg(feature = "additional-controls")]
+      enabled_features,
+    )
+  }
+}
+
 #[cfg(target_arch = "wasm32")]
 #[cfg(feature = "cbor")]
 #[cfg(feature = "additional-controls")]
@@ -247,8 +608,14 @@ pub fn validate_cbor_from_slice(
         &p.errors
           .iter()
           .filter_map(|e| {
-            if let parser::Error::PARSER { position, msg } = e {
+            if let parser::Error::PARSER {
+              #[cfg(feature = "ast-span")]
+              position,
+              msg,
+            } = e
+            {
               Some(ParserError {
+                #[cfg(feature = "ast-span")]
                 position: *position,
                 msg: msg.clone(),
               })
@@ -280,17 +647,23 @@ pub fn validate_cbor_from_slice(
   cddl: &str,
   cbor_slice: &[u8],
 ) -> std::result::Result<JsValue, JsValue> {
-  let mut l = Lexer::new(cddl);
-  let mut p = Parser::new((&mut l).iter(), cddl).map_err(|e| JsValue::from(e.to_string()))?;
+  let mut p = Parser::new(cddl, Box::new(crate::lexer::lexer_from_str(cddl).iter()))
+    .map_err(|e| JsValue::from(e.to_string()))?;
   let c = p.parse_cddl().map_err(|e| JsValue::from(e.to_string()))?;
   if !p.errors.is_empty() {
     return Err(
-      JsValue::from_serde(
+      serde_wasm_bindgen::to_value(
         &p.errors
           .iter()
           .filter_map(|e| {
-            if let parser::Error::PARSER { position, msg } = e {
+            if let parser::Error::PARSER {
+              #[cfg(feature = "ast-span")]
+              position,
+              msg,
+            } = e
+            {
               Some(ParserError {
+                #[cfg(feature = "ast-span")]
                 position: *position,
                 msg: msg.clone(),
               })
@@ -313,6 +686,211 @@ pub fn validate_cbor_from_slice(
     .map(|_| JsValue::default())
 }
 
+/// Whether the entries of an array group choice can be matched positionally,
+/// i.e. each entry matches a single array item and either at least one of them
+/// has an occurrence indicator or at least one of them is a nested array
+pub(crate) fn is_positional_array_group_choice(cddl: &CDDL, gc: &GroupChoice) -> bool {
+  gc.group_entries.iter().all(|(ge, _)| match ge {
+    GroupEntry::ValueMemberKey { .. } => true,
+    GroupEntry::TypeGroupname { ge, .. } => group_rule_from_ident(cddl, &ge.name).is_none(),
+    _ => false,
+  }) && ((gc.group_entries.len() > 1
+    && gc.group_entries.iter().any(|(ge, _)| match ge {
+      GroupEntry::ValueMemberKey { ge, .. } => ge.occur.is_some(),
+      GroupEntry::TypeGroupname { ge, .. } => ge.occur.is_some(),
+      _ => false,
+    }))
+    || gc
+      .group_entries
+      .iter()
+      .any(|(ge, _)| is_array_group_entry(cddl, ge)))
+}
+
+/// Whether a group entry is an array, either inline, e.g. `[[int, int], tstr]`,
+/// or by way of a type rule whose choices include an array
+fn is_array_group_entry(cddl: &CDDL, ge: &GroupEntry) -> bool {
+  fn is_array_type(cddl: &CDDL, t: &Type, resolve_typenames: bool) -> bool {
+    t.type_choices.iter().any(|tc| match &tc.type1.type2 {
+      Type2::Array { .. } => true,
+      Type2::Typename { ident, .. } if resolve_typenames => {
+        matches!(rule_from_ident(cddl, ident), Some(Rule::Type { rule, .. }) if is_array_type(cddl, &rule.value, false))
+      }
+      _ => false,
+    })
+  }
+
+  match ge {
+    GroupEntry::ValueMemberKey { ge, .. } => is_array_type(cddl, &ge.entry_type, true),
+    GroupEntry::TypeGroupname { ge, .. } => {
+      matches!(rule_from_ident(cddl, &ge.name), Some(Rule::Type { rule, .. }) if is_array_type(cddl, &rule.value, false))
+    }
+    _ => false,
+  }
+}
+
+/// Minimum and maximum number of array items matched by an entry with the
+/// given occurrence indicator
+pub(crate) fn occurrence_bounds(occurrence: Option<&Occurrence>) -> (usize, usize) {
+  match occurrence.map(|o| &o.occur) {
+    None => (1, 1),
+    Some(Occur::Optional { .. }) => (0, 1),
+    Some(Occur::ZeroOrMore { .. }) => (0, usize::MAX),
+    Some(Occur::OneOrMore { .. }) => (1, usize::MAX),
+    Some(Occur::Exact { lower, upper, .. }) => (lower.unwrap_or(0), upper.unwrap_or(usize::MAX)),
+  }
+}
+
+/// Why the items of an array failed to match the entries of an array group
+/// choice in order
+pub(crate) enum PositionalMismatch<V> {
+  /// Errors of the item which failed to match an entry
+  Item(Vec<V>),
+  /// The array ended before the entry at the given index was matched its
+  /// minimum number of times, at the given item index
+  MissingItem(usize, usize),
+  /// The item at the given index isn't matched by any entry
+  UnexpectedItem(usize),
+}
+
+/// Match array items against the entries of an array group choice in order,
+/// where each entry consumes as many consecutive items as its occurrence
+/// indicator allows. Each entry is tried with every number of items it allows,
+/// most first, so that e.g. an optional entry is skipped when the following
+/// entries wouldn't match otherwise. If no split matches, the mismatch of
+/// matching each entry greedily is returned. `validate_item` validates the
+/// item at an index against an entry and returns the resulting errors
+pub(crate) fn match_array_items_positionally<'a, V, E>(
+  gc: &GroupChoice<'a>,
+  item_count: usize,
+  mut validate_item: impl FnMut(&GroupEntry<'a>, usize) -> std::result::Result<Vec<V>, E>,
+) -> std::result::Result<Option<PositionalMismatch<V>>, E> {
+  struct Matcher<'m, 'a, F> {
+    entries: Vec<(&'m GroupEntry<'a>, usize, usize)>,
+    item_count: usize,
+    validate_item: F,
+    // Whether the item at an index matches the entry at an index
+    item_matches: HashMap<(usize, usize), bool>,
+    // Entry and item indices from which the remaining items can't be matched
+    dead_ends: HashSet<(usize, usize)>,
+  }
+
+  impl<'a, F> Matcher<'_, 'a, F> {
+    fn item_matches<V, E>(&mut self, entry: usize, cursor: usize) -> std::result::Result<bool, E>
+    where
+      F: FnMut(&GroupEntry<'a>, usize) -> std::result::Result<Vec<V>, E>,
+    {
+      if let Some(is_match) = self.item_matches.get(&(entry, cursor)) {
+        return Ok(*is_match);
+      }
+
+      let is_match = (self.validate_item)(self.entries[entry].0, cursor)?.is_empty();
+      self.item_matches.insert((entry, cursor), is_match);
+
+      Ok(is_match)
+    }
+
+    fn matches_from<V, E>(&mut self, entry: usize, cursor: usize) -> std::result::Result<bool, E>
+    where
+      F: FnMut(&GroupEntry<'a>, usize) -> std::result::Result<Vec<V>, E>,
+    {
+      if entry == self.entries.len() {
+        return Ok(cursor == self.item_count);
+      }
+
+      if self.dead_ends.contains(&(entry, cursor)) {
+        return Ok(false);
+      }
+
+      let (_, lower, upper) = self.entries[entry];
+      let mut count = 0;
+      while count < upper && cursor + count < self.item_count {
+        if !self.item_matches(entry, cursor + count)? {
+          break;
+        }
+
+        count += 1;
+      }
+
+      while count >= lower {
+        if self.matches_from(entry + 1, cursor + count)? {
+          return Ok(true);
+        }
+
+        if count == 0 {
+          break;
+        }
+
+        count -= 1;
+      }
+
+      self.dead_ends.insert((entry, cursor));
+
+      Ok(false)
+    }
+  }
+
+  let entries = gc
+    .group_entries
+    .iter()
+    .map(|(ge, _)| {
+      let (lower, upper) = match ge {
+        GroupEntry::ValueMemberKey { ge, .. } => occurrence_bounds(ge.occur.as_ref()),
+        GroupEntry::TypeGroupname { ge, .. } => occurrence_bounds(ge.occur.as_ref()),
+        _ => (1, 1),
+      };
+
+      (ge, lower, upper)
+    })
+    .collect::<Vec<_>>();
+
+  let mut matcher = Matcher {
+    entries,
+    item_count,
+    validate_item: &mut validate_item,
+    item_matches: HashMap::new(),
+    dead_ends: HashSet::new(),
+  };
+  if matcher.matches_from(0, 0)? {
+    return Ok(None);
+  }
+
+  let entries = matcher.entries;
+  let mut cursor = 0;
+  // Errors of the most recent attempt to match the item at the cursor
+  let mut item_errors = Vec::new();
+
+  for (idx, (ge, lower, upper)) in entries.into_iter().enumerate() {
+    let mut count = 0;
+    while count < upper && cursor < item_count {
+      item_errors = validate_item(ge, cursor)?;
+      if !item_errors.is_empty() {
+        break;
+      }
+
+      count += 1;
+      cursor += 1;
+    }
+
+    if count < lower {
+      if cursor < item_count {
+        return Ok(Some(PositionalMismatch::Item(item_errors)));
+      }
+
+      return Ok(Some(PositionalMismatch::MissingItem(idx, cursor)));
+    }
+  }
+
+  if cursor < item_count {
+    if item_errors.is_empty() {
+      return Ok(Some(PositionalMismatch::UnexpectedItem(cursor)));
+    }
+
+    return Ok(Some(PositionalMismatch::Item(item_errors)));
+  }
+
+  Ok(None)
+}
+
 /// Find non-choice alternate rule from a given identifier
 pub fn rule_from_ident<'a>(cddl: &'a CDDL, ident: &Identifier) -> Option<&'a Rule<'a>> {
   cddl.rules.iter().find(|r| match r {
@@ -443,6 +1021,132 @@ pub fn group_rule_from_ident<'a>(cddl: &'a CDDL, ident: &Identifier) -> Option<&
   })
 }
 
+/// Collect the bareword and text value member keys declared by a group entry,
+/// including those of nested inline groups and referenced group rules
+pub fn member_keys_from_group_entry<'a>(cddl: &'a CDDL, ge: &'a GroupEntry<'a>) -> Vec<&'a str> {
+  let mut keys = Vec::new();
+  let mut visited = Vec::new();
+  collect_member_keys(cddl, ge, &mut keys, &mut visited);
+  keys
+}
+
+fn collect_member_keys<'a>(
+  cddl: &'a CDDL,
+  ge: &'a GroupEntry<'a>,
+  keys: &mut Vec<&'a str>,
+  visited: &mut Vec<&'a str>,
+) {
+  match ge {
+    GroupEntry::ValueMemberKey { ge, .. } => match &ge.member_key {
+      Some(MemberKey::Bareword { ident, .. }) => keys.push(ident.ident),
+      Some(MemberKey::Value {
+        value: Value::TEXT(t),
+        ..
+      }) => keys.push(t.as_ref()),
+      Some(MemberKey::Type1 { t1, .. }) => {
+        if let Type2::TextValue { value, .. } = &t1.type2 {
+          keys.push(value.as_ref());
+        }
+      }
+      _ => (),
+    },
+    GroupEntry::InlineGroup { group, .. } => {
+      for gc in group.group_choices.iter() {
+        for (ge, _) in gc.group_entries.iter() {
+          collect_member_keys(cddl, ge, keys, visited);
+        }
+      }
+    }
+    GroupEntry::TypeGroupname { ge, .. } => {
+      if visited.contains(&ge.name.ident) {
+        return;
+      }
+      visited.push(ge.name.ident);
+
+      for rule in cddl.rules.iter() {
+        if let Rule::Group { rule, .. } = rule {
+          if rule.name.ident == ge.name.ident {
+            collect_member_keys(cddl, &rule.entry, keys, visited);
+          }
+        }
+      }
+    }
+  }
+}
+
+/// Find all identifiers referenced by type names, group names and group
+/// choices that are neither in the standard prelude nor defined by any rule.
+/// Generic parameters and sockets are not considered undefined. Each undefined
+/// identifier is returned once, in order of first reference
+pub fn undefined_idents<'a>(cddl: &'a CDDL<'a>) -> Vec<&'a Identifier<'a>> {
+  let mut collector = UndefinedIdents {
+    cddl,
+    generic_params: Vec::new(),
+    idents: Vec::new(),
+  };
+
+  // The collector never fails
+  let _ = collector.visit_cddl(cddl);
+
+  collector.idents
+}
+
+struct UndefinedIdents<'a> {
+  cddl: &'a CDDL<'a>,
+  generic_params: Vec<&'a str>,
+  idents: Vec<&'a Identifier<'a>>,
+}
+
+impl<'a> UndefinedIdents<'a> {
+  fn collect(&mut self, ident: &'a Identifier<'a>) {
+    if ident.socket.is_some()
+      || lookup_ident(ident.ident).in_standard_prelude().is_some()
+      || self.generic_params.contains(&ident.ident)
+      || self.cddl.rules.iter().any(|r| r.name() == ident.ident)
+      || self.idents.iter().any(|i| i.ident == ident.ident)
+    {
+      return;
+    }
+
+    self.idents.push(ident);
+  }
+}
+
+impl<'a> Visitor<'a, 'a, std::fmt::Error> for UndefinedIdents<'a> {
+  fn visit_rule(&mut self, rule: &'a Rule<'a>) -> visitor::Result<std::fmt::Error> {
+    let generic_params = match rule {
+      Rule::Type { rule, .. } => rule.generic_params.as_ref(),
+      Rule::Group { rule, .. } => rule.generic_params.as_ref(),
+    };
+
+    self.generic_params = generic_params
+      .map(|gp| gp.params.iter().map(|p| p.param.ident).collect())
+      .unwrap_or_default();
+
+    visitor::walk_rule(self, rule)
+  }
+
+  fn visit_type2(&mut self, t2: &'a Type2<'a>) -> visitor::Result<std::fmt::Error> {
+    match t2 {
+      Type2::Typename { ident, .. }
+      | Type2::ChoiceFromGroup { ident, .. }
+      | Type2::Unwrap { ident, .. } => self.collect(ident),
+      _ => (),
+    }
+
+    visitor::walk_type2(self, t2)
+  }
+
+  fn visit_type_groupname_entry(
+    &mut self,
+    entry: &'a TypeGroupnameEntry<'a>,
+  ) -> visitor::Result<std::fmt::Error> {
+    self.collect(&entry.name);
+
+    visitor::walk_type_groupname_entry(self, entry)
+  }
+}
+
 /// Find non-group choice alternate rule from a given identifier
 pub fn type_rule_from_ident<'a>(cddl: &'a CDDL, ident: &Identifier) -> Option<&'a TypeRule<'a>> {
   cddl.rules.iter().find_map(|r| match r {
@@ -465,6 +1169,40 @@ pub fn generic_params_from_rule<'a>(rule: &Rule<'a>) -> Option<Vec<&'a str>> {
   }
 }
 
+/// Describe the mismatch, if any, between the number of generic parameters
+/// of a rule and the number of generic arguments it is referenced with
+pub fn generic_arity_mismatch(rule: &Rule, ga: &GenericArgs) -> Option<String> {
+  let params = generic_params_from_rule(rule).map_or(0, |params| params.len());
+  if params == ga.args.len() {
+    return None;
+  }
+
+  Some(format!(
+    "rule {} expects {} generic argument(s), got {}",
+    rule.name(),
+    params,
+    ga.args.len()
+  ))
+}
+
+/// Bounds of a range as floats if one bound is a float and the other an
+/// integer
+pub fn mixed_numeric_range_bounds(lower: &Type2, upper: &Type2) -> Option<(f64, f64)> {
+  let as_f64 = |t2: &Type2| match t2 {
+    Type2::IntValue { value, .. } => Some(*value as f64),
+    Type2::UintValue { value, .. } => Some(*value as f64),
+    Type2::FloatValue { value, .. } => Some(*value),
+    _ => None,
+  };
+
+  let is_float = |t2: &Type2| matches!(t2, Type2::FloatValue { .. });
+  if is_float(lower) == is_float(upper) {
+    return None;
+  }
+
+  Some((as_f64(lower)?, as_f64(upper)?))
+}
+
 /// Find all type choice alternate rules from a given identifier
 pub fn type_choice_alternates_from_ident<'a>(
   cddl: &'a CDDL,
@@ -482,6 +1220,34 @@ pub fn type_choice_alternates_from_ident<'a>(
     .collect::<Vec<_>>()
 }
 
+/// Combine the type choices of a socket or type rule extended with `/=`
+/// alternates, i.e. those of its base definition, if any, followed by those of
+/// all its alternates. Returns `None` if there are no alternates or the base
+/// definition isn't a non-generic type rule
+pub fn extended_type_choices<'a>(
+  cddl: &'a CDDL,
+  ident: &Identifier,
+  alternates: &[&'a Type<'a>],
+) -> Option<Vec<TypeChoice<'a>>> {
+  if alternates.is_empty() {
+    return None;
+  }
+
+  let base = match rule_from_ident(cddl, ident) {
+    Some(Rule::Type { rule, .. }) if rule.generic_params.is_none() => Some(&rule.value),
+    None => None,
+    _ => return None,
+  };
+
+  Some(
+    base
+      .into_iter()
+      .chain(alternates.iter().copied())
+      .flat_map(|t| t.type_choices.iter().cloned())
+      .collect(),
+  )
+}
+
 /// Find all group choice alternate rules from a given identifier
 pub fn group_choice_alternates_from_ident<'a>(
   cddl: &'a CDDL,
@@ -551,6 +1317,24 @@ pub fn is_ident_null_data_type(cddl: &CDDL, ident: &Identifier) -> bool {
   })
 }
 
+/// Is the given identifier associated with the undefined data type
+pub fn is_ident_undefined_data_type(cddl: &CDDL, ident: &Identifier) -> bool {
+  if let Token::UNDEFINED = lookup_ident(ident.ident) {
+    return true;
+  }
+
+  cddl.rules.iter().any(|r| match r {
+    Rule::Type { rule, .. } if &rule.name == ident => rule.value.type_choices.iter().any(|tc| {
+      if let Type2::Typename { ident, .. } = &tc.type1.type2 {
+        is_ident_undefined_data_type(cddl, ident)
+      } else {
+        false
+      }
+    }),
+    _ => false,
+  })
+}
+
 /// Is the given identifier associated with a boolean data type
 pub fn is_ident_bool_data_type(cddl: &CDDL, ident: &Identifier) -> bool {
   if let Token::BOOL = lookup_ident(ident.ident) {
@@ -777,6 +1561,53 @@ pub fn is_ident_float_data_type(cddl: &CDDL, ident: &Identifier) -> bool {
   })
 }
 
+/// Whether the given number of milliseconds since the UNIX epoch is
+/// representable as a date
+#[cfg(feature = "tdate")]
+pub fn is_valid_unix_timestamp_millis(millis: i64) -> bool {
+  use chrono::{LocalResult, TimeZone, Utc};
+
+  !matches!(Utc.timestamp_millis_opt(millis), LocalResult::None)
+}
+
+/// Without the `tdate` feature, UNIX timestamps aren't range checked
+#[cfg(not(feature = "tdate"))]
+pub fn is_valid_unix_timestamp_millis(_millis: i64) -> bool {
+  true
+}
+
+/// Whether the given float falls within the range of the float data type
+/// identified by `ident`. Values are rounded to the nearest representable
+/// float of that width, so only finite magnitudes that would overflow are
+/// rejected
+pub fn is_float_in_range(ident: &Identifier, f: f64) -> bool {
+  let fits_f16 = || !f.is_finite() || half::f16::from_f64(f).is_finite();
+  let fits_f32 = || !f.is_finite() || (f as f32).is_finite();
+
+  match lookup_ident(ident.ident) {
+    Token::FLOAT16 => fits_f16(),
+    Token::FLOAT1632 | Token::FLOAT32 => fits_f32(),
+    // every value decoded as f64 is within the range of 64 bits
+    _ => true,
+  }
+}
+
+/// Whether the given float is exactly representable by the float data type
+/// identified by `ident`, i.e. it round-trips through a float of that width
+/// unchanged
+pub fn is_float_representable(ident: &Identifier, f: f64) -> bool {
+  let fits_f16 = || f.is_nan() || half::f16::from_f64(f).to_f64() == f;
+  let fits_f32 = || f.is_nan() || (f as f32) as f64 == f;
+
+  match lookup_ident(ident.ident) {
+    Token::FLOAT16 => fits_f16(),
+    Token::FLOAT1632 => fits_f16() || fits_f32(),
+    Token::FLOAT32 => fits_f32(),
+    // every value decoded as f64 is representable in 64 bits
+    _ => true,
+  }
+}
+
 /// Is the given identifier associated with a string data type
 pub fn is_ident_string_data_type(cddl: &CDDL, ident: &Identifier) -> bool {
   if let Token::TEXT | Token::TSTR = lookup_ident(ident.ident) {
@@ -841,6 +1672,9 @@ pub fn validate_array_occurrence<'de, T: Deserialize<'de>>(
   entry_counts: Option<&[EntryCount]>,
   values: &[T],
 ) -> std::result::Result<(bool, bool), Vec<String>> {
+  let occurrence = occurrence.and_then(|o| o.normalize());
+  let occurrence = occurrence.as_ref();
+
   let mut iter_items = false;
   #[cfg(feature = "ast-span")]
   let allow_empty_array = matches!(occurrence, Some(Occur::Optional { .. }));
@@ -873,10 +1707,11 @@ pub fn validate_array_occurrence<'de, T: Deserialize<'de>>(
     Some(Occur::Exact { lower, upper, .. }) => {
       if let Some(lower) = lower {
         if let Some(upper) = upper {
-          if lower == upper && values.len() != *lower {
-            errors.push(format!("array must have exactly {} items", lower));
-          }
-          if values.len() < *lower || values.len() > *upper {
+          if lower == upper {
+            if values.len() != *lower {
+              errors.push(format!("array must have exactly {} items", lower));
+            }
+          } else if values.len() < *lower || values.len() > *upper {
             errors.push(format!(
               "array must have between {} and {} items",
               lower, upper
@@ -918,7 +1753,9 @@ pub fn validate_array_occurrence<'de, T: Deserialize<'de>>(
     }
   }
 
-  if !iter_items && !allow_empty_array {
+  // The entry counts are only checked once the occurrence is satisfied, so
+  // that e.g. an empty array of `[+ item]` is only reported once
+  if !iter_items && !allow_empty_array && errors.is_empty() {
     if let Some(entry_counts) = entry_counts {
       let len = values.len();
       if !validate_entry_count(entry_counts, len) {
@@ -1094,12 +1931,20 @@ pub fn format_regex(input: &str) -> Option<String> {
   Some(formatted_regex)
 }
 
+/// Anchor a regex so that it has to match the entire text, as `.regexp` and
+/// `.pcre` patterns do per <https://datatracker.ietf.org/doc/html/rfc8610#section-3.8.3>.
+/// The pattern is wrapped in a group, so anchors it already contains, e.g. in
+/// `^a|b`, don't keep alternatives from having to match the entire text
+pub fn anchor_regex(input: &str) -> String {
+  format!("^(?:{})$", input)
+}
+
 #[allow(missing_docs)]
 #[derive(Debug)]
 pub enum ArrayItemToken<'a> {
   Value(&'a Value<'a>),
   Range(&'a Type2<'a>, &'a Type2<'a>, bool),
-  Group(&'a Group<'a>),
+  Map(&'a Type2<'a>),
   Identifier(&'a Identifier<'a>),
   TaggedData(&'a Type2<'a>),
 }
@@ -1128,11 +1973,11 @@ impl ArrayItemToken<'_> {
           )
         }
       }
-      ArrayItemToken::Group(group) => {
+      ArrayItemToken::Map(map) => {
         if let Some(idx) = idx {
-          format!("expected map object {} at index {}", group, idx)
+          format!("expected map object {} at index {}", map, idx)
         } else {
-          format!("expected map object {}", group)
+          format!("expected map object {}", map)
         }
       }
       ArrayItemToken::Identifier(ident) => {
@@ -1180,4 +2025,406 @@ mod tests {
       .iter()
       .all(|doc| cddl_schema.validate_json(doc.as_bytes(), None).is_ok());
   }
+
+  #[test]
+  fn validate_json_values_against_parsed_cddl() {
+    let cddl_schema = cddl_from_str(
+      r#"
+  foo = {
+    bar: tstr
+  }
+  "#,
+      true,
+    )
+    .unwrap();
+
+    let valid = [
+      serde_json::json!({ "bar": "foo" }),
+      serde_json::json!({ "bar": "foo2" }),
+    ];
+    let invalid = [serde_json::json!({ "bar": 1 }), serde_json::json!([])];
+
+    assert!(valid
+      .iter()
+      .all(|doc| validate_json_value(&cddl_schema, doc, None).is_ok()));
+    assert!(invalid
+      .iter()
+      .all(|doc| validate_json_value(&cddl_schema, doc, None).is_err()));
+  }
+
+  #[test]
+  fn validate_cbor_values_against_parsed_cddl() {
+    use ciborium::value::Value;
+
+    let cddl_schema = cddl_from_str(
+      r#"
+  foo = {
+    bar: tstr
+  }
+  "#,
+      true,
+    )
+    .unwrap();
+
+    let valid = [
+      Value::Map(vec![(Value::Text("bar".into()), Value::Text("foo".into()))]),
+      Value::Map(vec![(
+        Value::Text("bar".into()),
+        Value::Text("foo2".into()),
+      )]),
+    ];
+    let invalid = [
+      Value::Map(vec![(Value::Text("bar".into()), Value::Integer(1.into()))]),
+      Value::Array(vec![]),
+    ];
+
+    assert!(valid
+      .iter()
+      .all(|doc| validate_cbor_value(&cddl_schema, doc, None).is_ok()));
+    assert!(invalid
+      .iter()
+      .all(|doc| validate_cbor_value(&cddl_schema, doc, None).is_err()));
+  }
+
+  #[test]
+  fn validate_many_values_against_cached_schema() {
+    use ciborium::value::Value;
+
+    let schema = SchemaValidator::new("point = { x: int, y: int }").unwrap();
+
+    for i in 0..1000 {
+      let value = serde_json::json!({ "x": i, "y": -i });
+      assert!(schema.validate_json(&value, None).is_ok());
+
+      let value = Value::Map(vec![
+        (Value::Text("x".into()), Value::Integer(i.into())),
+        (Value::Text("y".into()), Value::Integer((-i).into())),
+      ]);
+      let mut bytes = Vec::new();
+      ciborium::ser::into_writer(&value, &mut bytes).unwrap();
+      assert!(schema.validate_cbor(&bytes, None).is_ok());
+    }
+
+    assert!(schema
+      .validate_json(&serde_json::json!({ "x": "a", "y": 1 }), None)
+      .is_err());
+  }
+
+  #[test]
+  fn validate_collecting_issues() {
+    let schema = SchemaValidator::new("person = { name: tstr, age: uint }").unwrap();
+
+    assert!(schema
+      .validate_collecting(&serde_json::json!({ "name": "x", "age": 1 }), None)
+      .is_empty());
+
+    let issues = schema.validate_collecting(&serde_json::json!({ "name": 1, "age": "old" }), None);
+    assert_eq!(
+      issues,
+      [
+        json::ValidationIssue {
+          location: "/name".to_string(),
+          expected: "expected type tstr, got 1".to_string(),
+          actual: serde_json::json!(1),
+        },
+        json::ValidationIssue {
+          location: "/age".to_string(),
+          expected: "expected type uint, got \"old\"".to_string(),
+          actual: serde_json::json!("old"),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn validate_against_named_rules() {
+    let schema = SchemaValidator::new(
+      r#"
+  request = { id: uint, method: tstr }
+  response = { id: uint, ? result: any }
+  "#,
+    )
+    .unwrap();
+
+    let document = serde_json::json!({ "id": 1, "result": true });
+    assert!(schema.validate_named("request", &document, None).is_err());
+    assert!(schema.validate_named("response", &document, None).is_ok());
+    assert!(matches!(
+      schema.validate_named("notification", &document, None),
+      Err(json::Error::RuleNotFound(_))
+    ));
+  }
+
+  #[test]
+  fn validate_json_with_duplicate_keys() {
+    let cddl = "foo = { a: int }";
+    let json = r#"{ "a": 1, "a": 2 }"#;
+
+    let options = json::ValidationOptions {
+      reject_duplicate_keys: true,
+      ..Default::default()
+    };
+
+    assert!(validate_json_from_str(cddl, json, None).is_ok());
+    assert!(matches!(
+      validate_json_from_str_with_options(cddl, json, None, options),
+      Err(json::Error::DuplicateKey(_))
+    ));
+    assert!(validate_json_from_str_with_options(cddl, r#"{ "a": 1 }"#, None, options).is_ok());
+    assert!(matches!(
+      validate_json_from_str_with_options(cddl, r#"{ "a": 1 "#, None, options),
+      Err(json::Error::JSONParsing(_))
+    ));
+  }
+
+  #[cfg(feature = "additional-controls")]
+  #[test]
+  fn validate_json_with_unknown_control_operator() {
+    assert!(matches!(
+      validate_json_from_str("foo = tstr .nonsense 3", r#""bar""#, None),
+      Err(json::Error::CDDLParsing(_))
+    ));
+  }
+
+  #[cfg(feature = "additional-controls")]
+  #[test]
+  fn validate_jsonl_lines() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("entry = { level: tstr, code: uint }", true)?;
+    let jsonl = "{ \"level\": \"info\", \"code\": 1 }\n\
+                 { \"level\": \"warn\", \"code\": -1 }\n\
+                 \n\
+                 { \"level\": \"error\", \"code\": 3 }\n";
+
+    let results = validate_jsonl(&cddl, jsonl.as_bytes(), None).collect::<Vec<_>>();
+
+    assert_eq!(results.len(), 3);
+    assert!(matches!(results[0], (1, Ok(()))));
+    assert!(matches!(results[1], (2, Err(json::Error::Validation(_)))));
+    assert!(matches!(results[2], (4, Ok(()))));
+
+    Ok(())
+  }
+
+  #[cfg(feature = "additional-controls")]
+  #[test]
+  fn validate_value_against_programmatic_type(
+  ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "ast-span")]
+    use crate::ast::Span;
+    use crate::ast::Type1;
+
+    let cddl = cddl_from_str("person = { name: tstr }", true)?;
+
+    // tstr / person
+    let type_choice = |ident: &'static str| TypeChoice {
+      type1: Type1 {
+        type2: Type2::Typename {
+          ident: Identifier::from(ident),
+          generic_args: None,
+          #[cfg(feature = "ast-span")]
+          span: Span::default(),
+        },
+        operator: None,
+        #[cfg(feature = "ast-span")]
+        span: Span::default(),
+        #[cfg(feature = "ast-comments")]
+        comments_after_type: None,
+      },
+      #[cfg(feature = "ast-comments")]
+      comments_before_type: None,
+      #[cfg(feature = "ast-comments")]
+      comments_after_type: None,
+    };
+    let t = Type {
+      type_choices: vec![type_choice("tstr"), type_choice("person")],
+      #[cfg(feature = "ast-span")]
+      span: Span::default(),
+    };
+
+    for value in [serde_json::json!("x"), serde_json::json!({ "name": "x" })] {
+      cddl.validate_value_against_type(&t, &value, None)?;
+    }
+
+    assert!(cddl
+      .validate_value_against_type(&t, &serde_json::json!({ "name": 1 }), None)
+      .is_err());
+    assert!(cddl
+      .validate_value_against_type(&t, &serde_json::json!(1), None)
+      .is_err());
+
+    Ok(())
+  }
+
+  #[cfg(feature = "additional-controls")]
+  #[test]
+  fn validate_json_read_from_reader() {
+    let cddl = "entry = { level: tstr, code: uint }";
+
+    let reader = std::io::Cursor::new(br#"{ "level": "info", "code": 1 }"#);
+    assert!(validate_json_from_reader(cddl, reader, None).is_ok());
+
+    let reader = std::io::Cursor::new(br#"{ "level": "info", "code": -1 }"#);
+    assert!(matches!(
+      validate_json_from_reader(cddl, reader, None),
+      Err(json::Error::Validation(_))
+    ));
+
+    let reader = std::io::Cursor::new(br#"{ "level": "info", "#);
+    assert!(matches!(
+      validate_json_from_reader(cddl, reader, None),
+      Err(json::Error::JSONParsing(_))
+    ));
+  }
+
+  #[test]
+  fn is_valid_json_and_cbor() {
+    let cddl = "entry = { level: tstr, code: uint }";
+
+    assert!(is_valid_json(cddl, r#"{ "level": "info", "code": 1 }"#));
+    assert!(!is_valid_json(cddl, r#"{ "level": "info", "code": -1 }"#));
+    assert!(!is_valid_json(cddl, r#"{ "level": "info", "#));
+    assert!(!is_valid_json(
+      "entry = {",
+      r#"{ "level": "info", "code": 1 }"#
+    ));
+
+    // { "level": "info", "code": 1 }
+    let cbor = b"\xa2\x65level\x64info\x64code\x01";
+    assert!(is_valid_cbor(cddl, cbor));
+    // { "level": "info", "code": -1 }
+    assert!(!is_valid_cbor(cddl, b"\xa2\x65level\x64info\x64code\x20"));
+    assert!(!is_valid_cbor(cddl, &cbor[..4]));
+    assert!(!is_valid_cbor("entry = {", cbor));
+  }
+
+  #[cfg(feature = "additional-controls")]
+  #[test]
+  fn validate_json_reporting_extra_keys() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str("entry = { name: tstr, * tstr => any }", true)?;
+    let json = serde_json::json!({ "name": "bob", "nickname": "bobby" });
+
+    let (result, extras) = validate_json_reporting_extras(&cddl, &json, None);
+
+    assert!(result.is_ok());
+    assert_eq!(extras, ["nickname"]);
+
+    let cddl = cddl_from_str("entry = { name: tstr }", true)?;
+    let json = serde_json::json!({ "name": "bob" });
+
+    let (result, extras) = validate_json_reporting_extras(&cddl, &json, None);
+
+    assert!(result.is_ok());
+    assert!(extras.is_empty());
+
+    Ok(())
+  }
+
+  #[cfg(feature = "additional-controls")]
+  #[test]
+  fn validate_json_reporting_extra_keys_of_failed_choices(
+  ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let json = serde_json::json!({ "y": 1, "x": "s" });
+
+    for cddl in [
+      "entry = { x: int, * tstr => any } / { y: int, * tstr => any }",
+      "entry = { (x: int // y: int), * tstr => any }",
+    ] {
+      let cddl = cddl_from_str(cddl, true)?;
+
+      let (result, extras) = validate_json_reporting_extras(&cddl, &json, None);
+
+      assert!(result.is_ok());
+      assert_eq!(extras, ["x"]);
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn undefined_idents_in_cddl() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let cddl = cddl_from_str(
+      "a = { b: &colors, c: &shapes, d: $ext, e: pair<int> }\n\
+       colors = ( red: 1, green: 2 )\n\
+       pair<T> = [T, T]",
+      true,
+    )?;
+
+    let idents = undefined_idents(&cddl);
+
+    assert_eq!(idents.len(), 1);
+    assert_eq!(idents[0].ident, "shapes");
+
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(all(feature = "json", feature = "cbor"))]
+  fn compare_target_values_across_formats() {
+    #[cfg(feature = "ast-span")]
+    use crate::ast::Span;
+    use ciborium::value::Value;
+    use value::{compare_numeric, range_contains};
+
+    let range = (
+      Type2::IntValue {
+        value: -2,
+        #[cfg(feature = "ast-span")]
+        span: Span::default(),
+      },
+      Type2::UintValue {
+        value: 5,
+        #[cfg(feature = "ast-span")]
+        span: Span::default(),
+      },
+    );
+
+    for (json, cbor) in [
+      (serde_json::json!(5), Value::Integer(5.into())),
+      (serde_json::json!(-3), Value::Integer((-3).into())),
+      (serde_json::json!(2.5), Value::Float(2.5)),
+      (serde_json::json!("5"), Value::Text("5".into())),
+    ] {
+      for ctrl in [
+        ControlOperator::EQ,
+        ControlOperator::NE,
+        ControlOperator::LT,
+        ControlOperator::GE,
+      ] {
+        for literal in [crate::token::Value::INT(-3), crate::token::Value::UINT(5)] {
+          assert_eq!(
+            compare_numeric(&json, ctrl, &literal),
+            compare_numeric(&cbor, ctrl, &literal)
+          );
+        }
+      }
+
+      for is_inclusive in [true, false] {
+        assert_eq!(
+          range_contains(&json, &range.0, &range.1, is_inclusive),
+          range_contains(&cbor, &range.0, &range.1, is_inclusive)
+        );
+      }
+    }
+
+    assert_eq!(
+      compare_numeric(
+        &serde_json::json!(5),
+        ControlOperator::LT,
+        &crate::token::Value::INT(6)
+      ),
+      Some(true)
+    );
+    assert_eq!(
+      range_contains(&Value::Integer(5.into()), &range.0, &range.1, false),
+      Some(false)
+    );
+    assert_eq!(
+      compare_numeric(
+        &Value::Float(2.5),
+        ControlOperator::EQ,
+        &crate::token::Value::FLOAT(2.5)
+      ),
+      Some(true)
+    );
+  }
 }
@@ -0,0 +1,199 @@
+#![cfg(any(feature = "json", feature = "cbor"))]
+#![cfg(not(feature = "lsp"))]
+
+use crate::{ast::Type2, token};
+
+use std::cmp::Ordering;
+
+use super::mixed_numeric_range_bounds;
+
+/// Operations on a document value that validation logic needs independent of
+/// the document's format. Control operators implemented against this trait
+/// behave the same for every format
+pub trait TargetValue: Sized {
+  /// Text string value
+  fn as_str(&self) -> Option<&str>;
+
+  /// Byte string value. Formats without byte strings return `None`
+  fn as_bytes(&self) -> Option<&[u8]>;
+
+  /// Integer value
+  fn as_i128(&self) -> Option<i128>;
+
+  /// Float value. Formats that don't distinguish integers from floats also
+  /// return integers as floats
+  fn as_f64(&self) -> Option<f64>;
+
+  /// Boolean value
+  fn as_bool(&self) -> Option<bool>;
+
+  /// Whether the value is null
+  fn is_null(&self) -> bool;
+
+  /// Items of an array value
+  fn as_array(&self) -> Option<&[Self]>;
+
+  /// Value of a map or object entry with the given text key
+  fn as_object_get(&self, key: &str) -> Option<&Self>;
+}
+
+#[cfg(feature = "json")]
+impl TargetValue for serde_json::Value {
+  fn as_str(&self) -> Option<&str> {
+    serde_json::Value::as_str(self)
+  }
+
+  fn as_bytes(&self) -> Option<&[u8]> {
+    None
+  }
+
+  fn as_i128(&self) -> Option<i128> {
+    match self {
+      serde_json::Value::Number(n) => n
+        .as_i64()
+        .map(i128::from)
+        .or_else(|| n.as_u64().map(i128::from)),
+      _ => None,
+    }
+  }
+
+  fn as_f64(&self) -> Option<f64> {
+    serde_json::Value::as_f64(self)
+  }
+
+  fn as_bool(&self) -> Option<bool> {
+    serde_json::Value::as_bool(self)
+  }
+
+  fn is_null(&self) -> bool {
+    serde_json::Value::is_null(self)
+  }
+
+  fn as_array(&self) -> Option<&[Self]> {
+    serde_json::Value::as_array(self).map(Vec::as_slice)
+  }
+
+  fn as_object_get(&self, key: &str) -> Option<&Self> {
+    serde_json::Value::as_object(self)?.get(key)
+  }
+}
+
+#[cfg(feature = "cbor")]
+impl TargetValue for ciborium::value::Value {
+  fn as_str(&self) -> Option<&str> {
+    self.as_text()
+  }
+
+  fn as_bytes(&self) -> Option<&[u8]> {
+    ciborium::value::Value::as_bytes(self).map(Vec::as_slice)
+  }
+
+  fn as_i128(&self) -> Option<i128> {
+    self.as_integer().map(i128::from)
+  }
+
+  fn as_f64(&self) -> Option<f64> {
+    self.as_float()
+  }
+
+  fn as_bool(&self) -> Option<bool> {
+    ciborium::value::Value::as_bool(self)
+  }
+
+  fn is_null(&self) -> bool {
+    ciborium::value::Value::is_null(self)
+  }
+
+  fn as_array(&self) -> Option<&[Self]> {
+    ciborium::value::Value::as_array(self).map(Vec::as_slice)
+  }
+
+  fn as_object_get(&self, key: &str) -> Option<&Self> {
+    self
+      .as_map()?
+      .iter()
+      .find(|(k, _)| k.as_text() == Some(key))
+      .map(|(_, v)| v)
+  }
+}
+
+/// Whether the value compares to the numeric literal per the `.eq`, `.ne`,
+/// `.lt`, `.le`, `.gt` or `.ge` control operator. Returns `None` if the
+/// value isn't a number comparable with the literal or the control operator
+/// isn't a comparison
+pub fn compare_numeric<V: TargetValue>(
+  target: &V,
+  ctrl: token::ControlOperator,
+  literal: &token::Value,
+) -> Option<bool> {
+  match literal {
+    token::Value::INT(v) => compare_ordering(ctrl, target.as_i128()?.cmp(&(*v as i128))),
+    token::Value::UINT(v) => compare_ordering(ctrl, target.as_i128()?.cmp(&(*v as i128))),
+    token::Value::FLOAT(v) => {
+      let f = target.as_f64()?;
+
+      match ctrl {
+        token::ControlOperator::EQ => Some((f - *v).abs() < f64::EPSILON),
+        token::ControlOperator::NE => Some((f - *v).abs() > f64::EPSILON),
+        _ => compare_ordering(ctrl, f.partial_cmp(v)?),
+      }
+    }
+    _ => None,
+  }
+}
+
+/// Whether the ordering of a value relative to a literal satisfies the `.eq`,
+/// `.ne`, `.lt`, `.le`, `.gt` or `.ge` control operator
+pub fn compare_ordering(ctrl: token::ControlOperator, ordering: Ordering) -> Option<bool> {
+  match ctrl {
+    token::ControlOperator::EQ => Some(ordering.is_eq()),
+    token::ControlOperator::NE => Some(ordering.is_ne()),
+    token::ControlOperator::LT => Some(ordering.is_lt()),
+    token::ControlOperator::LE => Some(ordering.is_le()),
+    token::ControlOperator::GT => Some(ordering.is_gt()),
+    token::ControlOperator::GE => Some(ordering.is_ge()),
+    _ => None,
+  }
+}
+
+/// Whether the value lies within the range bounded by the numeric literals
+/// lower and upper. Ranges mixing integer and float bounds are compared as
+/// floats. Returns `None` if the bounds aren't numeric literals
+pub fn range_contains<V: TargetValue>(
+  target: &V,
+  lower: &Type2,
+  upper: &Type2,
+  is_inclusive: bool,
+) -> Option<bool> {
+  let contains = |lower: Option<Ordering>, upper: Option<Ordering>| match (lower, upper) {
+    (Some(l), Some(u)) => l.is_ge() && (u.is_lt() || (is_inclusive && u.is_eq())),
+    _ => false,
+  };
+
+  let integer_bound = |t2: &Type2| match t2 {
+    Type2::IntValue { value, .. } => Some(*value as i128),
+    Type2::UintValue { value, .. } => Some(*value as i128),
+    _ => None,
+  };
+
+  if let (Some(l), Some(u)) = (integer_bound(lower), integer_bound(upper)) {
+    return Some(matches!(
+      target.as_i128(),
+      Some(i) if contains(Some(i.cmp(&l)), Some(i.cmp(&u)))
+    ));
+  }
+
+  if let (Type2::FloatValue { value: l, .. }, Type2::FloatValue { value: u, .. }) = (lower, upper) {
+    return Some(matches!(
+      target.as_f64(),
+      Some(f) if contains(f.partial_cmp(l), f.partial_cmp(u))
+    ));
+  }
+
+  let (l, u) = mixed_numeric_range_bounds(lower, upper)?;
+  let value = target
+    .as_f64()
+    .or_else(|| target.as_i128().map(|i| i as f64));
+
+  Some(matches!(value, Some(f) if contains(f.partial_cmp(&l), f.partial_cmp(&u))))
+}
@@ -29,6 +29,12 @@ pub mod cbor {
     pub const ARRAY_123:    &[u8] = b"\x83\x01\x02\x03";  // [1,2,3]
     pub const ARRAY_1_23_45:&[u8] = b"\x83\x01\x82\x02\x03\x82\x04\x05";  // [1, [2, 3], [4, 5]]
 
+    pub const ARRAY_INDEF_EMPTY:  &[u8] = b"\x9f\xff";                  // [_ ]
+    pub const ARRAY_INDEF_123:    &[u8] = b"\x9f\x01\x02\x03\xff";      // [_ 1, 2, 3]
+
+    pub const MAP_A1_B2:       &[u8] = b"\xa2\x61\x61\x01\x61\x62\x02";      // {"a": 1, "b": 2}
+    pub const MAP_INDEF_A1_B2: &[u8] = b"\xbf\x61\x61\x01\x61\x62\x02\xff";  // {_ "a": 1, "b": 2}
+
     pub const TEXT_EMPTY:   &[u8] = b"\x60";
     pub const TEXT_IETF:    &[u8] = b"\x64\x49\x45\x54\x46";
     pub const TEXT_CJK:     &[u8] = b"\x63\xe6\xb0\xb4";    // "水
@@ -46,6 +52,21 @@ fn validate_cbor_bool() {
   validate_cbor_from_slice(cddl_input, cbor::NULL, None).unwrap_err();
 }
 
+#[test]
+fn validate_cbor_undefined() {
+  let cddl_input = r#"thing = undefined"#;
+  validate_cbor_from_slice(cddl_input, cbor::UNDEFINED, None).unwrap();
+  validate_cbor_from_slice(cddl_input, cbor::BOOL_FALSE, None).unwrap_err();
+  validate_cbor_from_slice(cddl_input, cbor::INT_0, None).unwrap_err();
+  validate_cbor_from_slice(cddl_input, cbor::TEXT_EMPTY, None).unwrap_err();
+  // undefined decodes the same as null, so null can't be rejected
+  validate_cbor_from_slice(cddl_input, cbor::NULL, None).unwrap();
+
+  let cddl_input = r#"thing = [* undefined]"#;
+  validate_cbor_from_slice(cddl_input, b"\x82\xF7\xF7", None).unwrap();
+  validate_cbor_from_slice(cddl_input, b"\x82\xF7\x00", None).unwrap_err();
+}
+
 #[test]
 fn validate_cbor_float() {
   let cddl_input = r#"thing = 0.0"#;
@@ -96,6 +117,13 @@ fn validate_cbor_integer() {
   validate_cbor_from_slice(cddl_input, cbor::NINT_1000, None).unwrap_err();
 }
 
+#[test]
+fn validate_cbor_cyclic_named_range_bounds() {
+  let cddl_input = "thing = a .. 23\na = 1 / b\nb = 2 / a";
+  validate_cbor_from_slice(cddl_input, cbor::INT_23, None).unwrap();
+  validate_cbor_from_slice(cddl_input, cbor::INT_0, None).unwrap_err();
+}
+
 #[test]
 fn validate_cbor_textstring() {
   let cddl_input = r#"thing = tstr"#;
@@ -126,6 +154,121 @@ fn validate_cbor_array() {
   validate_cbor_from_slice(cddl_input, cbor::ARRAY_123, None).unwrap();
 }
 
+#[test]
+fn validate_cbor_nested_array() {
+  let cddl_input = r#"thing = [int, [int, int], [int, int]]"#;
+  validate_cbor_from_slice(cddl_input, cbor::ARRAY_1_23_45, None).unwrap();
+  validate_cbor_from_slice(cddl_input, cbor::ARRAY_123, None).unwrap_err();
+
+  let cddl_input = r#"thing = [[int, int], tstr]"#;
+  // [[1, 2], "x"]
+  validate_cbor_from_slice(cddl_input, b"\x82\x82\x01\x02\x61x", None).unwrap();
+  // [[1], "x"]
+  validate_cbor_from_slice(cddl_input, b"\x82\x81\x01\x61x", None).unwrap_err();
+}
+
+#[test]
+fn validate_cbor_array_of_maps() {
+  let cddl_input = r#"thing = [* { a: int }]"#;
+  // [{"a": 1}, {"a": 2}]
+  validate_cbor_from_slice(cddl_input, b"\x82\xa1\x61a\x01\xa1\x61a\x02", None).unwrap();
+  // [{"a": 1}, {"a": "x"}]
+  validate_cbor_from_slice(cddl_input, b"\x82\xa1\x61a\x01\xa1\x61a\x61x", None).unwrap_err();
+  // [{"a": 1, "b": 2}]
+  validate_cbor_from_slice(cddl_input, b"\x81\xa2\x61a\x01\x61b\x02", None).unwrap_err();
+  // [{"a": 1}, 3]
+  validate_cbor_from_slice(cddl_input, b"\x82\xa1\x61a\x01\x03", None).unwrap_err();
+}
+
+#[cfg(feature = "extensions")]
+#[test]
+fn validate_cbor_join_control() {
+  let cddl_input = r#"path = ["usr", "local", "bin"] .join "/""#;
+  // "usr/local/bin"
+  validate_cbor_from_slice(cddl_input, b"\x6dusr/local/bin", None).unwrap();
+  // "usr-local-bin"
+  validate_cbor_from_slice(cddl_input, b"\x6dusr-local-bin", None).unwrap_err();
+  // ["usr", "local", "bin"]
+  validate_cbor_from_slice(cddl_input, b"\x83\x63usr\x65local\x63bin", None).unwrap_err();
+}
+
+#[test]
+fn validate_cbor_array_exact_occurrence() {
+  let cddl_input = r#"thing = [2*3 int, tstr]"#;
+  // [1, 2, "x"]
+  validate_cbor_from_slice(cddl_input, b"\x83\x01\x02\x61x", None).unwrap();
+  // [1, 2, 3, "x"]
+  validate_cbor_from_slice(cddl_input, b"\x84\x01\x02\x03\x61x", None).unwrap();
+  // [1, "x"]
+  validate_cbor_from_slice(cddl_input, b"\x82\x01\x61x", None).unwrap_err();
+  // [1, 2, 3, 4, "x"]
+  validate_cbor_from_slice(cddl_input, b"\x85\x01\x02\x03\x04\x61x", None).unwrap_err();
+  // [1, 2]
+  validate_cbor_from_slice(cddl_input, b"\x82\x01\x02", None).unwrap_err();
+}
+
+#[test]
+fn validate_cbor_indefinite_length() {
+  // Indefinite-length arrays and maps validate exactly like their
+  // definite-length encodings
+  let empty = [cbor::ARRAY_EMPTY, cbor::ARRAY_INDEF_EMPTY];
+  let array_123 = [cbor::ARRAY_123, cbor::ARRAY_INDEF_123];
+  let map = [cbor::MAP_A1_B2, cbor::MAP_INDEF_A1_B2];
+
+  for (cddl_input, encodings, is_valid) in [
+    ("thing = []", empty, true),
+    ("thing = [* int]", empty, true),
+    ("thing = [+ int]", empty, false),
+    ("thing = [1, 2, 3]", array_123, true),
+    ("thing = [+ int]", array_123, true),
+    ("thing = [3*3 int]", array_123, true),
+    ("thing = [2*2 int]", array_123, false),
+    ("thing = [4* int]", array_123, false),
+    ("thing = []", array_123, false),
+    ("thing = {a: int, b: int}", map, true),
+    ("thing = {* tstr => int}", map, true),
+    ("thing = {a: int}", map, false),
+    ("thing = {a: int, b: tstr}", map, false),
+  ] {
+    for bytes in encodings {
+      assert_eq!(
+        validate_cbor_from_slice(cddl_input, bytes, None).is_ok(),
+        is_valid,
+        "{} against {:02x?}",
+        cddl_input,
+        bytes
+      );
+    }
+  }
+}
+
+#[test]
+fn validate_cbor_embedded() {
+  // h'83010203', the byte string wrapping [1, 2, 3]
+  let embedded_array: &[u8] = b"\x44\x83\x01\x02\x03";
+  // h'010203', the byte string wrapping the sequence 1, 2, 3
+  let embedded_seq: &[u8] = b"\x43\x01\x02\x03";
+  // h'ff', a lone break code which is not well-formed CBOR
+  let not_cbor: &[u8] = b"\x41\xff";
+
+  let cddl_input = r#"thing = bstr .cbor [* int]"#;
+  validate_cbor_from_slice(cddl_input, embedded_array, None).unwrap();
+  validate_cbor_from_slice(cddl_input, embedded_seq, None).unwrap_err();
+  validate_cbor_from_slice(cddl_input, not_cbor, None).unwrap_err();
+  validate_cbor_from_slice(cddl_input, cbor::ARRAY_123, None).unwrap_err();
+
+  let cddl_input = r#"thing = bstr .cbor [* tstr]"#;
+  validate_cbor_from_slice(cddl_input, embedded_array, None).unwrap_err();
+
+  let cddl_input = r#"thing = bstr .cborseq [* int]"#;
+  validate_cbor_from_slice(cddl_input, embedded_seq, None).unwrap();
+  validate_cbor_from_slice(cddl_input, cbor::BYTES_EMPTY, None).unwrap();
+  validate_cbor_from_slice(cddl_input, not_cbor, None).unwrap_err();
+
+  let cddl_input = r#"thing = bstr .cborseq [int, int]"#;
+  validate_cbor_from_slice(cddl_input, embedded_seq, None).unwrap_err();
+}
+
 // These data structures exist so that we can serialize some more complex
 // beyond the RFC examples.
 #[derive(Debug, Serialize, Deserialize)]
@@ -151,8 +294,12 @@ struct KitchenSink(String, u32, f64, bool);
 
 #[test]
 fn validate_cbor_group() {
+  // A group-only document has no type rule to validate against
   let cddl_input = r#"thing = (* int)"#;
-  validate_cbor_from_slice(cddl_input, cbor::INT_0, None).unwrap();
+  assert!(matches!(
+    validate_cbor_from_slice(cddl_input, cbor::INT_0, None),
+    Err(cddl::validator::cbor::Error::NoRootRule)
+  ));
 }
 
 #[test]
@@ -220,11 +367,17 @@ fn validate_cbor_array_record() {
 
   let cddl_input = r#"thing = [a: tstr, b: uint, c: float32, d: bool]"#;
 
-  let input = KitchenSink("xyz".to_string(), 17, 9.9, false);
+  let input = KitchenSink("xyz".to_string(), 17, 9.5, false);
   let mut cbor_bytes = Vec::new();
   ciborium::ser::into_writer(&input, &mut cbor_bytes).unwrap();
   validate_cbor_from_slice(cddl_input, &cbor_bytes, None).unwrap();
 
+  // 9.9 needs double precision, so it isn't a float32
+  let input = KitchenSink("xyz".to_string(), 17, 9.9, false);
+  let mut cbor_bytes = Vec::new();
+  ciborium::ser::into_writer(&input, &mut cbor_bytes).unwrap();
+  validate_cbor_from_slice(cddl_input, &cbor_bytes, None).unwrap_err();
+
   // FIXME: there isn't any way at present to serialize a struct
   // into a CBOR array. See https://github.com/pyfisch/cbor/issues/107
   // let input = PersonStruct{name: "Bob".to_string(), age: 43};
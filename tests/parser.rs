@@ -12,6 +12,12 @@ use cddl::{
 use indoc::indoc;
 use pretty_assertions::assert_eq;
 
+/// Assert that two AST nodes are equal including their spans and comments,
+/// which are ignored by their PartialEq implementations
+fn assert_eq_with_spans<T: std::fmt::Debug>(left: &T, right: &T) {
+  assert_eq!(format!("{:#?}", left), format!("{:#?}", right));
+}
+
 #[test]
 #[allow(unused_variables)]
 fn verify_cddl() -> Result<()> {
@@ -622,7 +628,7 @@ fn verify_cddl() -> Result<()> {
           comments: None,
         };
 
-        assert_eq!(cddl, expected_output);
+        assert_eq_with_spans(&cddl, &expected_output);
         assert_eq!(cddl.to_string(), expected_output.to_string());
 
         Ok(())
@@ -632,13 +638,23 @@ fn verify_cddl() -> Result<()> {
       Err(Error::INCREMENTAL) if !p.errors.is_empty() => {
         let _ = p.report_errors(true);
 
-        Err(Error::CDDL(p.report_errors(false).unwrap().unwrap()))
+        Err(Error::CDDL {
+          msg: p.report_errors(false).unwrap().unwrap(),
+          line: 0,
+          column: 0,
+          byte_offset: 0,
+        })
       }
       #[cfg(not(feature = "std"))]
       Err(Error::INCREMENTAL) if !p.errors.is_empty() => {
         let _ = p.report_errors();
 
-        Err(Error::CDDL(p.report_errors().unwrap()))
+        Err(Error::CDDL {
+          msg: p.report_errors().unwrap(),
+          line: 0,
+          column: 0,
+          byte_offset: 0,
+        })
       }
       Err(e) => Err(e),
     },
@@ -675,3 +691,30 @@ fn cri_reference() -> std::result::Result<(), String> {
 
   Ok(())
 }
+
+#[test]
+#[cfg(not(target_arch = "wasm32"))]
+fn verify_error_position() {
+  let input = indoc!(
+    r#"
+      a = int
+      b = tstr
+      c = { d: }
+    "#
+  );
+
+  match cddl::parser::cddl_from_str_with_position(input) {
+    Err(Error::CDDL {
+      msg,
+      line,
+      column,
+      byte_offset,
+    }) => {
+      assert!(!msg.is_empty());
+      assert_eq!(line, 3);
+      assert_eq!(column, 10);
+      assert_eq!(byte_offset, input.find('}').unwrap());
+    }
+    r => panic!("expected located parsing error, got {:?}", r),
+  }
+}